@@ -0,0 +1,96 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+use combine::{
+    attempt, eof, optional,
+    parser::{
+        char::{alpha_num, char, newline},
+        repeat::take_until,
+    },
+    EasyParser, Parser,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use lustre_collector::{
+    parse_lctl_output,
+    types::{JobStatOst, JobStatsOst},
+};
+use std::fmt::Write;
+
+/// `job_stats` of a single OST with `n` jobs.
+fn job_stats(n: usize) -> String {
+    let mut x = "job_stats:\n".to_string();
+
+    for i in 0..n {
+        let _ = write!(
+            x,
+            "- job_id:          dd.{i}
+  snapshot_time:   1686153914.643122579 secs.nsecs
+  start_time:      1686153914.643119181 secs.nsecs
+  elapsed_time:    0.000003398 secs.nsecs
+  read_bytes:      {{ samples:         256, unit: bytes, min:  4194304, max:  4194304, sum:       1073741824, sumsq:   4503599627370496, hist: {{ 4M: 256 }} }}
+  write_bytes:     {{ samples:           0, unit: bytes, min:        0, max:        0, sum:                0, sumsq:                  0, hist: {{  }} }}
+  read:            {{ samples:         256, unit: usecs, min:       51, max:     2042, sum:            97281, sumsq:           62013513 }}
+  write:           {{ samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }}
+  getattr:         {{ samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }}
+  setattr:         {{ samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }}
+  punch:           {{ samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }}
+  sync:            {{ samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }}
+  destroy:         {{ samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }}
+  create:          {{ samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }}
+  statfs:          {{ samples:           1, unit: usecs, min:        1, max:        1, sum:                1, sumsq:                  1 }}
+  get_info:        {{ samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }}
+  set_info:        {{ samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }}
+  quotactl:        {{ samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }}
+  prealloc:        {{ samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }}
+"
+        );
+    }
+
+    x
+}
+
+/// Parses a `job_stats=` param the way it was done before the combine
+/// parser: find the end of the block, copy it into a `String` and
+/// deserialize that with `serde_yaml`.
+fn parse_serde_yaml(x: &str) -> Option<Vec<JobStatOst>> {
+    let (yaml, _) = take_until::<String, _, _>(char('='))
+        .skip(char('='))
+        .skip(optional(newline()))
+        .with(take_until::<String, _, _>(attempt(
+            (newline(), alpha_num()).map(drop).or(eof()),
+        )))
+        .easy_parse(x)
+        .expect("job_stats block");
+
+    serde_yaml::from_str::<JobStatsOst>(&yaml)
+        .expect("job_stats to deserialize")
+        .job_stats
+}
+
+/// Compares the combine `job_stats` parser with the previous `serde_yaml`
+/// based one on the same `lctl get_param` output.
+fn bench_job_stats(c: &mut Criterion) {
+    let mut group = c.benchmark_group("job_stats");
+
+    for n in [10, 1_000] {
+        let lctl = format!("obdfilter.fs-OST0000.job_stats={}", job_stats(n));
+
+        assert_eq!(parse_serde_yaml(&lctl).map(|x| x.len()), Some(n));
+
+        group.throughput(Throughput::Bytes(lctl.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("combine", n), &lctl, |b, x| {
+            b.iter(|| parse_lctl_output(x.as_bytes()).expect("job_stats to parse"));
+        });
+
+        group.bench_with_input(BenchmarkId::new("serde_yaml", n), &lctl, |b, x| {
+            b.iter(|| parse_serde_yaml(x));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_job_stats);
+criterion_main!(benches);
@@ -3,14 +3,14 @@
 // license that can be found in the LICENSE file.
 
 use combine::{
-    attempt,
-    error::{Format, ParseError},
-    many1, one_of,
+    attempt, eof,
+    error::{Format, ParseError, StreamError},
+    many1, one_of, optional,
     parser::{
         char::{alpha_num, digit, newline, string},
         repeat::take_until,
     },
-    stream::Stream,
+    stream::{Stream, StreamErrorFor},
     token, unexpected, unexpected_any, value, Parser,
 };
 
@@ -49,6 +49,20 @@ where
     many1(alpha_num().or(one_of("_-".chars()))).map(Target)
 }
 
+/// Parses a client device name such as `fs-OST0000-osc-ffff8dc8f8d4b000`
+/// into the target it connects to and the client instance.
+pub(crate) fn client_device<I>(obd: &'static str) -> impl Parser<I, Output = (Target, String)>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    target().and_then(move |Target(x)| {
+        x.rsplit_once(&format!("-{obd}-"))
+            .map(|(target, client)| (Target(target.to_string()), client.to_string()))
+            .ok_or_else(|| StreamErrorFor::<I>::expected_static_message("client device"))
+    })
+}
+
 /// Takes many consecutive digits and
 /// returns them as u64
 pub(crate) fn digits<I>() -> impl Parser<I, Output = u64>
@@ -102,6 +116,25 @@ where
     }))
 }
 
+/// Takes the YAML block following a `param=` line, up to the next
+/// `param.` or `param=` line.
+pub(crate) fn yaml_block<I>() -> impl Parser<I, Output = String>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        newline(),
+        take_until(attempt(
+            (newline(), target(), one_of(".=".chars()))
+                .map(drop)
+                .or(eof()),
+        )),
+    )
+        .skip(optional(newline()))
+        .map(|(_, x)| x)
+}
+
 pub(crate) fn param<I>(x: &'static str) -> impl Parser<I, Output = Param>
 where
     I: Stream<Token = char>,
@@ -143,4 +176,24 @@ mod tests {
             Ok((Param("exports".to_string()), "1.2.3.130@o2ib.stats=Y\n"))
         )
     }
+
+    #[test]
+    fn test_client_device() {
+        let result = client_device("osc").parse("fs-OST0000-osc-ffff8dc8f8d4b000.stats=");
+
+        assert_eq!(
+            result,
+            Ok((
+                (
+                    Target("fs-OST0000".to_string()),
+                    "ffff8dc8f8d4b000".to_string()
+                ),
+                ".stats="
+            ))
+        );
+
+        assert!(client_device("osc")
+            .parse("fs-MDT0000-mdc-ffff8dc8f8d4b000.stats=")
+            .is_err());
+    }
 }
@@ -4,8 +4,8 @@
 
 use crate::{
     base_parsers::{digits, string_to, till_newline, word},
-    time::time_triple,
-    types::{BrwStats, BrwStatsBucket},
+    time::{time_triple, to_timestamp},
+    types::{BrwStats, BrwStatsBucket, UnsignedLustreTimestamp},
 };
 use combine::{
     attempt, choice,
@@ -16,7 +16,7 @@ use combine::{
     token, Parser,
 };
 
-fn human_to_bytes((x, y): (u64, Option<char>)) -> u64 {
+pub(crate) fn human_to_bytes((x, y): (u64, Option<char>)) -> u64 {
     let mult = match y {
         None => 1,
         Some('K') | Some('k') => 2_u64.pow(10),
@@ -67,7 +67,7 @@ where
     })
 }
 
-fn bucket<I>() -> impl Parser<I, Output = BrwStatsBucket>
+pub(crate) fn bucket<I>() -> impl Parser<I, Output = BrwStatsBucket>
 where
     I: Stream<Token = char>,
     I::Error: ParseError<I::Token, I::Range, I::Position>,
@@ -105,12 +105,15 @@ where
         })
 }
 
-pub(crate) fn brw_stats<I>() -> impl Parser<I, Output = Vec<BrwStats>>
+/// Parses a `brw_stats` block into its `snapshot_time` and histograms.
+pub(crate) fn brw_stats<I>(
+) -> impl Parser<I, Output = (Option<UnsignedLustreTimestamp>, Vec<BrwStats>)>
 where
     I: Stream<Token = char>,
     I::Error: ParseError<I::Token, I::Range, I::Position>,
 {
-    (newline().with(time_triple()), spaces(), many1(section())).map(|(_, _, y)| y)
+    (newline().with(time_triple()), spaces(), many1(section()))
+        .map(|(time, _, xs)| (to_timestamp(&time), xs))
 }
 
 #[cfg(test)]
@@ -266,48 +269,51 @@ pages per bulk r/w     rpcs  % cum % |  rpcs        % cum %
         assert_eq!(
             result,
             Ok((
-                vec![
-                    BrwStats {
-                        name: "pages".to_string(),
-                        unit: "rpcs".to_string(),
-                        buckets: vec![],
-                    },
-                    BrwStats {
-                        name: "discont_pages".to_string(),
-                        unit: "rpcs".to_string(),
-                        buckets: vec![],
-                    },
-                    BrwStats {
-                        name: "discont_blocks".to_string(),
-                        unit: "rpcs".to_string(),
-                        buckets: vec![],
-                    },
-                    BrwStats {
-                        name: "dio_frags".to_string(),
-                        unit: "ios".to_string(),
-                        buckets: vec![],
-                    },
-                    BrwStats {
-                        name: "rpc_hist".to_string(),
-                        unit: "ios".to_string(),
-                        buckets: vec![],
-                    },
-                    BrwStats {
-                        name: "io_time".to_string(),
-                        unit: "ios".to_string(),
-                        buckets: vec![],
-                    },
-                    BrwStats {
-                        name: "disk_iosize".to_string(),
-                        unit: "ios".to_string(),
-                        buckets: vec![],
-                    },
-                    BrwStats {
-                        name: "block_maps_msec".to_string(),
-                        unit: "maps".to_string(),
-                        buckets: vec![],
-                    },
-                ],
+                (
+                    Some(UnsignedLustreTimestamp(1_534_429_278_185)),
+                    vec![
+                        BrwStats {
+                            name: "pages".to_string(),
+                            unit: "rpcs".to_string(),
+                            buckets: vec![],
+                        },
+                        BrwStats {
+                            name: "discont_pages".to_string(),
+                            unit: "rpcs".to_string(),
+                            buckets: vec![],
+                        },
+                        BrwStats {
+                            name: "discont_blocks".to_string(),
+                            unit: "rpcs".to_string(),
+                            buckets: vec![],
+                        },
+                        BrwStats {
+                            name: "dio_frags".to_string(),
+                            unit: "ios".to_string(),
+                            buckets: vec![],
+                        },
+                        BrwStats {
+                            name: "rpc_hist".to_string(),
+                            unit: "ios".to_string(),
+                            buckets: vec![],
+                        },
+                        BrwStats {
+                            name: "io_time".to_string(),
+                            unit: "ios".to_string(),
+                            buckets: vec![],
+                        },
+                        BrwStats {
+                            name: "disk_iosize".to_string(),
+                            unit: "ios".to_string(),
+                            buckets: vec![],
+                        },
+                        BrwStats {
+                            name: "block_maps_msec".to_string(),
+                            unit: "maps".to_string(),
+                            buckets: vec![],
+                        },
+                    ],
+                ),
                 ""
             ))
         );
@@ -317,7 +323,7 @@ pages per bulk r/w     rpcs  % cum % |  rpcs        % cum %
     fn test_brw_stats() {
         let x = include_str!("../fixtures/brw_stats_with_data.txt");
 
-        let result: (Vec<_>, _) = brw_stats().parse(x).unwrap();
+        let result = brw_stats().parse(x).unwrap();
 
         assert_debug_snapshot!(result);
     }
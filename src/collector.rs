@@ -0,0 +1,686 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Runs `lctl` / `lnetctl` and parses their output into [`Record`]s.
+//!
+//! How the commands are executed is abstracted by [`CommandRunner`], so collection
+//! can be wrapped (`sudo`, `nsenter`, custom binary paths), replayed from a directory
+//! of captured output, or scripted in tests.
+
+use crate::{
+    device_list_parser, jobid, mgs::mgs_fs_parser, parse_device_list_output, parse_lnetctl_output,
+    parse_lnetctl_peer_output, parse_lnetctl_route_output, parse_lnetctl_routing_output,
+    parse_lnetctl_stats, parse_mgs_fs_output, parse_recovery_status_output, recovery_status_parser,
+    stream::RecordStream, LustreCollectorError, ParseMode, Record,
+};
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::PathBuf,
+    process::{Command, Stdio},
+    str,
+    sync::Mutex,
+    thread,
+};
+use tracing::debug;
+
+pub const LCTL_DEVICE_LIST: &str = "lctl_device_list";
+pub const LCTL_GET_PARAM: &str = "lctl_get_param";
+pub const LCTL_MGS_FS: &str = "lctl_mgs_fs";
+pub const LCTL_RECOVERY_STATUS: &str = "lctl_recovery_status";
+pub const LNETCTL_NET_SHOW: &str = "lnetctl_net_show";
+pub const LNETCTL_STATS_SHOW: &str = "lnetctl_stats_show";
+pub const LNETCTL_PEER_SHOW: &str = "lnetctl_peer_show";
+pub const LNETCTL_ROUTE_SHOW: &str = "lnetctl_route_show";
+pub const LNETCTL_ROUTING_SHOW: &str = "lnetctl_routing_show";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Program {
+    Lctl,
+    Lnetctl,
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lctl => write!(f, "lctl"),
+            Self::Lnetctl => write!(f, "lnetctl"),
+        }
+    }
+}
+
+/// A single `lctl` / `lnetctl` call made during collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    /// Stable name of this call, e.g. used as the file name by [`ReplayRunner`].
+    pub name: &'static str,
+    pub program: Program,
+    pub args: Vec<String>,
+}
+
+impl Invocation {
+    fn new(name: &'static str, program: Program, args: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            name,
+            program,
+            args: args.into_iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for Invocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.program, self.args.join(" "))
+    }
+}
+
+/// Parses command output read from a [`BufRead`] into records.
+pub type ParseOutput<'a> =
+    dyn FnMut(&mut dyn BufRead) -> Result<Vec<Record>, LustreCollectorError> + 'a;
+
+/// Executes an [`Invocation`] and returns its stdout.
+pub trait CommandRunner: Send + Sync {
+    fn run(&self, x: &Invocation) -> Result<Vec<u8>, LustreCollectorError>;
+
+    /// Runs `x` and hands its output to `f` as it is produced.
+    ///
+    /// The default buffers the whole output with [`CommandRunner::run`] first.
+    fn run_streaming(
+        &self,
+        x: &Invocation,
+        f: &mut ParseOutput,
+    ) -> Result<Vec<Record>, LustreCollectorError> {
+        let output = self.run(x)?;
+
+        f(&mut output.as_slice())
+    }
+}
+
+/// Runs commands on the local host.
+#[derive(Debug, Clone, Default)]
+pub struct LocalRunner {
+    /// Command every invocation is wrapped in, e.g. `["sudo"]` or `["nsenter", "-t", "1", "-m", "--"]`.
+    pub wrapper: Vec<String>,
+    /// Path to the `lctl` binary. Looked up in `$PATH` when `None`.
+    pub lctl: Option<String>,
+    /// Path to the `lnetctl` binary. Looked up in `$PATH` when `None`.
+    pub lnetctl: Option<String>,
+}
+
+impl LocalRunner {
+    fn command(&self, x: &Invocation) -> Command {
+        let program = match x.program {
+            Program::Lctl => self.lctl.clone(),
+            Program::Lnetctl => self.lnetctl.clone(),
+        }
+        .unwrap_or_else(|| x.program.to_string());
+
+        match self.wrapper.split_first() {
+            Some((wrapper, wrapper_args)) => {
+                let mut cmd = Command::new(wrapper);
+                cmd.args(wrapper_args).arg(program).args(&x.args);
+
+                cmd
+            }
+            None => {
+                let mut cmd = Command::new(program);
+                cmd.args(&x.args);
+
+                cmd
+            }
+        }
+    }
+}
+
+impl CommandRunner for LocalRunner {
+    fn run(&self, x: &Invocation) -> Result<Vec<u8>, LustreCollectorError> {
+        let r = self.command(x).output()?;
+
+        Ok(r.stdout)
+    }
+
+    fn run_streaming(
+        &self,
+        x: &Invocation,
+        f: &mut ParseOutput,
+    ) -> Result<Vec<Record>, LustreCollectorError> {
+        let mut child = self
+            .command(x)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::other(format!("No stdout for `{x}`")))?;
+
+        let mut reader = BufReader::new(stdout);
+        let r = f(&mut reader);
+
+        // Close the pipe first, so a child still writing isn't left blocked.
+        drop(reader);
+        child.wait()?;
+
+        r
+    }
+}
+
+/// Replays output previously captured to `<dir>/<invocation name>.txt`.
+///
+/// Missing files are treated as empty output.
+#[derive(Debug, Clone)]
+pub struct ReplayRunner {
+    pub dir: PathBuf,
+}
+
+impl CommandRunner for ReplayRunner {
+    fn run(&self, x: &Invocation) -> Result<Vec<u8>, LustreCollectorError> {
+        match fs::read(self.dir.join(format!("{}.txt", x.name))) {
+            Ok(x) => Ok(x),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Returns canned output per invocation name and records every call. Intended for tests.
+#[derive(Debug, Default)]
+pub struct ScriptedRunner {
+    outputs: HashMap<&'static str, Vec<u8>>,
+    calls: Mutex<Vec<Invocation>>,
+}
+
+impl ScriptedRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_output(mut self, name: &'static str, output: impl Into<Vec<u8>>) -> Self {
+        self.outputs.insert(name, output.into());
+
+        self
+    }
+
+    /// All invocations made so far.
+    pub fn calls(&self) -> Vec<Invocation> {
+        self.calls.lock().map(|x| x.clone()).unwrap_or_default()
+    }
+}
+
+impl CommandRunner for ScriptedRunner {
+    fn run(&self, x: &Invocation) -> Result<Vec<u8>, LustreCollectorError> {
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.push(x.clone());
+        }
+
+        self.outputs.get(x.name).cloned().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No scripted output for `{x}`"),
+            )
+            .into()
+        })
+    }
+}
+
+/// Runs all lctl and lnetctl commands through `runner` and returns the combined records.
+///
+/// Only a failure of the main `lctl get_param` call is fatal; the other commands
+/// are not available on every node type and contribute no records if they fail.
+/// `mode` decides whether `lctl get_param` output that doesn't fully parse fails
+/// collection or is skipped.
+///
+/// The `lctl get_param` output is parsed with [`RecordStream`] while it is read,
+/// so the raw output is never buffered in full. The parsed records are not
+/// streamed though: all of them are returned together.
+///
+/// Job IDs in job stats are split into their parts with [`jobid::annotate`].
+pub fn collect<R>(
+    runner: &R,
+    lctl_params: &[String],
+    mode: ParseMode,
+) -> Result<Vec<Record>, LustreCollectorError>
+where
+    R: CommandRunner + ?Sized,
+{
+    debug!(lctl_params = lctl_params.join(" "));
+
+    let lctl = Invocation::new(
+        LCTL_GET_PARAM,
+        Program::Lctl,
+        ["get_param".to_string()]
+            .into_iter()
+            .chain(lctl_params.iter().cloned()),
+    );
+    let mgs_fs = Invocation::new(
+        LCTL_MGS_FS,
+        Program::Lctl,
+        ["get_param".to_string(), "-N".to_string()]
+            .into_iter()
+            .chain(mgs_fs_parser::params()),
+    );
+    let recovery_status = Invocation::new(
+        LCTL_RECOVERY_STATUS,
+        Program::Lctl,
+        ["get_param".to_string()]
+            .into_iter()
+            .chain(recovery_status_parser::params()),
+    );
+    let device_list = Invocation::new(LCTL_DEVICE_LIST, Program::Lctl, device_list_parser::args());
+    let lnetctl_stats = Invocation::new(
+        LNETCTL_STATS_SHOW,
+        Program::Lnetctl,
+        ["stats", "show"].map(String::from),
+    );
+    let lnetctl_peer = Invocation::new(
+        LNETCTL_PEER_SHOW,
+        Program::Lnetctl,
+        ["peer", "show", "-v", "3"].map(String::from),
+    );
+    let lnetctl_route = Invocation::new(
+        LNETCTL_ROUTE_SHOW,
+        Program::Lnetctl,
+        ["route", "show", "-v"].map(String::from),
+    );
+    let lnetctl_routing = Invocation::new(
+        LNETCTL_ROUTING_SHOW,
+        Program::Lnetctl,
+        ["routing", "show"].map(String::from),
+    );
+    let lnetctl_net = Invocation::new(
+        LNETCTL_NET_SHOW,
+        Program::Lnetctl,
+        ["net", "show", "-v", "4"].map(String::from),
+    );
+
+    thread::scope(|s| {
+        let handle = s.spawn(|| -> Result<Vec<Record>, LustreCollectorError> {
+            runner.run_streaming(&lctl, &mut |x| {
+                RecordStream::new(x, mode).merged().collect()
+            })
+        });
+
+        let mgs_fs_handle = s.spawn(|| -> Result<Vec<Record>, LustreCollectorError> {
+            let lctl_output = runner.run(&mgs_fs)?;
+            let lctl_record = parse_mgs_fs_output(&lctl_output)?;
+
+            Ok(lctl_record)
+        });
+
+        let lnetctl_stats_handle = s.spawn(|| -> Result<Vec<Record>, LustreCollectorError> {
+            let lnetctl_stats_output = runner.run(&lnetctl_stats)?;
+            let lnetctl_stats_record = parse_lnetctl_stats(str::from_utf8(&lnetctl_stats_output)?)?;
+
+            Ok(lnetctl_stats_record)
+        });
+
+        let lnetctl_peer_handle = s.spawn(|| -> Result<Vec<Record>, LustreCollectorError> {
+            let lnetctl_peer_output = runner.run(&lnetctl_peer)?;
+            let lnetctl_peer_record =
+                parse_lnetctl_peer_output(str::from_utf8(&lnetctl_peer_output)?)?;
+
+            Ok(lnetctl_peer_record)
+        });
+
+        let lnetctl_route_handle = s.spawn(|| -> Result<Vec<Record>, LustreCollectorError> {
+            let lnetctl_route_output = runner.run(&lnetctl_route)?;
+            let lnetctl_route_record =
+                parse_lnetctl_route_output(str::from_utf8(&lnetctl_route_output)?)?;
+
+            Ok(lnetctl_route_record)
+        });
+
+        let lnetctl_routing_handle = s.spawn(|| -> Result<Vec<Record>, LustreCollectorError> {
+            let lnetctl_routing_output = runner.run(&lnetctl_routing)?;
+            let lnetctl_routing_record =
+                parse_lnetctl_routing_output(str::from_utf8(&lnetctl_routing_output)?)?;
+
+            Ok(lnetctl_routing_record)
+        });
+
+        let recovery_status_handle = s.spawn(|| -> Result<Vec<Record>, LustreCollectorError> {
+            let recovery_status_output = runner.run(&recovery_status)?;
+            let recovery_statuses = parse_recovery_status_output(&recovery_status_output)?;
+
+            Ok(recovery_statuses)
+        });
+
+        let device_list_handle = s.spawn(|| -> Result<Vec<Record>, LustreCollectorError> {
+            let device_list_output = runner.run(&device_list)?;
+            let devices = parse_device_list_output(&device_list_output)?;
+
+            Ok(devices)
+        });
+
+        let mut lnet_record = runner
+            .run(&lnetctl_net)
+            .and_then(|x| parse_lnetctl_output(str::from_utf8(&x)?))
+            .unwrap_or_else(|e| {
+                debug!("Skipping `{lnetctl_net}`: {e}");
+
+                vec![]
+            });
+
+        let mut lctl_record = match handle.join() {
+            Ok(r) => r?,
+            Err(e) => std::panic::resume_unwind(e),
+        };
+
+        let mut mgs_fs_record = match mgs_fs_handle.join() {
+            Ok(r) => r.unwrap_or_default(),
+            Err(e) => std::panic::resume_unwind(e),
+        };
+
+        let mut recovery_status_records = match recovery_status_handle.join() {
+            Ok(r) => r.unwrap_or_default(),
+            Err(e) => std::panic::resume_unwind(e),
+        };
+
+        let mut lnetctl_stats_record = match lnetctl_stats_handle.join() {
+            Ok(r) => r.unwrap_or_default(),
+            Err(e) => std::panic::resume_unwind(e),
+        };
+
+        lctl_record.append(&mut lnet_record);
+        lctl_record.append(&mut mgs_fs_record);
+        lctl_record.append(&mut recovery_status_records);
+        lctl_record.append(&mut lnetctl_stats_record);
+
+        let mut lnetctl_peer_record = match lnetctl_peer_handle.join() {
+            Ok(r) => r.unwrap_or_default(),
+            Err(e) => std::panic::resume_unwind(e),
+        };
+
+        let mut lnetctl_route_record = match lnetctl_route_handle.join() {
+            Ok(r) => r.unwrap_or_else(|e| {
+                debug!("Skipping `{lnetctl_route}`: {e}");
+
+                vec![]
+            }),
+            Err(e) => std::panic::resume_unwind(e),
+        };
+
+        let mut lnetctl_routing_record = match lnetctl_routing_handle.join() {
+            Ok(r) => r.unwrap_or_else(|e| {
+                debug!("Skipping `{lnetctl_routing}`: {e}");
+
+                vec![]
+            }),
+            Err(e) => std::panic::resume_unwind(e),
+        };
+
+        let mut device_list_record = match device_list_handle.join() {
+            Ok(r) => r.unwrap_or_default(),
+            Err(e) => std::panic::resume_unwind(e),
+        };
+
+        lctl_record.append(&mut lnetctl_peer_record);
+        lctl_record.append(&mut lnetctl_route_record);
+        lctl_record.append(&mut lnetctl_routing_record);
+        lctl_record.append(&mut device_list_record);
+
+        jobid::annotate(&mut lctl_record);
+
+        Ok(lctl_record)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        jobid::JobIdFields, parse_lctl_output, parse_lnetctl_stats, parser, Diagnostic, Param,
+        TargetStat, TargetStats,
+    };
+
+    const LCTL: &str = include_str!("./fixtures/valid/valid.txt");
+    const MGS_FS: &str = "mgs.MGS.live.fs\nmgs.MGS.live.params\n";
+    const RECOVERY_STATUS: &str = include_str!("../fixtures/recovery-multiple.txt");
+    const DEVICE_LIST: &str = include_str!("../fixtures/device_list.txt");
+    const LNETCTL_STATS: &str = r#"statistics:
+    msgs_alloc: 0
+    msgs_max: 2578
+    rst_alloc: 20
+    errors: 0
+    send_count: 171344551
+    resend_count: 0
+    response_timeout_count: 0
+    local_interrupt_count: 0
+    local_dropped_count: 0
+    local_aborted_count: 0
+    local_no_route_count: 0
+    local_timeout_count: 0
+    local_error_count: 0
+    remote_dropped_count: 4
+    remote_error_count: 0
+    remote_timeout_count: 0
+    network_timeout_count: 0
+    recv_count: 171609513
+    route_count: 0
+    drop_count: 1185
+    send_length: 62502714567608
+    recv_length: 17084716480056
+    route_length: 0
+    drop_length: 568792
+"#;
+
+    #[test]
+    fn test_collect() {
+        let runner = ScriptedRunner::new()
+            .with_output(LCTL_GET_PARAM, LCTL)
+            .with_output(LCTL_MGS_FS, MGS_FS)
+            .with_output(LCTL_RECOVERY_STATUS, RECOVERY_STATUS)
+            .with_output(LNETCTL_STATS_SHOW, LNETCTL_STATS)
+            .with_output(LCTL_DEVICE_LIST, DEVICE_LIST)
+            .with_output(LNETCTL_NET_SHOW, "");
+
+        let records = collect(&runner, &parser::params(), ParseMode::Strict).unwrap();
+
+        let expected: Vec<_> = [
+            parse_lctl_output(LCTL.as_bytes()).unwrap(),
+            parse_mgs_fs_output(MGS_FS.as_bytes()).unwrap(),
+            parse_recovery_status_output(RECOVERY_STATUS.as_bytes()).unwrap(),
+            parse_lnetctl_stats(LNETCTL_STATS).unwrap(),
+            parse_device_list_output(DEVICE_LIST.as_bytes()).unwrap(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        assert_eq!(records, expected);
+    }
+
+    #[test]
+    fn test_collect_optional_commands_fail() {
+        let runner = ScriptedRunner::new().with_output(LCTL_GET_PARAM, "memused=1\n");
+
+        let records = collect(&runner, &["memused".to_string()], ParseMode::Strict).unwrap();
+
+        assert_eq!(records.len(), 1);
+
+        let mut calls = runner.calls();
+        calls.sort_unstable_by_key(|x| x.name);
+
+        insta::assert_debug_snapshot!(calls);
+    }
+
+    #[test]
+    fn test_collect_routing_without_routes() {
+        let routing = "routing:\n    - enable: 1\n";
+
+        let runner = ScriptedRunner::new()
+            .with_output(LCTL_GET_PARAM, "memused=1\n")
+            .with_output(LNETCTL_ROUTE_SHOW, "route: [")
+            .with_output(LNETCTL_ROUTING_SHOW, routing)
+            .with_output(LNETCTL_NET_SHOW, "");
+
+        let records = collect(&runner, &["memused".to_string()], ParseMode::Strict).unwrap();
+
+        assert_eq!(records[1..], parse_lnetctl_routing_output(routing).unwrap());
+    }
+
+    #[test]
+    fn test_collect_job_id_fields() {
+        let lctl = r#"jobid_name=%j.%e.%u
+jobid_var=SLURM_JOB_ID
+obdfilter.fs-OST0000.job_stats=
+job_stats:
+- job_id:          4242.ior.1000
+  snapshot_time:   1700000000
+  read_bytes:      { samples:           1, unit: bytes, min:    4096, max:    4096, sum:            4096 }
+  write_bytes:     { samples:           0, unit: bytes, min:       0, max:       0, sum:               0 }
+  getattr:         { samples:           0, unit:  reqs }
+  setattr:         { samples:           0, unit:  reqs }
+  punch:           { samples:           0, unit:  reqs }
+  sync:            { samples:           0, unit:  reqs }
+  destroy:         { samples:           0, unit:  reqs }
+  create:          { samples:           0, unit:  reqs }
+  statfs:          { samples:           0, unit:  reqs }
+  get_info:        { samples:           0, unit:  reqs }
+  set_info:        { samples:           0, unit:  reqs }
+  quotactl:        { samples:           0, unit:  reqs }
+"#;
+
+        let runner = ScriptedRunner::new().with_output(LCTL_GET_PARAM, lctl);
+
+        let records = collect(&runner, &parser::params(), ParseMode::Strict).unwrap();
+
+        let Some(Record::Target(TargetStats::JobStatsOst(TargetStat {
+            value: Some(jobs), ..
+        }))) = records.get(2)
+        else {
+            panic!("Expected job stats, got {records:?}");
+        };
+
+        assert_eq!(
+            jobs[0].job_id_fields,
+            Some(JobIdFields {
+                executable: Some("ior".to_string()),
+                uid: Some(1000),
+                job: Some("4242".to_string()),
+                ..JobIdFields::default()
+            })
+        );
+        assert!(serde_json::to_string(&records)
+            .unwrap()
+            .contains(r#""job_id_fields":{"executable":"ior","uid":1000"#));
+    }
+
+    #[test]
+    fn test_collect_lenient() {
+        let runner = ScriptedRunner::new()
+            .with_output(
+                LCTL_GET_PARAM,
+                "memused=1\nfoo.bar.baz=\nqux\nmemused_max=2\n",
+            )
+            .with_output(LNETCTL_NET_SHOW, "");
+
+        let params = ["memused".to_string()];
+
+        assert!(collect(&runner, &params, ParseMode::Strict).is_err());
+
+        let records = collect(&runner, &params, ParseMode::Lenient).unwrap();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            records[1],
+            Record::Diagnostic(Diagnostic {
+                param: Some(Param("foo.bar.baz".to_string())),
+                line: 2,
+                text: "foo.bar.baz=\nqux\n".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_collect_lctl_fails() {
+        let runner = ScriptedRunner::new().with_output(LNETCTL_NET_SHOW, "");
+
+        assert!(collect(&runner, &parser::params(), ParseMode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_replay_runner() {
+        let runner = ReplayRunner {
+            dir: PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures")),
+        };
+
+        let x = Invocation::new(LCTL_GET_PARAM, Program::Lctl, []);
+
+        assert_eq!(runner.run(&x).unwrap(), Vec::<u8>::new());
+
+        let x = Invocation::new("mdd", Program::Lctl, []);
+
+        assert_eq!(
+            runner.run(&x).unwrap(),
+            include_bytes!("../fixtures/mdd.txt").to_vec()
+        );
+    }
+
+    #[test]
+    fn test_local_runner_command() {
+        let x = Invocation::new(
+            LNETCTL_STATS_SHOW,
+            Program::Lnetctl,
+            ["stats", "show"].map(String::from),
+        );
+
+        let cmd = LocalRunner::default().command(&x);
+
+        assert_eq!(cmd.get_program(), "lnetctl");
+        assert_eq!(cmd.get_args().collect::<Vec<_>>(), vec!["stats", "show"]);
+
+        let runner = LocalRunner {
+            wrapper: ["nsenter", "-t", "1", "-m", "--"]
+                .map(String::from)
+                .to_vec(),
+            lctl: None,
+            lnetctl: Some("/opt/lustre/bin/lnetctl".to_string()),
+        };
+
+        let cmd = runner.command(&x);
+
+        assert_eq!(cmd.get_program(), "nsenter");
+        assert_eq!(
+            cmd.get_args().collect::<Vec<_>>(),
+            vec![
+                "-t",
+                "1",
+                "-m",
+                "--",
+                "/opt/lustre/bin/lnetctl",
+                "stats",
+                "show"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_local_runner_streaming() {
+        let x = Invocation::new(
+            LCTL_GET_PARAM,
+            Program::Lctl,
+            ["memused=1\nhealth_check=healthy"].map(String::from),
+        );
+
+        let runner = LocalRunner {
+            wrapper: vec![],
+            lctl: Some("echo".to_string()),
+            lnetctl: None,
+        };
+
+        let xs = runner
+            .run_streaming(&x, &mut |x| {
+                RecordStream::new(x, ParseMode::Strict).merged().collect()
+            })
+            .unwrap();
+
+        assert_eq!(
+            xs,
+            parse_lctl_output(b"memused=1\nhealth_check=healthy\n").unwrap()
+        );
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Parses the OBD device list printed by `lctl dl -t`.
+
+use crate::{
+    base_parsers::digits,
+    types::{HostStat, HostStats, ObdDevice, ObdDeviceStatus, Param, Record},
+};
+use combine::{
+    attempt, eof, many, many1, optional,
+    parser::char::{newline, space},
+    satisfy, skip_many, skip_many1,
+    stream::Stream,
+    token, ParseError, Parser,
+};
+
+pub const DEVICES: &str = "devices";
+
+pub fn args() -> Vec<String> {
+    vec!["dl".to_string(), "-t".to_string()]
+}
+
+/// A single whitespace delimited column
+fn column<I>() -> impl Parser<I, Output = String>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    many1(satisfy(|c: char| !c.is_whitespace()))
+}
+
+fn separator<I>() -> impl Parser<I, Output = ()>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    skip_many1(token(' '))
+}
+
+fn device<I>() -> impl Parser<I, Output = ObdDevice>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        skip_many(token(' ')).with(digits()),
+        separator().with(column()),
+        separator().with(column()),
+        separator().with(column()),
+        separator().with(column()),
+        separator().with(digits()),
+        optional(attempt(separator().with(column()))),
+    )
+        .skip(skip_many(token(' ')))
+        .skip(newline().map(drop).or(eof()))
+        .map(
+            |(index, status, kind, name, uuid, refcount, nid)| ObdDevice {
+                index,
+                status: ObdDeviceStatus::from(status.as_str()),
+                kind,
+                name,
+                uuid,
+                refcount,
+                nid,
+            },
+        )
+        .message("while parsing device")
+}
+
+pub fn parse<I>() -> impl Parser<I, Output = Vec<Record>>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    many(attempt(device()).map(|value| {
+        Record::Host(HostStats::ObdDevice(HostStat {
+            param: Param(DEVICES.to_string()),
+            value,
+        }))
+    }))
+    .skip(skip_many(space()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn test_device() {
+        let x = "  2 UP mgc MGC10.73.20.11@tcp 2b1b3c6e-0c25 4 10.73.20.11@tcp\n";
+
+        assert_eq!(
+            device().parse(x),
+            Ok((
+                ObdDevice {
+                    index: 2,
+                    status: ObdDeviceStatus::Up,
+                    kind: "mgc".to_string(),
+                    name: "MGC10.73.20.11@tcp".to_string(),
+                    uuid: "2b1b3c6e-0c25".to_string(),
+                    refcount: 4,
+                    nid: Some("10.73.20.11@tcp".to_string()),
+                },
+                ""
+            ))
+        );
+    }
+
+    #[test]
+    fn test_device_without_nid() {
+        let x = " 10 ST obdfilter fs-OST0001 fs-OST0001_UUID 5";
+
+        assert_eq!(
+            device().parse(x),
+            Ok((
+                ObdDevice {
+                    index: 10,
+                    status: ObdDeviceStatus::Stopping,
+                    kind: "obdfilter".to_string(),
+                    name: "fs-OST0001".to_string(),
+                    uuid: "fs-OST0001_UUID".to_string(),
+                    refcount: 5,
+                    nid: None,
+                },
+                ""
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let x = include_str!("../fixtures/device_list.txt");
+
+        let (records, state) = parse().parse(x).unwrap();
+
+        assert_eq!(state, "");
+        assert_debug_snapshot!(records);
+    }
+}
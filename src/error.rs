@@ -2,10 +2,122 @@
 // Use of this source code is governed by a MIT-style
 // license that can be found in the LICENSE file.
 
-use combine::error::StringStreamError;
-use std::{io, str};
+use combine::{error::StringStreamError, stream::easy};
+use std::{fmt, io, str};
 use thiserror::Error;
 
+/// Longest excerpt of the offending line kept in a [`ParseFailure`].
+const EXCERPT_LEN: usize = 120;
+
+/// Where and why `lctl` output failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFailure {
+    /// The param whose block failed to parse, if the failure is inside one.
+    pub param: Option<String>,
+    /// Line of the failure, starting at 1.
+    pub line: usize,
+    /// Column of the failure, starting at 1.
+    pub column: usize,
+    /// What the parser expected to find at this position.
+    pub expected: Vec<String>,
+    /// The offending line, truncated.
+    pub excerpt: String,
+}
+
+impl ParseFailure {
+    /// Builds a failure at byte `offset` of `input`.
+    pub(crate) fn new(input: &str, offset: usize, expected: Vec<String>) -> Self {
+        let offset = offset.min(input.len());
+
+        let (before, after) = input.split_at(offset);
+        let line_start = before.rfind('\n').map_or(0, |x| x + 1);
+        let line_end = after.find('\n').map_or(input.len(), |x| offset + x);
+
+        let param = input[..line_end]
+            .lines()
+            .rev()
+            .find_map(crate::parser::param_line)
+            .map(String::from);
+
+        Self {
+            param,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            expected,
+            excerpt: input[line_start..line_end]
+                .chars()
+                .take(EXCERPT_LEN)
+                .collect(),
+        }
+    }
+
+    /// Builds a failure from the error of an `easy_parse` of `input`.
+    pub(crate) fn from_easy(input: &str, err: &easy::Errors<char, &str, usize>) -> Self {
+        let mut expected: Vec<String> = vec![];
+
+        for x in &err.errors {
+            let x = match x {
+                easy::Error::Expected(x) => x.to_string(),
+                easy::Error::Unexpected(_) => continue,
+                easy::Error::Message(x) => x.to_string(),
+                easy::Error::Other(x) => x.to_string(),
+            };
+
+            if !expected.contains(&x) {
+                expected.push(x);
+            }
+        }
+
+        Self::new(input, err.position, expected)
+    }
+
+    /// Renders the failure with the offending line and a marker at the column.
+    pub fn explain(&self) -> String {
+        let gutter = self.line.to_string().len();
+        let pad = " ".repeat(gutter);
+
+        let mut x = format!(
+            "error: parse error at line {}, column {}\n{pad} |\n{} | {}\n",
+            self.line, self.column, self.line, self.excerpt
+        );
+
+        x.push_str(&format!(
+            "{pad} | {}^\n",
+            " ".repeat(self.column.saturating_sub(1))
+        ));
+
+        if let Some(param) = &self.param {
+            x.push_str(&format!("{pad} = in param: {param}\n"));
+        }
+
+        if !self.expected.is_empty() {
+            x.push_str(&format!("{pad} = expected: {}\n", self.expected.join(", ")));
+        }
+
+        x
+    }
+}
+
+impl fmt::Display for ParseFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Parse error at line {}, column {}",
+            self.line, self.column
+        )?;
+
+        if let Some(param) = &self.param {
+            write!(f, " in `{param}`")?;
+        }
+
+        if !self.expected.is_empty() {
+            write!(f, ", expected {}", self.expected.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum LustreCollectorError {
     #[error(transparent)]
@@ -16,8 +128,8 @@ pub enum LustreCollectorError {
     SerdeYamlError(#[from] serde_yaml::Error),
     #[error(transparent)]
     StringStreamError(#[from] StringStreamError),
-    #[error(transparent)]
-    CombineEasyError(combine::stream::easy::Errors<char, &'static str, usize>),
+    #[error("{0}")]
+    ParseError(ParseFailure),
     #[error(transparent)]
     Utf8Error(#[from] str::Utf8Error),
     #[error("{0}")]
@@ -25,9 +137,3 @@ pub enum LustreCollectorError {
     #[error("Cannot convert timestamp {0} to a u64 of milliseconds")]
     InvalidTime(String),
 }
-
-impl From<combine::stream::easy::Errors<char, &str, usize>> for LustreCollectorError {
-    fn from(err: combine::stream::easy::Errors<char, &str, usize>) -> Self {
-        LustreCollectorError::CombineEasyError(err.map_range(|_| ""))
-    }
-}
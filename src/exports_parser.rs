@@ -42,7 +42,11 @@ where
         string("stats").skip(equals()),
         stats(),
     ))
-    .map(|(nid, _, stats)| ExportStats { nid, stats })
+    .map(|(nid, _, (snapshot_time, stats))| ExportStats {
+        nid,
+        stats,
+        snapshot_time,
+    })
     .message("while parsing export_stats")
 }
 
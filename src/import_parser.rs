@@ -0,0 +1,231 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Parsers for the YAML formatted `import` and `state` params of
+//! client (`osc`/`mdc`) devices.
+
+use crate::{
+    base_parsers::yaml_block,
+    types::{ClientImport, ImportState, ImportStateChange, ImportStateHistory},
+};
+use combine::{
+    error::{ParseError, StreamError},
+    stream::{Stream, StreamErrorFor},
+    Parser,
+};
+
+#[derive(Debug, serde::Deserialize)]
+struct ImportYaml {
+    import: Import,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Import {
+    state: String,
+    #[serde(default)]
+    connection: Connection,
+    #[serde(default)]
+    rpcs: Rpcs,
+    #[serde(default)]
+    service_estimates: ServiceEstimates,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct Connection {
+    failover_nids: Vec<String>,
+    current_connection: Option<String>,
+    connection_attempts: u64,
+    generation: u64,
+    #[serde(rename = "in-progress_invalidations")]
+    in_progress_invalidations: u64,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct Rpcs {
+    inflight: u64,
+    unregistering: u64,
+    timeouts: u64,
+    avg_waittime: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct ServiceEstimates {
+    services: Option<String>,
+    network: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct StateYaml {
+    current_state: String,
+    #[serde(default)]
+    state_history: Vec<(i64, String)>,
+}
+
+/// Takes the leading number of values like `1437 usec` or `1 sec`
+fn leading_number(x: Option<String>) -> Option<u64> {
+    x?.split_whitespace().next()?.parse().ok()
+}
+
+impl From<Import> for ClientImport {
+    fn from(x: Import) -> Self {
+        ClientImport {
+            state: x.state.as_str().into(),
+            failover_nids: x.connection.failover_nids,
+            current_connection: x.connection.current_connection,
+            connection_attempts: x.connection.connection_attempts,
+            generation: x.connection.generation,
+            in_progress_invalidations: x.connection.in_progress_invalidations,
+            inflight: x.rpcs.inflight,
+            unregistering: x.rpcs.unregistering,
+            timeouts: x.rpcs.timeouts,
+            avg_waittime: leading_number(x.rpcs.avg_waittime),
+            service_estimate: leading_number(x.service_estimates.services),
+            network_estimate: leading_number(x.service_estimates.network),
+        }
+    }
+}
+
+impl From<StateYaml> for ImportStateHistory {
+    fn from(x: StateYaml) -> Self {
+        ImportStateHistory {
+            current_state: x.current_state.as_str().into(),
+            state_history: x
+                .state_history
+                .into_iter()
+                .map(|(time, state)| ImportStateChange {
+                    time,
+                    state: ImportState::from(state.as_str()),
+                })
+                .collect(),
+        }
+    }
+}
+
+pub(crate) fn import<I>() -> impl Parser<I, Output = ClientImport>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    yaml_block().and_then(|x: String| {
+        serde_yaml::from_str(&x)
+            .map(|x: ImportYaml| x.import.into())
+            .map_err(StreamErrorFor::<I>::other)
+    })
+}
+
+pub(crate) fn state<I>() -> impl Parser<I, Output = ImportStateHistory>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    yaml_block().and_then(|x: String| {
+        serde_yaml::from_str(&x)
+            .map(|x: StateYaml| x.into())
+            .map_err(StreamErrorFor::<I>::other)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn test_import() {
+        let x = r#"
+import:
+    name: fs-OST0000-osc-ffff8dc8f8d4b000
+    target: fs-OST0000_UUID
+    state: FULL
+    connect_flags: [ write_grant, server_lock, version, request_portal, max_byte_per_rpc, early_lock_cancel, adaptive_timeouts, lru_resize, fid_is_enabled, version_recovery, grant_shrink, full20, layout_lock, 64bithash, object_max_bytes, jobstats, einprogress, lvb_type, short_io, lfsck, bulk_mbits, second_flags, lockaheadv2, increasing_xid, client_encryption, lseek, reply_mbits ]
+    connect_data:
+       flags: 0xa0425af2e3440478
+       instance: 1
+       target_version: 2.14.0.0
+       initial_grant: 8437760
+       max_brw_size: 4194304
+       grant_block_size: 4096
+       grant_inode_size: 32
+       grant_max_extent_size: 67108864
+       grant_extent_tax: 24576
+       cksum_types: 0xf7
+       max_object_bytes: 17592186040320
+    import_flags: [ replayable, pingable, connect_tried ]
+    connection:
+       failover_nids: [ 10.0.0.1@tcp, 10.0.0.2@tcp ]
+       current_connection: 10.0.0.1@tcp
+       connection_attempts: 3
+       generation: 2
+       in-progress_invalidations: 0
+       idle: 36 sec
+    rpcs:
+       inflight: 1
+       unregistering: 0
+       timeouts: 4
+       avg_waittime: 1437 usec
+    service_estimates:
+       services: 1 sec
+       network: 2 sec
+    transactions:
+       last_replay: 0
+       peer_committed: 0
+       last_checked: 0
+    read_data_averages:
+       bytes_per_rpc: 524288
+       usec_per_rpc: 3000
+       MB_per_sec: 174.76
+osc.fs-OST0001-osc-ffff8dc8f8d4b000.import=
+"#;
+
+        let result = import().parse(x);
+
+        assert_debug_snapshot!(result);
+    }
+
+    #[test]
+    fn test_state() {
+        let x = r#"
+current_state: FULL
+state_history:
+ - [ 1700000000, CONNECTING ]
+ - [ 1700000000, EVICTED ]
+ - [ 1700000010, DISCONN ]
+ - [ 1700000011, FULL ]
+osc.fs-OST0001-osc-ffff8dc8f8d4b000.state=
+"#;
+
+        let result = state().parse(x);
+
+        assert_eq!(
+            result,
+            Ok((
+                ImportStateHistory {
+                    current_state: ImportState::Full,
+                    state_history: vec![
+                        ImportStateChange {
+                            time: 1_700_000_000,
+                            state: ImportState::Connecting,
+                        },
+                        ImportStateChange {
+                            time: 1_700_000_000,
+                            state: ImportState::Evicted,
+                        },
+                        ImportStateChange {
+                            time: 1_700_000_010,
+                            state: ImportState::Disconn,
+                        },
+                        ImportStateChange {
+                            time: 1_700_000_011,
+                            state: ImportState::Full,
+                        },
+                    ],
+                },
+                "osc.fs-OST0001-osc-ffff8dc8f8d4b000.state=\n"
+            ))
+        );
+    }
+}
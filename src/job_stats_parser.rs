@@ -0,0 +1,304 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Parses the YAML-like `job_stats` output of OSTs and MDTs directly,
+//! without going through `serde_yaml`.
+//!
+//! ```text
+//! job_stats:
+//! - job_id:          dd.0
+//!   snapshot_time:   1686153914.643122579 secs.nsecs
+//!   read_bytes:      { samples: 1, unit: bytes, min: 4096, max: 4096, sum: 4096, sumsq: 16777216, hist: { 4K: 1 } }
+//!   getattr:         { samples: 0, unit: reqs }
+//! ```
+//!
+//! Every `{ .. }` line is kept by name in a [`JobStat`], so operations the
+//! fixed [`crate::types::JobStatOst`] / [`crate::types::JobStatMdt`] structs
+//! don't know about don't fail parsing.
+
+use crate::types::{JobStat, JobStatOp, UnsignedLustreTimestamp};
+use combine::{
+    between, eof,
+    error::{ParseError, StreamError},
+    many, many1, none_of, one_of, optional,
+    parser::char::{char, newline, spaces, string},
+    sep_end_by, skip_many,
+    stream::{Stream, StreamErrorFor},
+    Parser,
+};
+use std::collections::BTreeMap;
+
+/// Spaces and tabs, but not newlines.
+fn blanks<I>() -> impl Parser<I, Output = ()>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    skip_many(one_of(" \t".chars()))
+}
+
+/// The rest of the line, without the newline.
+fn rest_of_line<I>() -> impl Parser<I, Output = String>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    many(none_of("\n".chars()))
+}
+
+fn key<I>() -> impl Parser<I, Output = String>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    many1(none_of(":{},\n \t".chars())).skip(char(':'))
+}
+
+/// A plain, double or single quoted scalar up to the end of the line.
+///
+/// In a single quoted scalar `''` stands for a `'`.
+fn scalar<I>() -> impl Parser<I, Output = String>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    rest_of_line().map(|x: String| {
+        let x = x.trim();
+
+        if let Some(x) = x.strip_prefix('"').and_then(|x| x.strip_suffix('"')) {
+            return x.to_string();
+        }
+
+        if let Some(x) = x.strip_prefix('\'').and_then(|x| x.strip_suffix('\'')) {
+            return x.replace("''", "'");
+        }
+
+        x.to_string()
+    })
+}
+
+/// A `{ key: value, .. }` flow mapping of an operation.
+///
+/// The mapping may be wrapped over several lines. Nested mappings such as
+/// `hist: { 4K: 1 }` are skipped.
+fn op<I>() -> impl Parser<I, Output = JobStatOp>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    let value = between(char('{'), char('}'), skip_many(none_of("}".chars())))
+        .map(|_| None)
+        .or(many1(none_of(",}\n \t".chars())).map(Some));
+
+    let entry = (key().skip(spaces()), value.skip(spaces()));
+
+    between(
+        char('{').skip(spaces()),
+        char('}'),
+        sep_end_by(entry, char(',').skip(spaces())),
+    )
+    .and_then(|xs: Vec<(String, Option<String>)>| {
+        let mut op = JobStatOp::default();
+
+        for (k, v) in xs {
+            let Some(v) = v else {
+                continue;
+            };
+
+            let n = || {
+                v.parse::<i64>()
+                    .map_err(|_| StreamErrorFor::<I>::message_format(format!("`{k}: {v}`")))
+            };
+
+            match k.as_str() {
+                "samples" => op.samples = n()?,
+                "unit" => op.unit = v,
+                "min" => op.min = Some(n()?),
+                "max" => op.max = Some(n()?),
+                "sum" => op.sum = Some(n()?),
+                "sumsq" => op.sumsq = Some(n()?),
+                _ => {}
+            }
+        }
+
+        Ok::<_, StreamErrorFor<I>>(op)
+    })
+}
+
+enum Field {
+    Scalar(String, String),
+    Op(String, JobStatOp),
+}
+
+/// An indented `key: value` line of a job entry.
+fn field<I>() -> impl Parser<I, Output = Field>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        char(' ').with(blanks()).with(key()).skip(blanks()),
+        op().skip(rest_of_line()).map(Ok).or(scalar().map(Err)),
+    )
+        .map(|(k, x)| match x {
+            Ok(op) => Field::Op(k, op),
+            Err(v) => Field::Scalar(k, v),
+        })
+        .skip(newline().map(drop).or(eof()))
+}
+
+fn timestamp(x: &str) -> Result<UnsignedLustreTimestamp, String> {
+    UnsignedLustreTimestamp::try_from(x.to_string()).map_err(|e| e.to_string())
+}
+
+fn job<I>() -> impl Parser<I, Output = JobStat>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        string("- job_id:").with(scalar()),
+        newline().map(drop).or(eof()),
+        many(field()),
+    )
+        .and_then(|(job_id, _, fields): (_, _, Vec<_>)| {
+            let mut snapshot_time = None;
+            let mut start_time = None;
+            let mut elapsed_time = None;
+            let mut ops = BTreeMap::new();
+
+            for x in fields {
+                match x {
+                    Field::Scalar(k, v) => match k.as_str() {
+                        "snapshot_time" => snapshot_time = Some(timestamp(&v)),
+                        "start_time" => start_time = Some(timestamp(&v)),
+                        "elapsed_time" => elapsed_time = Some(v),
+                        _ => {}
+                    },
+                    Field::Op(k, op) => {
+                        ops.insert(k, op);
+                    }
+                }
+            }
+
+            let snapshot_time = snapshot_time
+                .unwrap_or_else(|| Err(format!("job {job_id}: missing `snapshot_time`")))
+                .map_err(StreamErrorFor::<I>::message_format)?;
+
+            let start_time = start_time
+                .transpose()
+                .map_err(StreamErrorFor::<I>::message_format)?;
+
+            Ok::<_, StreamErrorFor<I>>(JobStat {
+                job_id,
+                snapshot_time,
+                start_time,
+                elapsed_time,
+                ops,
+            })
+        })
+}
+
+/// Parses the `job_stats:` block following a `job_stats=` param.
+///
+/// Returns `None` when no jobs are listed.
+pub(crate) fn parse<I>() -> impl Parser<I, Output = Option<Vec<JobStat>>>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        optional(newline()),
+        string("job_stats:").with(rest_of_line()),
+        newline().map(drop).or(eof()),
+        many(job()),
+    )
+        .map(|(_, _, _, xs): (_, _, _, Vec<_>)| (!xs.is_empty()).then_some(xs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use combine::EasyParser;
+
+    #[test]
+    fn test_unknown_ops() {
+        let x = r#"job_stats:
+- job_id:          "lfs 0"
+  snapshot_time:   1686153914.643122579 secs.nsecs
+  start_time:      1686153914.643119181 secs.nsecs
+  elapsed_time:    0.000003398 secs.nsecs
+  read_bytes:      { samples:           1, unit: bytes, min:     4096, max:     4096, sum:             4096, sumsq:           16777216, hist: { 4K: 1 } }
+  fallocate:       { samples:           3, unit: usecs, min:        1, max:        5, sum:                9, sumsq:                 35 }
+  getattr:         { samples:           0, unit:  reqs }
+"#;
+
+        let (xs, rest) = parse().easy_parse(x).unwrap();
+
+        assert_eq!(rest, "");
+
+        let xs = xs.unwrap();
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].job_id, "lfs 0");
+        assert_eq!(
+            xs[0].start_time,
+            Some(UnsignedLustreTimestamp(1_686_153_914_643))
+        );
+        assert_eq!(
+            xs[0].elapsed_time.as_deref(),
+            Some("0.000003398 secs.nsecs")
+        );
+        assert_eq!(
+            xs[0].ops["fallocate"],
+            JobStatOp {
+                samples: 3,
+                unit: "usecs".to_string(),
+                min: Some(1),
+                max: Some(5),
+                sum: Some(9),
+                sumsq: Some(35),
+            }
+        );
+        assert_eq!(xs[0].ops["read_bytes"].sum, Some(4096));
+        assert_eq!(xs[0].ops["getattr"].min, None);
+    }
+
+    #[test]
+    fn test_quoted_job_ids() {
+        let x = r#"job_stats:
+- job_id:          'lfs 0'
+  snapshot_time:   1686153914
+- job_id:          'it''s.0'
+  snapshot_time:   1686153914
+- job_id:          "dd 1"
+  snapshot_time:   1686153914
+"#;
+
+        let (xs, _) = parse().easy_parse(x).unwrap();
+
+        let ids: Vec<_> = xs.unwrap().into_iter().map(|x| x.job_id).collect();
+
+        assert_eq!(ids, ["lfs 0", "it's.0", "dd 1"]);
+
+        let expected: Vec<_> = serde_yaml::from_str::<serde_yaml::Value>(x).unwrap()["job_stats"]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .map(|x| x["job_id"].as_str().unwrap().to_string())
+            .collect();
+
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn test_empty() {
+        let x = "job_stats:\nobdfilter.fs-OST0000.stats=";
+
+        assert_eq!(
+            parse().easy_parse(x),
+            Ok((None, "obdfilter.fs-OST0000.stats="))
+        );
+    }
+}
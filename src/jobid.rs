@@ -0,0 +1,381 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Splits job IDs into their parts according to the `jobid_name` and
+//! `jobid_var` in effect on the node.
+//!
+//! `jobid_name` is a format such as `%e.%u` where each `%` specifier
+//! expands to part of the job ID:
+//!
+//! | specifier | field        |
+//! |-----------|--------------|
+//! | `%e`      | `executable` |
+//! | `%u`      | `uid`        |
+//! | `%g`      | `gid`        |
+//! | `%h`      | `hostname`   |
+//! | `%H`      | `hostname`   |
+//! | `%j`      | `job`        |
+//! | `%p`      | `project`    |
+//!
+//! `%h` is the full node name, `%H` the short hostname up to the first `.`.
+
+use crate::types::{HostStats, Record, TargetStats};
+
+pub const PROCNAME_UID: &str = "procname_uid";
+pub const DISABLE: &str = "disable";
+
+/// The default `jobid_name` when none was collected.
+pub const DEFAULT_JOBID_NAME: &str = "%e.%u";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Executable,
+    Uid,
+    Gid,
+    Hostname,
+    ShortHostname,
+    Job,
+    Project,
+}
+
+impl Field {
+    fn from_specifier(x: char) -> Option<Self> {
+        match x {
+            'e' => Some(Self::Executable),
+            'u' => Some(Self::Uid),
+            'g' => Some(Self::Gid),
+            'h' => Some(Self::Hostname),
+            'H' => Some(Self::ShortHostname),
+            'j' => Some(Self::Job),
+            'p' => Some(Self::Project),
+            _ => None,
+        }
+    }
+
+    /// Whether `x` is a valid value for this field.
+    fn accepts(self, x: &str) -> bool {
+        match self {
+            Self::Uid | Self::Gid | Self::Project => x.chars().all(|c| c.is_ascii_digit()),
+            // The short hostname is cut at the first `.`
+            Self::ShortHostname => !x.contains('.'),
+            Self::Executable | Self::Hostname | Self::Job => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Field(Field),
+}
+
+/// The parts of a job ID, as far as the format in effect contains them.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct JobIdFields {
+    pub executable: Option<String>,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    pub hostname: Option<String>,
+    /// The scheduler job ID, taken from the `jobid_var` environment variable.
+    pub job: Option<String>,
+    /// The project ID of the file or directory.
+    pub project: Option<u32>,
+}
+
+impl JobIdFields {
+    fn set(&mut self, field: Field, x: &str) -> Option<()> {
+        match field {
+            Field::Executable => self.executable = Some(x.to_string()),
+            Field::Uid => self.uid = Some(x.parse().ok()?),
+            Field::Gid => self.gid = Some(x.parse().ok()?),
+            Field::Hostname | Field::ShortHostname => self.hostname = Some(x.to_string()),
+            Field::Job => self.job = Some(x.to_string()),
+            Field::Project => self.project = Some(x.parse().ok()?),
+        }
+
+        Some(())
+    }
+
+    /// The fields that are set, as `(label, value)` pairs.
+    pub fn labels(&self) -> Vec<(&'static str, String)> {
+        [
+            ("job_executable", self.executable.clone()),
+            ("job_uid", self.uid.map(|x| x.to_string())),
+            ("job_gid", self.gid.map(|x| x.to_string())),
+            ("job_hostname", self.hostname.clone()),
+            ("job_scheduler_id", self.job.clone()),
+            ("job_project", self.project.map(|x| x.to_string())),
+        ]
+        .into_iter()
+        .filter_map(|(k, v)| Some((k, v?)))
+        .collect()
+    }
+}
+
+/// The job ID format in effect, built from `jobid_name` and `jobid_var`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobIdFormat {
+    segments: Vec<Segment>,
+    /// Set when `jobid_var` names an environment variable
+    /// (or `nodelocal`/`session`) whose value is used as the whole job ID.
+    from_var: bool,
+    disabled: bool,
+}
+
+impl Default for JobIdFormat {
+    fn default() -> Self {
+        Self::new(DEFAULT_JOBID_NAME, PROCNAME_UID)
+    }
+}
+
+impl JobIdFormat {
+    pub fn new(jobid_name: &str, jobid_var: &str) -> Self {
+        let mut segments = vec![];
+        let mut literal = String::new();
+        let mut chars = jobid_name.trim().chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                literal.push(c);
+                continue;
+            }
+
+            let Some(x) = chars.next() else {
+                literal.push(c);
+                break;
+            };
+
+            match Field::from_specifier(x) {
+                Some(field) => {
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+
+                    segments.push(Segment::Field(field));
+                }
+                None if x == '%' => literal.push('%'),
+                None => {
+                    literal.push(c);
+                    literal.push(x);
+                }
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        let jobid_var = jobid_var.trim();
+
+        Self {
+            segments,
+            from_var: !matches!(jobid_var, PROCNAME_UID | DISABLE),
+            disabled: jobid_var == DISABLE,
+        }
+    }
+
+    /// Builds the format from the `jobid_name` and `jobid_var` host records,
+    /// falling back to the Lustre defaults for whichever is missing.
+    pub fn from_records(records: &[Record]) -> Self {
+        let mut name = None;
+        let mut var = None;
+
+        for x in records {
+            match x {
+                Record::Host(HostStats::JobIdName(x)) => name = Some(x.value.as_str()),
+                Record::Host(HostStats::JobIdVar(x)) => var = Some(x.value.as_str()),
+                _ => {}
+            }
+        }
+
+        Self::new(
+            name.unwrap_or(DEFAULT_JOBID_NAME),
+            var.unwrap_or(PROCNAME_UID),
+        )
+    }
+
+    /// Splits `job_id` into its fields.
+    ///
+    /// Returns `None` when job IDs are disabled or `job_id` does not match
+    /// the format. When `jobid_var` names an environment variable, a job ID
+    /// not matching `jobid_name` is taken to be that variable's value.
+    pub fn decompose(&self, job_id: &str) -> Option<JobIdFields> {
+        if self.disabled {
+            return None;
+        }
+
+        let mut fields = JobIdFields::default();
+
+        if matches(&self.segments, job_id, &mut fields) {
+            return Some(fields);
+        }
+
+        self.from_var.then(|| JobIdFields {
+            job: Some(job_id.to_string()),
+            ..JobIdFields::default()
+        })
+    }
+}
+
+/// Sets the decomposed `job_id_fields` of every job in `records`, using the
+/// `jobid_name` and `jobid_var` found in `records`.
+pub fn annotate(records: &mut [Record]) {
+    let jobid = JobIdFormat::from_records(records);
+
+    for x in records {
+        match x {
+            Record::Target(TargetStats::JobStatsOst(x)) => {
+                for job in x.value.iter_mut().flatten() {
+                    job.job_id_fields = jobid.decompose(&job.job_id);
+                }
+            }
+            Record::Target(TargetStats::JobStatsMdt(x)) => {
+                for job in x.value.iter_mut().flatten() {
+                    job.job_id_fields = jobid.decompose(&job.job_id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Matches `x` against `segments`, preferring the shortest value for each
+/// field so that later literals split at their first occurrence.
+fn matches(segments: &[Segment], x: &str, out: &mut JobIdFields) -> bool {
+    let Some((head, rest)) = segments.split_first() else {
+        return x.is_empty();
+    };
+
+    match head {
+        Segment::Literal(lit) => x
+            .strip_prefix(lit.as_str())
+            .is_some_and(|x| matches(rest, x, out)),
+        Segment::Field(field) => {
+            // The last field takes everything that's left.
+            let ends: Vec<usize> = if rest.is_empty() {
+                vec![x.len()]
+            } else {
+                x.char_indices().skip(1).map(|(i, _)| i).collect()
+            };
+
+            for end in ends {
+                let (value, tail) = x.split_at(end);
+
+                if value.is_empty() || !field.accepts(value) {
+                    continue;
+                }
+
+                let mut candidate = out.clone();
+
+                if candidate.set(*field, value).is_some() && matches(rest, tail, &mut candidate) {
+                    *out = candidate;
+
+                    return true;
+                }
+            }
+
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_format() {
+        let x = JobIdFormat::default();
+
+        assert_eq!(
+            x.decompose("dd.1000"),
+            Some(JobIdFields {
+                executable: Some("dd".to_string()),
+                uid: Some(1000),
+                ..JobIdFields::default()
+            })
+        );
+        assert_eq!(
+            x.decompose("python3.9.0").unwrap().executable.as_deref(),
+            Some("python3.9")
+        );
+        assert_eq!(x.decompose("cp"), None);
+    }
+
+    #[test]
+    fn test_full_format() {
+        let x = JobIdFormat::new("%j:%e.%u.%g@%h.%p\n", PROCNAME_UID);
+
+        assert_eq!(
+            x.decompose("4242:ior.500.100@node01.7"),
+            Some(JobIdFields {
+                executable: Some("ior".to_string()),
+                uid: Some(500),
+                gid: Some(100),
+                hostname: Some("node01".to_string()),
+                job: Some("4242".to_string()),
+                project: Some(7),
+            })
+        );
+    }
+
+    #[test]
+    fn test_hostname() {
+        let x = JobIdFormat::new("%e.%h", PROCNAME_UID);
+
+        assert_eq!(
+            x.decompose("dd.node01.example.com")
+                .unwrap()
+                .hostname
+                .as_deref(),
+            Some("node01.example.com")
+        );
+    }
+
+    #[test]
+    fn test_short_hostname() {
+        let x = JobIdFormat::new("%H.%e", PROCNAME_UID);
+
+        assert_eq!(
+            x.decompose("node01.dd.x").unwrap().hostname.as_deref(),
+            Some("node01")
+        );
+
+        let x = JobIdFormat::new("%e@%H", PROCNAME_UID);
+
+        assert_eq!(x.decompose("dd@node01.example.com"), None);
+        assert_eq!(
+            x.decompose("dd@node01").unwrap().hostname.as_deref(),
+            Some("node01")
+        );
+    }
+
+    #[test]
+    fn test_jobid_var() {
+        let x = JobIdFormat::new(DEFAULT_JOBID_NAME, "SLURM_JOB_ID");
+
+        assert_eq!(
+            x.decompose("123456"),
+            Some(JobIdFields {
+                job: Some("123456".to_string()),
+                ..JobIdFields::default()
+            })
+        );
+        assert_eq!(x.decompose("dd.0").unwrap().uid, Some(0));
+
+        let x = JobIdFormat::new(DEFAULT_JOBID_NAME, DISABLE);
+
+        assert_eq!(x.decompose("dd.0"), None);
+    }
+
+    #[test]
+    fn test_literal_percent() {
+        let x = JobIdFormat::new("%e%%%u", PROCNAME_UID);
+
+        assert_eq!(
+            x.decompose("ls%0").unwrap().executable.as_deref(),
+            Some("ls")
+        );
+    }
+}
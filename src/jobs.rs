@@ -0,0 +1,192 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Aggregates job stats across all OSTs and MDTs of a node.
+//!
+//! Lustre reports `job_stats` per target, so a job doing I/O against many
+//! targets shows up once per target. [`aggregate`] folds those into a single
+//! entry per `job_id`, keeping the per-target breakdown.
+
+use crate::types::{JobStat, Record, Target, TargetStat, TargetStats, TargetVariant};
+use std::collections::BTreeMap;
+
+/// Bytes and operation counts of a job.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct JobTotals {
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    /// Samples per operation, e.g. `read_bytes`, `getattr`, `open`.
+    pub ops: BTreeMap<String, u64>,
+}
+
+impl JobTotals {
+    fn add(&mut self, x: &JobTotals) {
+        self.read_bytes += x.read_bytes;
+        self.write_bytes += x.write_bytes;
+
+        for (name, samples) in &x.ops {
+            *self.ops.entry(name.clone()).or_default() += samples;
+        }
+    }
+}
+
+/// What a job did on a single target.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct JobTargetTotals {
+    pub kind: TargetVariant,
+    pub target: Target,
+    #[serde(flatten)]
+    pub totals: JobTotals,
+}
+
+/// A job's stats summed over every target it touched.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct JobAggregate {
+    pub job_id: String,
+    #[serde(flatten)]
+    pub totals: JobTotals,
+    pub targets: Vec<JobTargetTotals>,
+}
+
+/// Lustre counters are signed, negative values are treated as 0.
+fn unsigned(x: i64) -> u64 {
+    u64::try_from(x).unwrap_or_default()
+}
+
+fn totals(x: &JobStat) -> JobTotals {
+    let sum = |name| unsigned(x.ops.get(name).and_then(|x| x.sum).unwrap_or_default());
+
+    JobTotals {
+        read_bytes: sum("read_bytes"),
+        write_bytes: sum("write_bytes"),
+        ops: x
+            .ops
+            .iter()
+            .map(|(name, x)| (name.clone(), unsigned(x.samples)))
+            .collect(),
+    }
+}
+
+/// Returns one [`JobAggregate`] per `job_id` found in the `job_stats` of
+/// `records`, ordered by `job_id`.
+pub fn aggregate(records: &[Record]) -> Vec<JobAggregate> {
+    let mut jobs: BTreeMap<&str, Vec<JobTargetTotals>> = BTreeMap::new();
+
+    let mut push = |job_id, kind, target: &Target, totals| {
+        jobs.entry(job_id).or_default().push(JobTargetTotals {
+            kind,
+            target: target.clone(),
+            totals,
+        });
+    };
+
+    for x in records {
+        match x {
+            Record::Target(TargetStats::JobStatsOst(TargetStat {
+                kind,
+                target,
+                value: Some(xs),
+                ..
+            })) => {
+                for x in xs {
+                    push(&x.job_id, *kind, target, totals(&x.into()));
+                }
+            }
+            Record::Target(TargetStats::JobStatsMdt(TargetStat {
+                kind,
+                target,
+                value: Some(xs),
+                ..
+            })) => {
+                for x in xs {
+                    push(&x.job_id, *kind, target, totals(&x.into()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    jobs.into_iter()
+        .map(|(job_id, targets)| {
+            let mut totals = JobTotals::default();
+
+            for x in &targets {
+                totals.add(&x.totals);
+            }
+
+            JobAggregate {
+                job_id: job_id.to_string(),
+                totals,
+                targets,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_lctl_output;
+
+    fn ost_job(target: &str, job_id: &str, read: u64, write: u64) -> String {
+        format!(
+            "obdfilter.{target}.job_stats=
+job_stats:
+- job_id:          {job_id}
+  snapshot_time:   1700000000
+  read_bytes:      {{ samples:           1, unit: bytes, min: {read}, max: {read}, sum: {read} }}
+  write_bytes:     {{ samples:           2, unit: bytes, min: 0, max: {write}, sum: {write} }}
+  getattr:         {{ samples:           0, unit:  reqs }}
+  setattr:         {{ samples:           0, unit:  reqs }}
+  punch:           {{ samples:           1, unit:  reqs }}
+  sync:            {{ samples:           0, unit:  reqs }}
+  destroy:         {{ samples:           0, unit:  reqs }}
+  create:          {{ samples:           0, unit:  reqs }}
+  statfs:          {{ samples:           0, unit:  reqs }}
+  get_info:        {{ samples:           0, unit:  reqs }}
+  set_info:        {{ samples:           0, unit:  reqs }}
+  quotactl:        {{ samples:           0, unit:  reqs }}
+"
+        )
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let x = [
+            ost_job("fs-OST0000", "dd.0", 4096, 8192),
+            ost_job("fs-OST0001", "dd.0", 1024, 0),
+            ost_job("fs-OST0001", "cp.0", 0, 512),
+        ]
+        .concat();
+
+        let records = parse_lctl_output(x.as_bytes()).unwrap();
+
+        let xs = aggregate(&records);
+
+        assert_eq!(
+            xs.iter().map(|x| x.job_id.as_str()).collect::<Vec<_>>(),
+            ["cp.0", "dd.0"]
+        );
+
+        let dd = &xs[1];
+
+        assert_eq!(dd.totals.read_bytes, 5120);
+        assert_eq!(dd.totals.write_bytes, 8192);
+        assert_eq!(dd.totals.ops["write_bytes"], 4);
+        assert_eq!(dd.totals.ops["punch"], 2);
+        assert_eq!(
+            dd.targets.iter().map(|x| &x.target.0).collect::<Vec<_>>(),
+            ["fs-OST0000", "fs-OST0001"]
+        );
+        assert_eq!(dd.targets[1].totals.read_bytes, 1024);
+    }
+
+    #[test]
+    fn test_aggregate_mdt() {
+        let xs = include_bytes!("./fixtures/valid/ex8761-lctl.txt");
+        let records = parse_lctl_output(xs).unwrap();
+
+        insta::assert_debug_snapshot!(aggregate(&records));
+    }
+}
@@ -112,72 +112,84 @@ where
                 target,
                 param: Param(p),
                 value,
+                snapshot_time: None,
             })),
             CONTENTION_SECONDS => Ok(TargetStats::ContentionSeconds(TargetStat {
                 kind,
                 target,
                 param: Param(p),
                 value,
+                snapshot_time: None,
             })),
             CTIME_AGE_LIMIT => Ok(TargetStats::CtimeAgeLimit(TargetStat {
                 kind,
                 target,
                 param: Param(p),
                 value,
+                snapshot_time: None,
             })),
             EARLY_LOCK_CANCEL => Ok(TargetStats::EarlyLockCancel(TargetStat {
                 kind,
                 target,
                 param: Param(p),
                 value,
+                snapshot_time: None,
             })),
             LOCK_COUNT => Ok(TargetStats::LockCount(TargetStat {
                 kind,
                 target,
                 param: Param(p),
                 value,
+                snapshot_time: None,
             })),
             LOCK_TIMEOUTS => Ok(TargetStats::LockTimeouts(TargetStat {
                 kind,
                 target,
                 param: Param(p),
                 value,
+                snapshot_time: None,
             })),
             LOCK_UNUSED_COUNT => Ok(TargetStats::LockUnusedCount(TargetStat {
                 kind,
                 target,
                 param: Param(p),
                 value,
+                snapshot_time: None,
             })),
             LRU_MAX_AGE => Ok(TargetStats::LruMaxAge(TargetStat {
                 kind,
                 target,
                 param: Param(p),
                 value,
+                snapshot_time: None,
             })),
             LRU_SIZE => Ok(TargetStats::LruSize(TargetStat {
                 kind,
                 target,
                 param: Param(p),
                 value,
+                snapshot_time: None,
             })),
             MAX_NOLOCK_BYTES => Ok(TargetStats::MaxNolockBytes(TargetStat {
                 kind,
                 target,
                 param: Param(p),
                 value,
+                snapshot_time: None,
             })),
             MAX_PARALLEL_AST => Ok(TargetStats::MaxParallelAst(TargetStat {
                 kind,
                 target,
                 param: Param(p),
                 value,
+                snapshot_time: None,
             })),
             RESOURCE_COUNT => Ok(TargetStats::ResourceCount(TargetStat {
                 kind,
                 target,
                 param: Param(p),
                 value,
+                snapshot_time: None,
             })),
             _ => Err(StreamErrorFor::<I>::unexpected_static_message(
                 "Unexpected top-level param",
@@ -41,7 +41,7 @@ where
 {
     attempt((string(LDLM_CANCELD), period(), param(STATS)))
         .with(stats())
-        .map(LustreServiceStats::LdlmCanceld)
+        .map(|(_, xs)| LustreServiceStats::LdlmCanceld(xs))
         .message("While parsing ldlm_canceld.stats")
 }
 
@@ -52,6 +52,6 @@ where
 {
     (string(LDLM_CBD), period(), param(STATS))
         .with(stats())
-        .map(LustreServiceStats::LdlmCbd)
+        .map(|(_, xs)| LustreServiceStats::LdlmCbd(xs))
         .message("While parsing ldlm_cbd.stats")
 }
@@ -0,0 +1,129 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Parser for the `key: value` output of `lfsck_namespace` and `lfsck_layout`.
+
+use crate::{
+    base_parsers::{till_newline, word},
+    types::LfsckStats,
+};
+use combine::{
+    attempt,
+    error::ParseError,
+    many,
+    parser::char::{newline, string},
+    stream::Stream,
+    token, Parser,
+};
+use std::collections::BTreeMap;
+
+fn field<I>() -> impl Parser<I, Output = (String, String)>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    attempt((word().skip(token(':')), till_newline().skip(newline())))
+        .map(|(k, v)| (k, v.trim().to_string()))
+}
+
+/// Takes the leading number of values like `12 seconds` or `1234 items/sec`,
+/// `N/A` gives `None`
+fn leading_number(x: Option<&String>) -> Option<u64> {
+    x?.split_whitespace().next()?.parse().ok()
+}
+
+impl From<BTreeMap<String, String>> for LfsckStats {
+    fn from(xs: BTreeMap<String, String>) -> Self {
+        let number = |k: &str| leading_number(xs.get(k));
+
+        LfsckStats {
+            status: xs
+                .get("status")
+                .map(|x| x.as_str())
+                .unwrap_or_default()
+                .into(),
+            success_count: number("success_count").unwrap_or_default(),
+            checked_phase1: number("checked_phase1").unwrap_or_default(),
+            checked_phase2: number("checked_phase2").unwrap_or_default(),
+            failed_phase1: number("failed_phase1").unwrap_or_default(),
+            failed_phase2: number("failed_phase2").unwrap_or_default(),
+            repaired: xs
+                .iter()
+                .filter(|(k, _)| k.contains("repaired"))
+                .filter_map(|(k, v)| Some((k.to_string(), v.parse().ok()?)))
+                .collect(),
+            run_time_phase1: number("run_time_phase1"),
+            run_time_phase2: number("run_time_phase2"),
+            average_speed_phase1: number("average_speed_phase1"),
+            average_speed_phase2: number("average_speed_phase2"),
+            real_time_speed_phase1: number("real_time_speed_phase1"),
+            real_time_speed_phase2: number("real_time_speed_phase2"),
+            time_since_last_completed: number("time_since_last_completed"),
+        }
+    }
+}
+
+pub(crate) fn lfsck<I>() -> impl Parser<I, Output = LfsckStats>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        newline(),
+        string("name:").skip(till_newline()).skip(newline()),
+        many(field()),
+    )
+        .map(|(_, _, xs): (_, _, BTreeMap<_, _>)| xs.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn test_lfsck_namespace() {
+        let x = r#"
+name: lfsck_namespace
+magic: 0xa06249ff
+version: 2
+status: scanning-phase1
+flags:
+param:
+last_completed_time: N/A
+time_since_last_completed: N/A
+latest_start_time: 1700000000
+time_since_latest_start: 12 seconds
+last_checkpoint_time: N/A
+time_since_last_checkpoint: N/A
+latest_start_position: 77, N/A, N/A
+last_checkpoint_position: N/A
+first_failure_position: N/A, N/A, N/A
+checked_phase1: 30211
+checked_phase2: 0
+updated_phase1: 0
+updated_phase2: 0
+failed_phase1: 0
+failed_phase2: 0
+directories: 2021
+dirent_repaired: 3
+linkea_repaired: 1
+nlinks_repaired: 0
+success_count: 0
+run_time_phase1: 12 seconds
+run_time_phase2: 0 seconds
+average_speed_phase1: 2517 items/sec
+average_speed_phase2: N/A
+average_speed_total: 2517 items/sec
+real_time_speed_phase1: 2611 items/sec
+real_time_speed_phase2: N/A
+current_position: 1203, [0x200000401:0x1:0x0], 0x0
+mdd.fs-MDT0000.lfsck_layout=
+"#;
+
+        let result = lfsck().parse(x);
+
+        assert_debug_snapshot!(result);
+    }
+}
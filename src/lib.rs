@@ -4,56 +4,138 @@
 
 mod base_parsers;
 pub(crate) mod brw_stats_parser;
+pub mod collector;
+pub mod device_list_parser;
 pub mod error;
 pub(crate) mod exports_parser;
+mod import_parser;
+mod job_stats_parser;
+pub mod jobid;
+pub mod jobs;
 pub(crate) mod ldlm;
+mod lfsck_parser;
 pub(crate) mod llite;
 mod lnetctl_parser;
+mod lod_parser;
+pub(crate) mod mdc;
 mod mdd_parser;
 mod mds;
 pub mod mgs;
 mod node_stats_parsers;
+pub(crate) mod osc;
 mod osd_parser;
 mod oss;
 pub mod parser;
+mod prometheus;
 pub(crate) mod quota;
+pub mod rates;
 pub mod recovery_status_parser;
+pub mod report;
+mod rpc_stats_parser;
+pub mod server;
 mod stats_parser;
+pub mod stream;
 mod time;
 mod top_level_parser;
+pub mod topology;
 pub mod types;
 
-pub use crate::error::LustreCollectorError;
-use combine::parser::EasyParser;
+pub use crate::error::{LustreCollectorError, ParseFailure};
+use combine::{
+    parser::EasyParser,
+    stream::{easy, PointerOffset},
+};
 pub use lnetctl_parser::parse as parse_lnetctl_output;
-pub use lnetctl_parser::parse_lnetctl_stats;
+pub use lnetctl_parser::{
+    parse_lnetctl_peer_output, parse_lnetctl_route_output, parse_lnetctl_routing_output,
+    parse_lnetctl_stats,
+};
 pub use node_stats_parsers::{parse_cpustats_output, parse_meminfo_output};
-use std::{io, str};
+pub use prometheus::{
+    render as render_prometheus, render_rates as render_rates_prometheus,
+    render_report as render_report_prometheus,
+};
+use std::str;
 pub use types::*;
 
-fn check_output(records: Vec<Record>, state: &str) -> Result<Vec<Record>, LustreCollectorError> {
-    let params = crate::parser::params().join(" ");
+/// Converts the error of an `easy_parse` of `input` into a [`ParseFailure`].
+pub(crate) fn parse_error(
+    input: &str,
+    err: easy::Errors<char, &str, PointerOffset<str>>,
+) -> LustreCollectorError {
+    let err = err.map_position(|p| p.translate_position(input));
 
+    LustreCollectorError::ParseError(ParseFailure::from_easy(input, &err))
+}
+
+/// Fails with a [`ParseFailure`] pointing at `state` if the parser
+/// didn't consume all of `input`.
+pub(crate) fn check_output<T>(
+    records: Vec<T>,
+    input: &str,
+    state: &str,
+) -> Result<Vec<T>, LustreCollectorError> {
     if !state.is_empty() {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            format!("Content left in input buffer. Please run and supply to support: `lctl get_param {params}`"),
-        )
-        .into());
+        return Err(LustreCollectorError::ParseError(ParseFailure::new(
+            input,
+            input.len() - state.len(),
+            vec!["end of input".to_string()],
+        )));
     }
 
     Ok(records)
 }
 
+/// Like [`check_output`] for `lctl get_param` output left over by
+/// [`parser::parse`].
+///
+/// `parse` stops at the first block it can't parse, so the block is parsed
+/// again on its own to report where inside it and why it fails.
+pub(crate) fn check_lctl_output(
+    records: Vec<Record>,
+    input: &str,
+    state: &str,
+) -> Result<Vec<Record>, LustreCollectorError> {
+    if state.is_empty() {
+        return Ok(records);
+    }
+
+    match parser::block().easy_parse(state) {
+        Err(err) => Err(parse_error(input, err)),
+        Ok(_) => check_output(records, input, state),
+    }
+}
+
+/// How to handle `lctl get_param` output that doesn't fully parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Fail on the first param block that doesn't parse.
+    #[default]
+    Strict,
+    /// Skip param blocks that don't parse, returning a [`Record::Diagnostic`] for each.
+    Lenient,
+}
+
+/// Parses `lctl get_param` output according to `mode`.
+pub fn parse_lctl_output_with(
+    lctl_output: &[u8],
+    mode: ParseMode,
+) -> Result<Vec<Record>, LustreCollectorError> {
+    match mode {
+        ParseMode::Strict => parse_lctl_output(lctl_output),
+        ParseMode::Lenient => Ok(parser::parse_lenient(str::from_utf8(lctl_output)?)),
+    }
+}
+
 /// Must be called with output of `lctl get_params` for all params returned from `parser::parse()`
 pub fn parse_lctl_output(lctl_output: &[u8]) -> Result<Vec<Record>, LustreCollectorError> {
     let lctl_stats = str::from_utf8(lctl_output)?;
 
     let (lctl_record, state) = parser::parse()
         .easy_parse(lctl_stats)
-        .map_err(|err| err.map_position(|p| p.translate_position(lctl_stats)))?;
+        .map_err(|err| parse_error(lctl_stats, err))?;
 
-    check_output(lctl_record, state)
+    check_lctl_output(lctl_record, lctl_stats, state)
 }
 
 pub fn parse_mgs_fs_output(mgs_fs_output: &[u8]) -> Result<Vec<Record>, LustreCollectorError> {
@@ -61,9 +143,9 @@ pub fn parse_mgs_fs_output(mgs_fs_output: &[u8]) -> Result<Vec<Record>, LustreCo
 
     let (mgs_fs_record, state) = mgs::mgs_fs_parser::parse()
         .easy_parse(mgs_fs)
-        .map_err(|err| err.map_position(|p| p.translate_position(mgs_fs)))?;
+        .map_err(|err| parse_error(mgs_fs, err))?;
 
-    check_output(mgs_fs_record, state)
+    check_output(mgs_fs_record, mgs_fs, state)
 }
 
 pub fn parse_recovery_status_output(
@@ -74,14 +156,41 @@ pub fn parse_recovery_status_output(
 
     let (recovery_statuses, state) = recovery_status_parser::parse()
         .easy_parse(recovery_status)
-        .map_err(|err| err.map_position(|p| p.translate_position(recovery_status)))?;
+        .map_err(|err| parse_error(recovery_status, err))?;
+
+    check_output(recovery_statuses, recovery_status, state)
+}
+
+/// Must be called with output of `lctl dl -t`
+pub fn parse_device_list_output(
+    device_list_output: &[u8],
+) -> Result<Vec<Record>, LustreCollectorError> {
+    let device_list = str::from_utf8(device_list_output)?;
+
+    let (devices, state) = device_list_parser::parse()
+        .easy_parse(device_list)
+        .map_err(|err| parse_error(device_list, err))?;
 
-    check_output(recovery_statuses, state)
+    check_output(devices, device_list, state)
+}
+
+/// Parses a `job_stats` block on its own, as printed by
+/// `lctl get_param -n obdfilter.*.job_stats`, keeping every operation.
+pub fn parse_job_stats_output(
+    job_stats_output: &[u8],
+) -> Result<Vec<JobStat>, LustreCollectorError> {
+    let job_stats = str::from_utf8(job_stats_output)?;
+
+    let (xs, state) = job_stats_parser::parse()
+        .easy_parse(job_stats)
+        .map_err(|err| parse_error(job_stats, err))?;
+
+    check_output(xs.unwrap_or_default(), job_stats, state)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_lctl_output, Record};
+    use super::{parse_job_stats_output, parse_lctl_output, LustreCollectorError, Record};
 
     #[test]
     fn ex8761_job_stats() {
@@ -111,4 +220,49 @@ mod tests {
 
         insta::assert_snapshot!(xs.join(" "));
     }
+
+    #[test]
+    fn parse_error_location() {
+        let xs = b"memused=1234\nobdfilter.fs-OST0000.kbytestotal=12x\nhealth_check=healthy\n";
+
+        let Err(LustreCollectorError::ParseError(e)) = parse_lctl_output(xs) else {
+            panic!("expected a parse error");
+        };
+
+        // obdfilter has no kbytestotal param, the failure is at its name
+        assert_eq!(e.param.as_deref(), Some("obdfilter.fs-OST0000.kbytestotal"));
+        assert_eq!((e.line, e.column), (2, 22));
+        assert_eq!(e.excerpt, "obdfilter.fs-OST0000.kbytestotal=12x");
+        assert!(e.expected.contains(&"job_stats".to_string()));
+        assert!(e.expected.contains(&"num_exports".to_string()));
+
+        insta::assert_snapshot!(e.explain());
+
+        let xs = b"memused=1234\nosd-ldiskfs.fs-OST0000.kbytestotal=12x\nhealth_check=healthy\n";
+
+        let Err(LustreCollectorError::ParseError(e)) = parse_lctl_output(xs) else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!((e.line, e.column), (2, 38));
+        assert!(e.expected.contains(&"lf newline".to_string()));
+    }
+
+    #[test]
+    fn job_stats_keeps_all_ops() {
+        let xs = b"job_stats:
+- job_id:          ls.0
+  snapshot_time:   1700000000
+  open:            { samples:           2, unit: usecs, min:        3, max:        9, sum:               12, sumsq:                 90 }
+  migrate:         { samples:           1, unit: usecs, min:        7, max:        7, sum:                7, sumsq:                 49 }
+";
+
+        let xs = parse_job_stats_output(xs).unwrap();
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].ops.keys().collect::<Vec<_>>(), ["migrate", "open"]);
+        assert_eq!(xs[0].ops["open"].sumsq, Some(90));
+
+        assert_eq!(parse_job_stats_output(b"job_stats:\n").unwrap(), []);
+    }
 }
@@ -2,21 +2,42 @@
 // Use of this source code is governed by a MIT-style
 // license that can be found in the LICENSE file.
 
+mod rw_stats_parser;
+
 use crate::{
-    base_parsers::{param, period, target},
+    base_parsers::{digits, param, period, target, yaml_block},
     stats_parser::stats,
-    Param, Record, Stat, Target, TargetStats,
+    types::{LliteMaxCachedMb, LliteOffsetStat, LliteTargetStat, UnsignedLustreTimestamp},
+    BrwStats, Param, Record, Stat, Target, TargetStats,
+};
+use combine::{
+    choice,
+    error::StreamError,
+    parser::char::{newline, string},
+    stream::StreamErrorFor,
+    ParseError, Parser, Stream,
 };
-use combine::{parser::char::string, ParseError, Parser, Stream};
 
 pub(crate) const LLITE: &str = "llite";
 pub(crate) const STATS: &str = "stats";
+pub(crate) const READ_AHEAD_STATS: &str = "read_ahead_stats";
+pub(crate) const EXTENTS_STATS: &str = "extents_stats";
+pub(crate) const OFFSET_STATS: &str = "offset_stats";
+pub(crate) const MAX_CACHED_MB: &str = "max_cached_mb";
+pub(crate) const MAX_READ_AHEAD_MB: &str = "max_read_ahead_mb";
 
 pub(crate) fn params() -> Vec<String> {
-    [STATS]
-        .into_iter()
-        .map(|x| format!("{LLITE}.*.{x}"))
-        .collect()
+    [
+        STATS,
+        READ_AHEAD_STATS,
+        EXTENTS_STATS,
+        OFFSET_STATS,
+        MAX_CACHED_MB,
+        MAX_READ_AHEAD_MB,
+    ]
+    .into_iter()
+    .map(|x| format!("{LLITE}.*.{x}"))
+    .collect()
 }
 
 fn target_name<I>() -> impl Parser<I, Output = Target>
@@ -30,7 +51,20 @@ where
 }
 
 enum LliteStat {
-    Stats(Vec<Stat>),
+    Stats(Option<UnsignedLustreTimestamp>, Vec<Stat>),
+    ReadAheadStats(Option<UnsignedLustreTimestamp>, Vec<Stat>),
+    ExtentsStats(Option<UnsignedLustreTimestamp>, Option<BrwStats>),
+    OffsetStats(Option<Vec<LliteOffsetStat>>),
+    MaxCachedMb(LliteMaxCachedMb),
+    MaxReadAheadMb(u64),
+}
+
+fn max_cached_mb<I>() -> impl Parser<I, Output = LliteMaxCachedMb>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    yaml_block().and_then(|x: String| serde_yaml::from_str(&x).map_err(StreamErrorFor::<I>::other))
 }
 
 fn llite_stat<I>() -> impl Parser<I, Output = (Param, LliteStat)>
@@ -38,7 +72,33 @@ where
     I: Stream<Token = char>,
     I::Error: ParseError<I::Token, I::Range, I::Position>,
 {
-    (param(STATS), stats().map(LliteStat::Stats)).message("while parsing llite_stat")
+    choice((
+        (
+            param(STATS),
+            stats().map(|(time, xs)| LliteStat::Stats(time, xs)),
+        ),
+        (
+            param(READ_AHEAD_STATS),
+            stats().map(|(time, xs)| LliteStat::ReadAheadStats(time, xs)),
+        ),
+        (
+            param(EXTENTS_STATS),
+            rw_stats_parser::extents_stats().map(|(time, x)| LliteStat::ExtentsStats(time, x)),
+        ),
+        (
+            param(OFFSET_STATS),
+            rw_stats_parser::offset_stats().map(LliteStat::OffsetStats),
+        ),
+        (
+            param(MAX_CACHED_MB),
+            max_cached_mb().map(LliteStat::MaxCachedMb),
+        ),
+        (
+            param(MAX_READ_AHEAD_MB),
+            digits().skip(newline()).map(LliteStat::MaxReadAheadMb),
+        ),
+    ))
+    .message("while parsing llite_stat")
 }
 
 pub(crate) fn parse<I>() -> impl Parser<I, Output = Record>
@@ -48,10 +108,45 @@ where
 {
     (target_name(), llite_stat())
         .map(|(target, (param, value))| match value {
-            LliteStat::Stats(stats) => TargetStats::Llite(crate::types::LliteStat {
+            LliteStat::Stats(snapshot_time, stats) => TargetStats::Llite(crate::types::LliteStat {
                 target,
                 param,
                 stats,
+                snapshot_time,
+            }),
+            LliteStat::ReadAheadStats(snapshot_time, value) => {
+                TargetStats::LliteReadAheadStats(LliteTargetStat {
+                    target,
+                    param,
+                    value,
+                    snapshot_time,
+                })
+            }
+            LliteStat::ExtentsStats(snapshot_time, value) => {
+                TargetStats::LliteExtentsStats(LliteTargetStat {
+                    target,
+                    param,
+                    value,
+                    snapshot_time,
+                })
+            }
+            LliteStat::OffsetStats(value) => TargetStats::LliteOffsetStats(LliteTargetStat {
+                target,
+                param,
+                value,
+                snapshot_time: None,
+            }),
+            LliteStat::MaxCachedMb(value) => TargetStats::LliteMaxCachedMb(LliteTargetStat {
+                target,
+                param,
+                value,
+                snapshot_time: None,
+            }),
+            LliteStat::MaxReadAheadMb(value) => TargetStats::LliteMaxReadAheadMb(LliteTargetStat {
+                target,
+                param,
+                value,
+                snapshot_time: None,
             }),
         })
         .map(Record::Target)
@@ -81,6 +176,33 @@ statfs                    7 samples [usec] 147 197 1236 220284
 inode_permission          251887103 samples [usec] 0 14235 178199279 1102415701
 opencount                 13812424 samples [reqs] 1 2 20718632 34531048
 openclosetime             6906208 samples [usec] 2225920 34405427 163169641155255 11416538743473681487
+llite.ai400x2-ffff9440f1003000.read_ahead_stats=
+snapshot_time             1689697369.331040915 secs.nsecs
+start_time                1689000000.000000000 secs.nsecs
+elapsed_time              697369.331040915 secs.nsecs
+hits                      1228131 samples [pages]
+misses                    3204 samples [pages]
+readpage_not_consecutive  512 samples [pages]
+miss_inside_window        12 samples [pages]
+zero_size_window          8 samples [pages]
+failed_to_reach_end       2201 samples [pages]
+async_readahead           1100 samples [pages]
+llite.ai400x2-ffff9440f1003000.extents_stats=
+snapshot_time:         1689697369.331040915 (secs.nsecs)
+                           read       |                write
+      extents          calls    % cum%   |          calls    % cum%
+   0K -    4K :          4   40   40   |              0    0    0
+   4K -    8K :          6   60  100   |              1  100  100
+llite.ai400x2-ffff9440f1003000.offset_stats=
+disabled
+ write anything to this file to activate, then '0' or 'disable' to deactivate
+llite.ai400x2-ffff9440f1003000.max_cached_mb=
+users: 2
+max_cached_mb: 31963
+used_mb: 1234
+unused_mb: 30729
+reclaim_count: 0
+llite.ai400x2-ffff9440f1003000.max_read_ahead_mb=1024
 "#;
 
         let result: (Vec<_>, _) = many(parse()).parse(x).unwrap();
@@ -0,0 +1,241 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Parsers for `llite.*.extents_stats` and `llite.*.offset_stats`.
+//!
+//! Both are only collected after being enabled on the client, and print a
+//! `disabled` notice otherwise.
+
+use crate::{
+    base_parsers::{digits, till_newline, word},
+    brw_stats_parser::human_to_bytes,
+    time::{time_triple, to_timestamp},
+    types::{BrwStats, BrwStatsBucket, LliteOffsetStat, UnsignedLustreTimestamp},
+};
+use combine::{
+    attempt,
+    error::StreamError,
+    many, many1, one_of, optional,
+    parser::char::{digit, newline, spaces, string},
+    skip_many, skip_many1,
+    stream::StreamErrorFor,
+    token, ParseError, Parser, Stream,
+};
+
+/// Parses the notice printed while rw stats are disabled
+fn disabled<I>() -> impl Parser<I, Output = ()>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        string("disabled").skip(till_newline()).skip(newline()),
+        many::<Vec<_>, _, _>(attempt(token(' ').skip(till_newline()).skip(newline()))),
+    )
+        .map(drop)
+}
+
+fn blanks<I>() -> impl Parser<I, Output = ()>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    skip_many(one_of(" \t".chars()))
+}
+
+fn size<I>() -> impl Parser<I, Output = u64>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    digits()
+        .and(optional(one_of("KkMmGg".chars())))
+        .map(human_to_bytes)
+}
+
+/// Parses an extents bucket such as `   4K -    8K :   0    0    0   |   2   50   50`,
+/// named by its upper bound
+fn extents_bucket<I>() -> impl Parser<I, Output = BrwStatsBucket>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    attempt((
+        blanks().with(size()),
+        blanks().skip(token('-')),
+        blanks().with(size()),
+        blanks().skip(token(':')),
+        spaces().with(digits()),
+        spaces().with(digits()),
+        spaces().with(digits()),
+        spaces().skip(token('|')),
+        spaces().with(digits()),
+        till_newline(),
+    ))
+    .map(|(_, _, name, _, read, _, _, _, write, _)| BrwStatsBucket { name, read, write })
+}
+
+fn extents<I>() -> impl Parser<I, Output = (Option<UnsignedLustreTimestamp>, BrwStats)>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        time_triple(),
+        (blanks(), string("read"), till_newline(), newline()),
+        (blanks(), string("extents"), blanks()).with(word()),
+        till_newline().skip(newline()),
+        many(extents_bucket().skip(newline())),
+    )
+        .map(|(time, _, unit, _, buckets)| {
+            (
+                to_timestamp(&time),
+                BrwStats {
+                    name: "extents".to_string(),
+                    unit,
+                    buckets,
+                },
+            )
+        })
+}
+
+/// Parses `extents_stats` into its `snapshot_time` and histogram, both
+/// absent while the stats are disabled.
+pub(crate) fn extents_stats<I>(
+) -> impl Parser<I, Output = (Option<UnsignedLustreTimestamp>, Option<BrwStats>)>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    newline().with(
+        disabled()
+            .map(|_| (None, None))
+            .or(extents().map(|(time, x)| (time, Some(x)))),
+    )
+}
+
+fn signed<I>() -> impl Parser<I, Output = i64>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (optional(token('-')), many1::<String, _, _>(digit())).and_then(|(sign, x)| {
+        format!("{}{x}", sign.map(String::from).unwrap_or_default())
+            .parse::<i64>()
+            .map_err(StreamErrorFor::<I>::other)
+    })
+}
+
+fn offset_row<I>() -> impl Parser<I, Output = LliteOffsetStat>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    attempt((
+        blanks().with(one_of("RW".chars())),
+        skip_many1(one_of(" \t".chars())).with(digits()),
+        blanks().with(digits()),
+        blanks().with(digits()),
+        blanks().with(digits()),
+        blanks().with(digits()),
+        blanks().with(signed()),
+        till_newline(),
+    ))
+    .map(
+        |(op, pid, range_start, range_end, smallest_extent, largest_extent, offset, _)| {
+            LliteOffsetStat {
+                op,
+                pid,
+                range_start,
+                range_end,
+                smallest_extent,
+                largest_extent,
+                offset,
+            }
+        },
+    )
+}
+
+fn offsets<I>() -> impl Parser<I, Output = Vec<LliteOffsetStat>>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        time_triple(),
+        optional(attempt((
+            blanks(),
+            string("RANGE"),
+            till_newline(),
+            newline(),
+        ))),
+        (blanks(), string("R/W"), till_newline(), newline()),
+        many(offset_row().skip(newline())),
+    )
+        .map(|(_, _, _, xs)| xs)
+}
+
+pub(crate) fn offset_stats<I>() -> impl Parser<I, Output = Option<Vec<LliteOffsetStat>>>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    newline().with(disabled().map(|_| None).or(offsets().map(Some)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn test_extents_stats() {
+        let x = r#"
+snapshot_time:         1700000000.123456789 (secs.nsecs)
+                           read       |                write
+      extents          calls    % cum%   |          calls    % cum%
+   0K -    4K :          0    0    0   |              2    2    2
+   4K -    8K :          1   50   50   |              0    0    2
+ 512K - 1024K :          0    0   50   |             87   85   87
+   1M -    2M :          1   50  100   |             13   13  100
+"#;
+
+        let result = extents_stats().parse(x);
+
+        assert_debug_snapshot!(result);
+    }
+
+    #[test]
+    fn test_offset_stats() {
+        let x = r#"
+snapshot_time:         1700000000.123456789 (secs.nsecs)
+R/W        PID    RANGE START      RANGE END   SMALLEST EXTENT    LARGEST EXTENT         OFFSET
+  R       8385              0            128               128               128              0
+  R       8385              0            224               224               224           -128
+  W       8385              0            250                50               100              0
+"#;
+
+        let result = offset_stats().parse(x);
+
+        assert_debug_snapshot!(result);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let x = r#"
+disabled
+ write anything to this file to activate, then '0' or 'disable' to deactivate
+llite.fs-ffff8dc8f8d4b000.offset_stats=
+"#;
+
+        assert_eq!(
+            extents_stats().parse(x),
+            Ok(((None, None), "llite.fs-ffff8dc8f8d4b000.offset_stats=\n"))
+        );
+        assert_eq!(
+            offset_stats().parse(x),
+            Ok((None, "llite.fs-ffff8dc8f8d4b000.offset_stats=\n"))
+        );
+    }
+}
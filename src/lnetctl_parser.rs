@@ -4,7 +4,11 @@
 
 use crate::{
     lnet_exports::LNetStatsStatistics,
-    types::{lnet_exports::Net, LNetStat, LNetStats, Param, Record},
+    types::{
+        lnet_exports::{Net, Peer, PeerNi, Route, Routing},
+        LNetNiCpt, LNetNiStatus, LNetPeerStat, LNetRouteStat, LNetRouterBufferStat, LNetStat,
+        LNetStats, Param, Record,
+    },
     LNetStatGlobal, LustreCollectorError,
 };
 
@@ -33,6 +37,41 @@ pub(crate) fn build_lnet_stats(x: &Net) -> Vec<Record> {
                     param: Param("drop_count".to_string()),
                     value: y.statistics.drop_count,
                 }),
+                LNetStats::SentStats(LNetStat {
+                    nid: y.nid.to_string(),
+                    param: Param("sent_stats".to_string()),
+                    value: y.sent_stats.clone(),
+                }),
+                LNetStats::ReceivedStats(LNetStat {
+                    nid: y.nid.to_string(),
+                    param: Param("received_stats".to_string()),
+                    value: y.received_stats.clone(),
+                }),
+                LNetStats::DroppedStats(LNetStat {
+                    nid: y.nid.to_string(),
+                    param: Param("dropped_stats".to_string()),
+                    value: y.dropped_stats.clone(),
+                }),
+                LNetStats::Health(LNetStat {
+                    nid: y.nid.to_string(),
+                    param: Param("health_stats".to_string()),
+                    value: y.health_stats.clone(),
+                }),
+                LNetStats::Status(LNetStat {
+                    nid: y.nid.to_string(),
+                    param: Param("status".to_string()),
+                    value: LNetNiStatus::from(y.status.as_str()),
+                }),
+                LNetStats::Tunables(LNetStat {
+                    nid: y.nid.to_string(),
+                    param: Param("tunables".to_string()),
+                    value: y.tunables.clone(),
+                }),
+                LNetStats::Cpt(LNetStat {
+                    nid: y.nid.to_string(),
+                    param: Param("cpt".to_string()),
+                    value: LNetNiCpt::from(y),
+                }),
             ]
         })
         .map(Record::LNetStat)
@@ -72,6 +111,90 @@ pub(crate) fn build_lnetctl_stats(x: &LNetStatsStatistics) -> Vec<Record> {
             param: Param("drop_length".to_string()),
             value: x.drop_length,
         })),
+        Record::LNetStat(LNetStats::MsgsAlloc(LNetStatGlobal {
+            param: Param("msgs_alloc".to_string()),
+            value: x.msgs_alloc,
+        })),
+        Record::LNetStat(LNetStats::MsgsMax(LNetStatGlobal {
+            param: Param("msgs_max".to_string()),
+            value: x.msgs_max,
+        })),
+        Record::LNetStat(LNetStats::RstAlloc(LNetStatGlobal {
+            param: Param("rst_alloc".to_string()),
+            value: x.rst_alloc,
+        })),
+        Record::LNetStat(LNetStats::Errors(LNetStatGlobal {
+            param: Param("errors".to_string()),
+            value: x.errors,
+        })),
+        Record::LNetStat(LNetStats::GlobalSendCount(LNetStatGlobal {
+            param: Param("send_count".to_string()),
+            value: x.send_count,
+        })),
+        Record::LNetStat(LNetStats::ResendCount(LNetStatGlobal {
+            param: Param("resend_count".to_string()),
+            value: x.resend_count,
+        })),
+        Record::LNetStat(LNetStats::ResponseTimeoutCount(LNetStatGlobal {
+            param: Param("response_timeout_count".to_string()),
+            value: x.response_timeout_count,
+        })),
+        Record::LNetStat(LNetStats::LocalInterruptCount(LNetStatGlobal {
+            param: Param("local_interrupt_count".to_string()),
+            value: x.local_interrupt_count,
+        })),
+        Record::LNetStat(LNetStats::LocalDroppedCount(LNetStatGlobal {
+            param: Param("local_dropped_count".to_string()),
+            value: x.local_dropped_count,
+        })),
+        Record::LNetStat(LNetStats::LocalAbortedCount(LNetStatGlobal {
+            param: Param("local_aborted_count".to_string()),
+            value: x.local_aborted_count,
+        })),
+        Record::LNetStat(LNetStats::LocalNoRouteCount(LNetStatGlobal {
+            param: Param("local_no_route_count".to_string()),
+            value: x.local_no_route_count,
+        })),
+        Record::LNetStat(LNetStats::LocalTimeoutCount(LNetStatGlobal {
+            param: Param("local_timeout_count".to_string()),
+            value: x.local_timeout_count,
+        })),
+        Record::LNetStat(LNetStats::LocalErrorCount(LNetStatGlobal {
+            param: Param("local_error_count".to_string()),
+            value: x.local_error_count,
+        })),
+        Record::LNetStat(LNetStats::RemoteDroppedCount(LNetStatGlobal {
+            param: Param("remote_dropped_count".to_string()),
+            value: x.remote_dropped_count,
+        })),
+        Record::LNetStat(LNetStats::RemoteErrorCount(LNetStatGlobal {
+            param: Param("remote_error_count".to_string()),
+            value: x.remote_error_count,
+        })),
+        Record::LNetStat(LNetStats::RemoteTimeoutCount(LNetStatGlobal {
+            param: Param("remote_timeout_count".to_string()),
+            value: x.remote_timeout_count,
+        })),
+        Record::LNetStat(LNetStats::NetworkTimeoutCount(LNetStatGlobal {
+            param: Param("network_timeout_count".to_string()),
+            value: x.network_timeout_count,
+        })),
+        Record::LNetStat(LNetStats::GlobalRecvCount(LNetStatGlobal {
+            param: Param("recv_count".to_string()),
+            value: x.recv_count,
+        })),
+        Record::LNetStat(LNetStats::RouteCount(LNetStatGlobal {
+            param: Param("route_count".to_string()),
+            value: x.route_count,
+        })),
+        Record::LNetStat(LNetStats::GlobalDropCount(LNetStatGlobal {
+            param: Param("drop_count".to_string()),
+            value: x.drop_count,
+        })),
+        Record::LNetStat(LNetStats::RouteLength(LNetStatGlobal {
+            param: Param("route_length".to_string()),
+            value: x.route_length,
+        })),
     ]
 }
 
@@ -89,6 +212,177 @@ pub fn parse_lnetctl_stats(x: &str) -> Result<Vec<Record>, LustreCollectorError>
         .unwrap_or_default())
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LnetPeerStats {
+    peer: Option<Vec<Peer>>,
+}
+
+fn peer_stat<T>(x: &Peer, y: &PeerNi, param: &str, value: T) -> LNetPeerStat<T> {
+    LNetPeerStat {
+        primary_nid: x.primary_nid.to_string(),
+        nid: y.nid.to_string(),
+        param: Param(param.to_string()),
+        value,
+    }
+}
+
+pub(crate) fn build_lnet_peer_stats(x: &Peer) -> Vec<Record> {
+    x.peer_ni
+        .iter()
+        .flat_map(|y| {
+            let mut xs = vec![
+                LNetStats::PeerMaxNiTxCredits(peer_stat(
+                    x,
+                    y,
+                    "max_ni_tx_credits",
+                    y.max_ni_tx_credits,
+                )),
+                LNetStats::PeerAvailableTxCredits(peer_stat(
+                    x,
+                    y,
+                    "available_tx_credits",
+                    y.available_tx_credits,
+                )),
+                LNetStats::PeerMinTxCredits(peer_stat(x, y, "min_tx_credits", y.min_tx_credits)),
+                LNetStats::PeerTxQNumOfBuf(peer_stat(x, y, "tx_q_num_of_buf", y.tx_q_num_of_buf)),
+                LNetStats::PeerAvailableRtrCredits(peer_stat(
+                    x,
+                    y,
+                    "available_rtr_credits",
+                    y.available_rtr_credits,
+                )),
+                LNetStats::PeerMinRtrCredits(peer_stat(x, y, "min_rtr_credits", y.min_rtr_credits)),
+                LNetStats::PeerState(peer_stat(
+                    x,
+                    y,
+                    "state",
+                    LNetNiStatus::from(y.state.as_str()),
+                )),
+            ];
+
+            if let Some(health_stats) = &y.health_stats {
+                xs.push(LNetStats::PeerHealth(peer_stat(
+                    x,
+                    y,
+                    "health_stats",
+                    health_stats.clone(),
+                )));
+            }
+
+            xs
+        })
+        .map(Record::LNetStat)
+        .collect()
+}
+
+/// Must be called with the output of `lnetctl peer show -v 3`
+pub fn parse_lnetctl_peer_output(x: &str) -> Result<Vec<Record>, LustreCollectorError> {
+    let x = x.trim();
+
+    if x.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let y: LnetPeerStats = serde_yaml::from_str(x)?;
+
+    Ok(y.peer
+        .map(|x| x.iter().flat_map(build_lnet_peer_stats).collect())
+        .unwrap_or_default())
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LnetRouteStats {
+    route: Option<Vec<Route>>,
+}
+
+pub(crate) fn build_lnet_route_stats(x: &Route) -> Vec<Record> {
+    vec![
+        LNetStats::RouteState(LNetRouteStat {
+            net: x.net.to_string(),
+            gateway: x.gateway.to_string(),
+            hop: x.hop,
+            param: Param("state".to_string()),
+            value: LNetNiStatus::from(x.state.as_str()),
+        }),
+        LNetStats::RoutePriority(LNetRouteStat {
+            net: x.net.to_string(),
+            gateway: x.gateway.to_string(),
+            hop: x.hop,
+            param: Param("priority".to_string()),
+            value: x.priority,
+        }),
+    ]
+    .into_iter()
+    .map(Record::LNetStat)
+    .collect()
+}
+
+/// Must be called with the output of `lnetctl route show -v`
+pub fn parse_lnetctl_route_output(x: &str) -> Result<Vec<Record>, LustreCollectorError> {
+    let x = x.trim();
+
+    if x.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let y: LnetRouteStats = serde_yaml::from_str(x)?;
+
+    Ok(y.route
+        .map(|x| x.iter().flat_map(build_lnet_route_stats).collect())
+        .unwrap_or_default())
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LnetRoutingStats {
+    routing: Option<Vec<Routing>>,
+}
+
+pub(crate) fn build_lnet_routing_stats(x: &Routing) -> Vec<Record> {
+    match x {
+        Routing::Enable { enable } => vec![LNetStats::RoutingEnabled(LNetStatGlobal {
+            param: Param("enable".to_string()),
+            value: *enable != 0,
+        })],
+        Routing::Cpt(xs) => xs
+            .iter()
+            .filter_map(|(cpt, pools)| {
+                let cpt = cpt
+                    .strip_prefix("cpt[")
+                    .and_then(|x| x.strip_suffix(']'))
+                    .and_then(|x| x.parse::<u64>().ok())?;
+
+                Some(pools.iter().map(move |(pool, value)| {
+                    LNetStats::RouterBuffers(LNetRouterBufferStat {
+                        cpt,
+                        pool: pool.to_string(),
+                        param: Param("buffers".to_string()),
+                        value: value.clone(),
+                    })
+                }))
+            })
+            .flatten()
+            .collect(),
+    }
+    .into_iter()
+    .map(Record::LNetStat)
+    .collect()
+}
+
+/// Must be called with the output of `lnetctl routing show`
+pub fn parse_lnetctl_routing_output(x: &str) -> Result<Vec<Record>, LustreCollectorError> {
+    let x = x.trim();
+
+    if x.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let y: LnetRoutingStats = serde_yaml::from_str(x)?;
+
+    Ok(y.routing
+        .map(|x| x.iter().flat_map(build_lnet_routing_stats).collect())
+        .unwrap_or_default())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -494,4 +788,182 @@ mod tests {
 
         assert_debug_snapshot!(x);
     }
+
+    #[test]
+    fn test_lnet_peer_parse() {
+        let x = parse_lnetctl_peer_output(
+            r#"peer:
+    - primary nid: 10.73.20.21@tcp
+      Multi-Rail: True
+      peer ni:
+        - nid: 10.73.20.21@tcp
+          state: up
+          max_ni_tx_credits: 8
+          available_tx_credits: 8
+          min_tx_credits: -3
+          tx_q_num_of_buf: 0
+          available_rtr_credits: 8
+          min_rtr_credits: 8
+          refcount: 4
+          statistics:
+              send_count: 3470
+              recv_count: 3470
+              drop_count: 0
+          sent_stats:
+              put: 3450
+              get: 20
+              reply: 0
+              ack: 0
+              hello: 0
+          received_stats:
+              put: 3447
+              get: 0
+              reply: 20
+              ack: 3
+              hello: 0
+          dropped_stats:
+              put: 0
+              get: 0
+              reply: 0
+              ack: 0
+              hello: 0
+          health stats:
+              health value: 1000
+              dropped: 0
+              timeout: 0
+              error: 0
+              network timeout: 0
+        - nid: 10.73.30.21@tcp1
+          state: down
+          max_ni_tx_credits: 8
+          available_tx_credits: 8
+          min_tx_credits: 8
+          tx_q_num_of_buf: 0
+          available_rtr_credits: 8
+          min_rtr_credits: 8
+          refcount: 1
+          statistics:
+              send_count: 0
+              recv_count: 0
+              drop_count: 0
+          sent_stats:
+              put: 0
+              get: 0
+              reply: 0
+              ack: 0
+              hello: 0
+          received_stats:
+              put: 0
+              get: 0
+              reply: 0
+              ack: 0
+              hello: 0
+          dropped_stats:
+              put: 0
+              get: 0
+              reply: 0
+              ack: 0
+              hello: 0
+          health stats:
+              health value: 0
+              dropped: 2
+              timeout: 14
+              error: 0
+              network timeout: 3
+    - primary nid: 0@lo
+      Multi-Rail: False
+      peer ni:
+        - nid: 0@lo
+          state: NA
+          max_ni_tx_credits: 0
+          available_tx_credits: 0
+          min_tx_credits: 0
+          tx_q_num_of_buf: 0
+          available_rtr_credits: 0
+          min_rtr_credits: 0
+          refcount: 1
+"#,
+        )
+        .unwrap();
+
+        assert_debug_snapshot!(x);
+    }
+
+    #[test]
+    fn test_lnet_peer_empty_input() {
+        assert_eq!(parse_lnetctl_peer_output("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_lnet_route_parse() {
+        let x = parse_lnetctl_route_output(
+            r#"route:
+    - net: o2ib1
+      gateway: 10.73.20.31@tcp
+      hop: -1
+      priority: 0
+      health_sensitivity: 1
+      state: up
+    - net: o2ib1
+      gateway: 10.73.20.32@tcp
+      hop: 2
+      priority: 1
+      health_sensitivity: 1
+      state: down
+"#,
+        )
+        .unwrap();
+
+        assert_debug_snapshot!(x);
+    }
+
+    #[test]
+    fn test_lnet_routing_parse() {
+        let x = parse_lnetctl_routing_output(
+            r#"routing:
+    - cpt[0]:
+          tiny:
+              npages: 0
+              nbuffers: 2048
+              credits: 2048
+              mincredits: 2040
+          small:
+              npages: 1
+              nbuffers: 16384
+              credits: 16384
+              mincredits: 16100
+          large:
+              npages: 256
+              nbuffers: 1024
+              credits: 1022
+              mincredits: 998
+    - cpt[1]:
+          tiny:
+              npages: 0
+              nbuffers: 2048
+              credits: 2048
+              mincredits: 2048
+          small:
+              npages: 1
+              nbuffers: 16384
+              credits: 16384
+              mincredits: 16384
+          large:
+              npages: 256
+              nbuffers: 1024
+              credits: 1024
+              mincredits: 1024
+    - enable: 1
+"#,
+        )
+        .unwrap();
+
+        assert_debug_snapshot!(x);
+    }
+
+    #[test]
+    fn test_lnet_route_empty_input() {
+        assert_eq!(parse_lnetctl_route_output("").unwrap(), vec![]);
+        assert_eq!(parse_lnetctl_routing_output("").unwrap(), vec![]);
+    }
 }
@@ -0,0 +1,111 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+use crate::{
+    base_parsers::{equals, period, target},
+    types::{OstPool, Param, Record, Target, TargetStat, TargetStats, TargetVariant},
+};
+use combine::{
+    attempt,
+    error::{ParseError, StreamError},
+    many,
+    parser::char::{newline, string},
+    stream::{Stream, StreamErrorFor},
+    Parser,
+};
+
+pub(crate) const LOD: &str = "lod";
+pub(crate) const POOLS: &str = "pools";
+
+pub(crate) fn params() -> Vec<String> {
+    vec![format!("{LOD}.*.{POOLS}.*")]
+}
+
+/// Parses a `fs-MDT0000-mdtlov` device into the MDT it belongs to.
+fn target_name<I>() -> impl Parser<I, Output = Target>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        attempt(string(LOD).skip(period())),
+        target()
+            .and_then(|Target(x)| {
+                x.strip_suffix("-mdtlov")
+                    .map(|x| Target(x.to_string()))
+                    .ok_or_else(|| StreamErrorFor::<I>::expected_static_message("lod device"))
+            })
+            .skip(period()),
+    )
+        .map(|(_, x)| x)
+        .message("while parsing lod target_name")
+}
+
+/// Parses the `fs-OST0000_UUID` members of a pool, one per line.
+fn osts<I>() -> impl Parser<I, Output = Vec<Target>>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    many(attempt(target().skip(newline()).and_then(|Target(x)| {
+        x.strip_suffix("_UUID")
+            .map(|x| Target(x.to_string()))
+            .ok_or_else(|| StreamErrorFor::<I>::expected_static_message("OST UUID"))
+    })))
+}
+
+fn pool<I>() -> impl Parser<I, Output = OstPool>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        string(POOLS).skip(period()),
+        target().skip(equals()).skip(newline()),
+        osts(),
+    )
+        .map(|(_, Target(name), osts)| OstPool { name, osts })
+        .message("while parsing lod pool")
+}
+
+pub(crate) fn parse<I>() -> impl Parser<I, Output = Record>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (target_name(), pool())
+        .map(|(target, value)| {
+            TargetStats::OstPool(TargetStat {
+                kind: TargetVariant::Mdt,
+                param: Param(POOLS.to_string()),
+                target,
+                value,
+                snapshot_time: None,
+            })
+        })
+        .map(Record::Target)
+        .message("while parsing lod")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use combine::{many, EasyParser};
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn test_pools() {
+        let x = r#"lod.fs-MDT0000-mdtlov.pools.fast=
+fs-OST0000_UUID
+fs-OST0001_UUID
+lod.fs-MDT0000-mdtlov.pools.empty=
+lod.fs-MDT0000-mdtlov.pools.slow=
+fs-OST0002_UUID
+"#;
+
+        let result: (Vec<_>, _) = many(parse()).easy_parse(x).unwrap();
+
+        assert_debug_snapshot!(result);
+    }
+}
@@ -2,17 +2,27 @@
 // Use of this source code is governed by a MIT-style
 // license that can be found in the LICENSE file.
 
-use clap::{value_parser, Arg, ValueEnum};
+use clap::{error::ErrorKind, value_parser, Arg, ArgMatches, ValueEnum};
 use lustre_collector::{
-    error::LustreCollectorError, mgs::mgs_fs_parser, parse_lctl_output, parse_lnetctl_output,
-    parse_lnetctl_stats, parse_mgs_fs_output, parse_recovery_status_output, parser,
-    recovery_status_parser, types::Record,
+    collector::{collect, CommandRunner, LocalRunner, ReplayRunner},
+    error::LustreCollectorError,
+    parse_device_list_output, parse_lctl_output_with, parse_lnetctl_output,
+    parse_lnetctl_peer_output, parse_lnetctl_route_output, parse_lnetctl_routing_output,
+    parse_lnetctl_stats, parse_mgs_fs_output, parse_recovery_status_output, parser, rates,
+    render_prometheus, render_rates_prometheus, render_report_prometheus, report,
+    server::{Server, METRICS_PATH},
+    types::Record,
+    ParseMode,
 };
 use std::{
-    fmt, panic,
-    process::{Command, ExitCode},
+    fmt, fs,
+    io::{self, Read},
+    net::{SocketAddr, TcpListener},
+    path::PathBuf,
+    process::ExitCode,
     str::{self, FromStr},
     thread,
+    time::Duration,
 };
 use tracing::debug;
 
@@ -20,6 +30,7 @@ use tracing::debug;
 enum Format {
     Json,
     Yaml,
+    Prometheus,
 }
 
 impl FromStr for Format {
@@ -29,6 +40,7 @@ impl FromStr for Format {
         match s.to_lowercase().trim() {
             "json" => Ok(Format::Json),
             "yaml" => Ok(Format::Yaml),
+            "prometheus" => Ok(Format::Prometheus),
             _ => Err(format!("Could not convert {s} to format type")),
         }
     }
@@ -39,152 +51,414 @@ impl fmt::Display for Format {
         match self {
             Self::Json => write!(f, "json"),
             Self::Yaml => write!(f, "yaml"),
+            Self::Prometheus => write!(f, "prometheus"),
         }
     }
 }
 
-fn get_lctl_output() -> Result<Vec<u8>, LustreCollectorError> {
-    let lctl_params = parser::params();
+fn main() -> ExitCode {
+    tracing_subscriber::fmt::init();
+
+    let mut cmd = command();
+    let matches = cmd.get_matches_mut();
+
+    let explain_errors = matches.get_flag("explain_errors");
 
-    debug!(lctl_params = lctl_params.join(" "));
+    match run(&mut cmd, &matches) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(LustreCollectorError::ParseError(e)) if explain_errors => {
+            eprint!("{}", e.explain());
 
-    let r = Command::new("lctl")
-        .arg("get_param")
-        .args(lctl_params)
-        .output()?;
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("{e}");
 
-    Ok(r.stdout)
+            ExitCode::FAILURE
+        }
+    }
 }
 
-fn get_lctl_mgs_fs_output() -> Result<Vec<u8>, LustreCollectorError> {
-    let r = Command::new("lctl")
-        .arg("get_param")
-        .arg("-N")
-        .args(mgs_fs_parser::params())
-        .output()?;
+type ParseFn = fn(&[u8], ParseMode) -> Result<Vec<Record>, LustreCollectorError>;
+
+/// Offline inputs, as `(arg id, long flag, help, parser)`.
+const INPUTS: [(&str, &str, &str, ParseFn); 9] = [
+    (
+        "lctl_input",
+        "lctl-input",
+        "Parse captured `lctl get_param` output from FILE ('-' for stdin)",
+        parse_lctl_output_with,
+    ),
+    (
+        "mgs_fs_input",
+        "mgs-fs-input",
+        "Parse captured `lctl get_param -N mgs.*.live.*` output from FILE ('-' for stdin)",
+        |x, _| parse_mgs_fs_output(x),
+    ),
+    (
+        "recovery_status_input",
+        "recovery-status-input",
+        "Parse captured `lctl get_param obdfilter.*OST*.recovery_status mdt.*MDT*.recovery_status` output from FILE ('-' for stdin)",
+        |x, _| parse_recovery_status_output(x),
+    ),
+    (
+        "device_list_input",
+        "device-list-input",
+        "Parse captured `lctl dl -t` output from FILE ('-' for stdin)",
+        |x, _| parse_device_list_output(x),
+    ),
+    (
+        "lnetctl_net_input",
+        "lnetctl-net-input",
+        "Parse captured `lnetctl net show -v 4` output from FILE ('-' for stdin)",
+        |x, _| parse_lnetctl_output(str::from_utf8(x)?),
+    ),
+    (
+        "lnetctl_stats_input",
+        "lnetctl-stats-input",
+        "Parse captured `lnetctl stats show` output from FILE ('-' for stdin)",
+        |x, _| parse_lnetctl_stats(str::from_utf8(x)?),
+    ),
+    (
+        "lnetctl_peer_input",
+        "lnetctl-peer-input",
+        "Parse captured `lnetctl peer show -v 3` output from FILE ('-' for stdin)",
+        |x, _| parse_lnetctl_peer_output(str::from_utf8(x)?),
+    ),
+    (
+        "lnetctl_route_input",
+        "lnetctl-route-input",
+        "Parse captured `lnetctl route show -v` output from FILE ('-' for stdin)",
+        |x, _| parse_lnetctl_route_output(str::from_utf8(x)?),
+    ),
+    (
+        "lnetctl_routing_input",
+        "lnetctl-routing-input",
+        "Parse captured `lnetctl routing show` output from FILE ('-' for stdin)",
+        |x, _| parse_lnetctl_routing_output(str::from_utf8(x)?),
+    ),
+];
+
+fn read_input(path: &str) -> Result<Vec<u8>, LustreCollectorError> {
+    if path == "-" {
+        let mut buf = vec![];
+        io::stdin().read_to_end(&mut buf)?;
+
+        return Ok(buf);
+    }
 
-    Ok(r.stdout)
+    Ok(fs::read(path)?)
 }
 
-fn get_recovery_status_output() -> Result<Vec<u8>, LustreCollectorError> {
-    let r = Command::new("lctl")
-        .arg("get_param")
-        .args(recovery_status_parser::params())
-        .output()?;
+/// Parses previously captured command output instead of running the commands.
+fn parse_inputs(
+    matches: &ArgMatches,
+    mode: ParseMode,
+) -> Result<Vec<Record>, LustreCollectorError> {
+    let mut records = vec![];
 
-    Ok(r.stdout)
+    for (id, _, _, parse) in INPUTS {
+        if let Some(path) = matches.get_one::<String>(id) {
+            records.append(&mut parse(&read_input(path)?, mode)?);
+        }
+    }
+
+    Ok(records)
 }
 
-fn get_lnetctl_stats_output() -> Result<Vec<u8>, LustreCollectorError> {
-    let r = Command::new("lnetctl").arg("stats").arg("show").output()?;
+/// Builds the runner used to invoke lctl and lnetctl from the command line options.
+fn runner(matches: &ArgMatches) -> Box<dyn CommandRunner> {
+    if let Some(dir) = matches.get_one::<PathBuf>("replay_dir") {
+        return Box::new(ReplayRunner { dir: dir.clone() });
+    }
 
-    Ok(r.stdout)
+    Box::new(LocalRunner {
+        wrapper: matches
+            .get_one::<String>("wrapper")
+            .map(|x| x.split_whitespace().map(String::from).collect())
+            .unwrap_or_default(),
+        lctl: matches.get_one::<String>("lctl").cloned(),
+        lnetctl: matches.get_one::<String>("lnetctl").cloned(),
+    })
 }
 
-fn main() -> ExitCode {
-    match run() {
-        Ok(()) => ExitCode::SUCCESS,
-        Err(e) => {
-            eprintln!("{e}");
+fn render(format: Format, records: &[Record]) -> Result<String, LustreCollectorError> {
+    let x = match format {
+        Format::Json => serde_json::to_string(records)?,
+        Format::Yaml => serde_yaml::to_string(records)?,
+        Format::Prometheus => render_prometheus(records),
+    };
 
-            ExitCode::FAILURE
-        }
+    Ok(x)
+}
+
+fn render_rates(format: Format, rates: &[rates::Rate]) -> Result<String, LustreCollectorError> {
+    let x = match format {
+        Format::Json => serde_json::to_string(rates)?,
+        Format::Yaml => serde_yaml::to_string(rates)?,
+        Format::Prometheus => render_rates_prometheus(rates),
+    };
+
+    Ok(x)
+}
+
+fn render_report(
+    format: Format,
+    report: &[report::FilesystemReport],
+) -> Result<String, LustreCollectorError> {
+    let x = match format {
+        Format::Json => serde_json::to_string(report)?,
+        Format::Yaml => serde_yaml::to_string(report)?,
+        Format::Prometheus => render_report_prometheus(report),
+    };
+
+    Ok(x)
+}
+
+fn content_type(format: Format) -> &'static str {
+    match format {
+        Format::Json => "application/json",
+        Format::Yaml => "application/yaml",
+        Format::Prometheus => "text/plain; version=0.0.4",
     }
 }
 
-fn run() -> Result<(), LustreCollectorError> {
-    tracing_subscriber::fmt::init();
+fn command() -> clap::Command {
+    let inputs = INPUTS.map(|(id, long, help, _)| {
+        Arg::new(id)
+            .long(long)
+            .value_name("FILE")
+            .help(help)
+            .help_heading("Offline parsing")
+    });
 
-    let matches = clap::Command::new(env!("CARGO_PKG_NAME"))
+    clap::Command::new(env!("CARGO_PKG_NAME"))
         .version(env!("CARGO_PKG_VERSION"))
         .author("Whamcloud")
-        .about("Grabs various Lustre statistics for display in JSON or YAML")
+        .about("Grabs various Lustre statistics for display in JSON, YAML or Prometheus format")
         .arg(
             Arg::new("format")
                 .short('f')
                 .long("format")
                 .value_parser(value_parser!(Format))
                 .default_value("json")
+                .global(true)
                 .help("Sets the output formatting"),
         )
-        .get_matches();
+        .arg(
+            Arg::new("wrapper")
+                .long("wrapper")
+                .value_name("COMMAND")
+                .global(true)
+                .help("Wraps every lctl / lnetctl call in COMMAND, e.g. 'sudo' or 'nsenter -t 1 -m --'"),
+        )
+        .arg(
+            Arg::new("lctl")
+                .long("lctl")
+                .value_name("PATH")
+                .global(true)
+                .help("Path to the lctl binary"),
+        )
+        .arg(
+            Arg::new("lnetctl")
+                .long("lnetctl")
+                .value_name("PATH")
+                .global(true)
+                .help("Path to the lnetctl binary"),
+        )
+        .arg(
+            Arg::new("replay_dir")
+                .long("replay-dir")
+                .value_name("DIR")
+                .value_parser(value_parser!(PathBuf))
+                .global(true)
+                .conflicts_with_all(["wrapper", "lctl", "lnetctl"])
+                .help("Replays captured command output from DIR/<name>.txt instead of running lctl / lnetctl"),
+        )
+        .arg(
+            Arg::new("lenient")
+                .long("lenient")
+                .action(clap::ArgAction::SetTrue)
+                .global(true)
+                .help("Skip lctl get_param output that doesn't parse with a diagnostic record, instead of failing"),
+        )
+        .arg(
+            Arg::new("explain_errors")
+                .long("explain-errors")
+                .action(clap::ArgAction::SetTrue)
+                .global(true)
+                .help("On a parse error, print the offending line with a marker at the failing column"),
+        )
+        .args(inputs)
+        .subcommand(
+            clap::Command::new("serve")
+                .about("Stays resident and serves the latest stats over HTTP on /metrics")
+                .arg(
+                    Arg::new("bind")
+                        .short('b')
+                        .long("bind")
+                        .value_parser(value_parser!(SocketAddr))
+                        .default_value("0.0.0.0:32221")
+                        .help("Address to listen on"),
+                )
+                .arg(
+                    Arg::new("interval")
+                        .short('i')
+                        .long("interval")
+                        .value_parser(value_parser!(u64))
+                        .help("Collect every N seconds instead of on every scrape"),
+                ),
+        )
+        .subcommand(
+            clap::Command::new("rates")
+                .about("Computes per-second rates of cumulative counters between two snapshots")
+                .arg(
+                    Arg::new("interval")
+                        .short('i')
+                        .long("interval")
+                        .value_parser(value_parser!(u64))
+                        .default_value("10")
+                        .help("Seconds between the two collections, or between FROM and TO for counters without a snapshot_time"),
+                )
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .value_name("FILE")
+                        .requires("to")
+                        .help("Earlier snapshot in JSON format, instead of collecting"),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .value_name("FILE")
+                        .requires("from")
+                        .help("Later snapshot in JSON format, instead of collecting"),
+                ),
+        )
+        .subcommand(
+            clap::Command::new("report")
+                .about("Reports capacity and inode utilisation per filesystem and target, flagging imbalanced OSTs")
+                .arg(
+                    Arg::new("threshold")
+                        .short('t')
+                        .long("threshold")
+                        .value_parser(value_parser!(f64))
+                        .help("Percentage points an OST may deviate from the mean fill level of its pool, or of all OSTs in the filesystem when it is in no pool [default: 10]"),
+                )
+                .arg(
+                    Arg::new("snapshot")
+                        .long("snapshot")
+                        .value_name("FILE")
+                        .help("Snapshot in JSON format, instead of collecting"),
+                ),
+        )
+}
 
-    let format = matches
-        .get_one::<Format>("format")
-        .expect("Required argument `format` missing");
+fn run(cmd: &mut clap::Command, matches: &ArgMatches) -> Result<(), LustreCollectorError> {
+    let offline = INPUTS.iter().any(|(id, _, _, _)| matches.contains_id(id));
 
-    let handle = thread::spawn(move || -> Result<Vec<Record>, LustreCollectorError> {
-        let lctl_output = get_lctl_output()?;
+    if offline && matches.subcommand().is_some() {
+        cmd.error(
+            ErrorKind::ArgumentConflict,
+            "Offline parsing inputs cannot be used with a subcommand",
+        )
+        .exit();
+    }
 
-        let lctl_record = parse_lctl_output(&lctl_output)?;
+    let format = *matches
+        .get_one::<Format>("format")
+        .expect("Required argument `format` missing");
 
-        Ok(lctl_record)
-    });
+    let lctl_params = parser::params();
 
-    let mgs_fs_handle = thread::spawn(move || -> Result<Vec<Record>, LustreCollectorError> {
-        let lctl_output = get_lctl_mgs_fs_output()?;
-        let lctl_record = parse_mgs_fs_output(&lctl_output)?;
+    let mode = if matches.get_flag("lenient") {
+        ParseMode::Lenient
+    } else {
+        ParseMode::Strict
+    };
 
-        Ok(lctl_record)
-    });
+    if let Some(matches) = matches.subcommand_matches("serve") {
+        let runner = runner(matches);
 
-    let lnetctl_stats_handle =
-        thread::spawn(move || -> Result<Vec<Record>, LustreCollectorError> {
-            let lnetctl_stats_output = get_lnetctl_stats_output()?;
-            let lnetctl_stats_record = parse_lnetctl_stats(str::from_utf8(&lnetctl_stats_output)?)?;
+        let bind = matches
+            .get_one::<SocketAddr>("bind")
+            .expect("Required argument `bind` missing");
 
-            Ok(lnetctl_stats_record)
+        let server = Server::new(content_type(format), move || {
+            render(format, &collect(&*runner, &lctl_params, mode)?)
         });
 
-    let recovery_status_handle =
-        thread::spawn(move || -> Result<Vec<Record>, LustreCollectorError> {
-            let recovery_status_output = get_recovery_status_output()?;
-            let recovery_statuses = parse_recovery_status_output(&recovery_status_output)?;
+        let server = match matches.get_one::<u64>("interval") {
+            Some(x) => server.with_interval(Duration::from_secs(*x)),
+            None => server,
+        };
 
-            Ok(recovery_statuses)
-        });
+        let listener = TcpListener::bind(bind)?;
 
-    let lnetctl_net_show_output = Command::new("lnetctl")
-        .args(["net", "show", "-v", "4"])
-        .output()
-        .expect("failed to get lnetctl stats");
+        debug!("Serving {format} stats on http://{bind}{METRICS_PATH}");
 
-    let lnetctl_net_show_stats = str::from_utf8(&lnetctl_net_show_output.stdout)
-        .expect("while converting 'lnetctl net show -v 4' stdout from utf8");
+        return server.serve(listener);
+    }
 
-    let mut lnet_record = parse_lnetctl_output(lnetctl_net_show_stats)
-        .expect("while parsing 'lnetctl net show -v 4' stats");
+    if let Some(matches) = matches.subcommand_matches("rates") {
+        let interval = Duration::from_secs(
+            *matches
+                .get_one::<u64>("interval")
+                .expect("Required argument `interval` missing"),
+        );
+
+        let (prev, cur) = match (
+            matches.get_one::<String>("from"),
+            matches.get_one::<String>("to"),
+        ) {
+            (Some(from), Some(to)) => (
+                serde_json::from_slice::<Vec<Record>>(&read_input(from)?)?,
+                serde_json::from_slice::<Vec<Record>>(&read_input(to)?)?,
+            ),
+            _ => {
+                let runner = runner(matches);
+
+                let prev = collect(&*runner, &lctl_params, mode)?;
+                thread::sleep(interval);
+                let cur = collect(&*runner, &lctl_params, mode)?;
+
+                (prev, cur)
+            }
+        };
+
+        let x = render_rates(format, &rates::rates(&prev, &cur, interval))?;
+
+        println!("{x}");
+
+        return Ok(());
+    }
 
-    let mut lctl_record = match handle.join() {
-        Ok(r) => r?,
-        Err(e) => panic::resume_unwind(e),
-    };
+    if let Some(matches) = matches.subcommand_matches("report") {
+        let threshold = matches
+            .get_one::<f64>("threshold")
+            .copied()
+            .unwrap_or(report::DEFAULT_IMBALANCE_THRESHOLD);
 
-    let mut mgs_fs_record = match mgs_fs_handle.join() {
-        Ok(r) => r.unwrap_or_default(),
-        Err(e) => panic::resume_unwind(e),
-    };
+        let records = match matches.get_one::<String>("snapshot") {
+            Some(x) => serde_json::from_slice::<Vec<Record>>(&read_input(x)?)?,
+            None => collect(&*runner(matches), &lctl_params, mode)?,
+        };
 
-    let mut recovery_status_records = match recovery_status_handle.join() {
-        Ok(r) => r.unwrap_or_default(),
-        Err(e) => panic::resume_unwind(e),
-    };
+        let x = render_report(format, &report::report(&records, threshold))?;
 
-    let mut lnetctl_stats_record = match lnetctl_stats_handle.join() {
-        Ok(r) => r.unwrap_or_default(),
-        Err(e) => panic::resume_unwind(e),
-    };
+        println!("{x}");
 
-    lctl_record.append(&mut lnet_record);
-    lctl_record.append(&mut mgs_fs_record);
-    lctl_record.append(&mut recovery_status_records);
-    lctl_record.append(&mut lnetctl_stats_record);
+        return Ok(());
+    }
 
-    let x = match format {
-        Format::Json => serde_json::to_string(&lctl_record)?,
-        Format::Yaml => serde_yaml::to_string(&lctl_record)?,
+    let records = if offline {
+        parse_inputs(matches, mode)?
+    } else {
+        collect(&*runner(matches), &lctl_params, mode)?
     };
 
+    let x = render(format, &records)?;
+
     println!("{x}");
 
     Ok(())
@@ -0,0 +1,166 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+use crate::{
+    base_parsers::{client_device, param, period},
+    import_parser::import,
+    rpc_stats_parser::rpc_stats,
+    stats_parser::stats,
+    types::{ClientImport, ClientTargetStat, RpcStats, UnsignedLustreTimestamp},
+    Param, Record, Stat, Target, TargetStats, TargetVariant,
+};
+use combine::{attempt, choice, parser::char::string, ParseError, Parser, Stream};
+
+pub(crate) const MDC: &str = "mdc";
+pub(crate) const STATS: &str = "stats";
+pub(crate) const RPC_STATS: &str = "rpc_stats";
+pub(crate) const IMPORT: &str = "import";
+
+pub(crate) fn params() -> Vec<String> {
+    [STATS, RPC_STATS, IMPORT]
+        .into_iter()
+        .map(|x| format!("{MDC}.*.{x}"))
+        .collect()
+}
+
+fn target_name<I>() -> impl Parser<I, Output = (Target, String)>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        attempt(string(MDC)).skip(period()),
+        client_device(MDC).skip(period()),
+    )
+        .map(|(_, x)| x)
+        .message("while parsing mdc target_name")
+}
+
+enum MdcStat {
+    Stats(Option<UnsignedLustreTimestamp>, Vec<Stat>),
+    RpcStats(Option<UnsignedLustreTimestamp>, RpcStats),
+    Import(ClientImport),
+}
+
+fn mdc_stat<I>() -> impl Parser<I, Output = (Param, MdcStat)>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    choice((
+        (
+            param(STATS),
+            stats().map(|(time, xs)| MdcStat::Stats(time, xs)),
+        ),
+        (
+            param(RPC_STATS),
+            rpc_stats().map(|(time, x)| MdcStat::RpcStats(time, x)),
+        ),
+        (param(IMPORT), import().map(MdcStat::Import)),
+    ))
+    .message("while parsing mdc_stat")
+}
+
+pub(crate) fn parse<I>() -> impl Parser<I, Output = Record>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (target_name(), mdc_stat())
+        .map(|((target, client), (param, value))| match value {
+            MdcStat::Stats(snapshot_time, value) => TargetStats::ClientStats(ClientTargetStat {
+                kind: TargetVariant::Mdt,
+                target,
+                client,
+                param,
+                value,
+                snapshot_time,
+            }),
+            MdcStat::RpcStats(snapshot_time, value) => {
+                TargetStats::ClientRpcStats(ClientTargetStat {
+                    kind: TargetVariant::Mdt,
+                    target,
+                    client,
+                    param,
+                    value,
+                    snapshot_time,
+                })
+            }
+            MdcStat::Import(value) => TargetStats::ClientImport(ClientTargetStat {
+                kind: TargetVariant::Mdt,
+                target,
+                client,
+                param,
+                value,
+                snapshot_time: None,
+            }),
+        })
+        .map(Record::Target)
+        .message("while parsing mdc")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use combine::many;
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn test_parse() {
+        let x = r#"mdc.fs-MDT0000-mdc-ffff8dc8f8d4b000.stats=
+snapshot_time             1700000000.123456789 secs.nsecs
+start_time                1690000000.000000000 secs.nsecs
+elapsed_time              10000000.123456789 secs.nsecs
+req_waittime              5212 samples [usec] 31 8102 1201567 1052001853
+req_active                5212 samples [reqs] 1 3 5300 5584
+mds_getattr               12 samples [usec] 52 213 1130 131582
+mds_close                 1210 samples [usec] 33 2210 97511 22302127
+ldlm_ibits_enqueue        2410 samples [reqs] 1 1 2410 2410
+obd_ping                  1580 samples [usec] 31 925 96421 8842001
+mdc.fs-MDT0000-mdc-ffff8dc8f8d4b000.rpc_stats=
+snapshot_time:         1700000000.123456789 secs.nsecs
+read RPCs in flight:  0
+write RPCs in flight: 0
+pending write pages:  0
+pending read pages:   0
+
+			read			write
+pages per rpc         rpcs   % cum % |       rpcs   % cum %
+1:		         2 100 100   |          0   0   0
+
+			read			write
+rpcs in flight        rpcs   % cum % |       rpcs   % cum %
+1:		         2 100 100   |          0   0   0
+mdc.fs-MDT0000-mdc-ffff8dc8f8d4b000.import=
+import:
+    name: fs-MDT0000-mdc-ffff8dc8f8d4b000
+    target: fs-MDT0000_UUID
+    state: EVICTED
+    import_flags: [ replayable, pingable, connect_tried ]
+    connection:
+       failover_nids: [ 10.0.0.1@tcp ]
+       current_connection: 10.0.0.1@tcp
+       connection_attempts: 2
+       generation: 2
+       in-progress_invalidations: 0
+       idle: 3 sec
+    rpcs:
+       inflight: 0
+       unregistering: 0
+       timeouts: 1
+       avg_waittime: 1437 usec
+    service_estimates:
+       services: 1 sec
+       network: 1 sec
+    transactions:
+       last_replay: 0
+       peer_committed: 0
+       last_checked: 0
+"#;
+
+        let result: (Vec<_>, _) = many(parse()).parse(x).unwrap();
+
+        assert_debug_snapshot!(result)
+    }
+}
@@ -4,7 +4,8 @@
 
 use crate::{
     base_parsers::{digits, param, period, target, till_newline, till_period},
-    types::{Param, Record, Target, TargetStat, TargetStats, TargetVariant},
+    lfsck_parser::lfsck,
+    types::{LfsckStats, Param, Record, Target, TargetStat, TargetStats, TargetVariant},
     ChangeLogUser, ChangelogStat,
 };
 use combine::{
@@ -18,14 +19,22 @@ use combine::{
 
 pub(crate) const MDD: &str = "mdd";
 pub(crate) const CHANGELOG_USERS: &str = "changelog_users";
+pub(crate) const LFSCK_NAMESPACE: &str = "lfsck_namespace";
+pub(crate) const LFSCK_LAYOUT: &str = "lfsck_layout";
+
 pub(crate) fn params() -> Vec<String> {
-    vec![format!("{MDD}.*.{CHANGELOG_USERS}")]
+    [CHANGELOG_USERS, LFSCK_NAMESPACE, LFSCK_LAYOUT]
+        .into_iter()
+        .map(|x| format!("{MDD}.*.{x}"))
+        .collect()
 }
 
 #[derive(Debug)]
 enum MddStat {
     /// Changelog stat
     ChangeLog(ChangelogStat),
+    LfsckNamespace(LfsckStats),
+    LfsckLayout(LfsckStats),
 }
 
 fn target_and_variant<I>() -> impl Parser<I, Output = (Target, TargetVariant)>
@@ -87,25 +96,29 @@ where
     I: Stream<Token = char>,
     I::Error: ParseError<I::Token, I::Range, I::Position>,
 {
-    choice(((
-        param(CHANGELOG_USERS),
+    choice((
         (
-            newline(),
-            string("current_index: "),
-            digits(),
-            newline(),
-            table_headers(),
-            newline(),
-            table_rows(),
+            param(CHANGELOG_USERS),
+            (
+                newline(),
+                string("current_index: "),
+                digits(),
+                newline(),
+                table_headers(),
+                newline(),
+                table_rows(),
+            )
+                .map(|(_, _, x, _, _, _, y)| {
+                    MddStat::ChangeLog(ChangelogStat {
+                        current_index: x,
+                        users: y,
+                    })
+                }),
         )
-            .map(|(_, _, x, _, _, _, y)| {
-                MddStat::ChangeLog(ChangelogStat {
-                    current_index: x,
-                    users: y,
-                })
-            }),
-    )
-        .message("while parsing changelog"),))
+            .message("while parsing changelog"),
+        (param(LFSCK_NAMESPACE), lfsck().map(MddStat::LfsckNamespace)),
+        (param(LFSCK_LAYOUT), lfsck().map(MddStat::LfsckLayout)),
+    ))
 }
 
 pub(crate) fn parse<I>() -> impl Parser<I, Output = Record>
@@ -120,6 +133,21 @@ where
                 target,
                 param,
                 value,
+                snapshot_time: None,
+            }),
+            MddStat::LfsckNamespace(value) => TargetStats::LfsckNamespace(TargetStat {
+                kind,
+                target,
+                param,
+                value,
+                snapshot_time: None,
+            }),
+            MddStat::LfsckLayout(value) => TargetStats::LfsckLayout(TargetStat {
+                kind,
+                target,
+                param,
+                value,
+                snapshot_time: None,
             }),
         })
         .map(Record::Target)
@@ -144,4 +172,16 @@ mod tests {
 
         assert_debug_snapshot!(result);
     }
+
+    #[test]
+    fn test_lfsck() {
+        static FIXTURE: &str = include_str!("../fixtures/lfsck.txt");
+
+        let result = many::<Vec<_>, _, _>(parse())
+            .easy_parse(FIXTURE)
+            .map_err(|err| err.map_position(|p| p.translate_position(FIXTURE)))
+            .unwrap();
+
+        assert_debug_snapshot!(result);
+    }
 }
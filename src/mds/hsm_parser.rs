@@ -0,0 +1,289 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Parsers for the HSM coordinator params of an MDT.
+
+use crate::{
+    base_parsers::{till_newline, word},
+    types::{HsmActionCount, HsmAgent, HsmControl},
+};
+use combine::{
+    attempt,
+    error::ParseError,
+    many,
+    parser::char::{newline, string},
+    stream::Stream,
+    Parser,
+};
+use std::collections::{BTreeMap, HashMap};
+
+pub(crate) const HSM_CONTROL: &str = "hsm_control";
+pub(crate) const HSM_ACTIONS: &str = "hsm.actions";
+pub(crate) const HSM_ACTIVE_REQUESTS: &str = "hsm.active_requests";
+pub(crate) const HSM_AGENTS: &str = "hsm.agents";
+
+/// Splits a line on whitespace outside of `[..]` groups.
+fn tokens(line: &str) -> Vec<&str> {
+    let mut xs = vec![];
+    let mut depth = 0_usize;
+    let mut start = None;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(x) = start.take() {
+                    xs.push(&line[x..i]);
+                }
+
+                continue;
+            }
+            _ => {}
+        }
+
+        start.get_or_insert(i);
+    }
+
+    if let Some(x) = start {
+        xs.push(&line[x..]);
+    }
+
+    xs
+}
+
+/// Splits a line like `action=ARCHIVE archive#=1 requests=[current:0 ok:5]`
+/// into its `key=value` fields.
+///
+/// A bracketed value is kept whole, so `fid=[0x200000400:0x1:0x0]` maps `fid`
+/// to `0x200000400:0x1:0x0`. Named `key=value` or `key:value` entries in the
+/// group, such as `current:0`, are added as fields of their own.
+fn fields(line: &str) -> HashMap<String, String> {
+    fn named(x: &str) -> Option<(&str, &str)> {
+        x.split_once('=')
+            .or_else(|| x.split_once(':'))
+            .filter(|(k, _)| k.starts_with(|c: char| c.is_ascii_alphabetic()))
+    }
+
+    let mut fields = HashMap::new();
+
+    for x in tokens(line) {
+        let Some((k, v)) = named(x) else {
+            continue;
+        };
+
+        let v = match v.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+            Some(group) => {
+                for (k, v) in group.split_whitespace().filter_map(named) {
+                    fields.insert(k.to_string(), v.to_string());
+                }
+
+                group
+            }
+            None => v,
+        };
+
+        fields.insert(k.to_string(), v.to_string());
+    }
+
+    fields
+}
+
+/// Takes every line starting with `prefix`
+fn lines<I>(prefix: &'static str) -> impl Parser<I, Output = Vec<String>>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    newline().with(many(attempt(
+        (string(prefix), till_newline(), newline()).map(move |(_, x, _)| format!("{prefix}{x}")),
+    )))
+}
+
+/// Counts actions per archive, action and status. `status` is used for
+/// lines without a `status=` field.
+fn count_actions(xs: Vec<String>, status: &str) -> Vec<HsmActionCount> {
+    let mut counts: BTreeMap<(u64, String, String), u64> = BTreeMap::new();
+
+    for x in &xs {
+        let fields = fields(x);
+
+        let archive_id = fields
+            .get("archive#")
+            .and_then(|x| x.parse().ok())
+            .unwrap_or_default();
+        let action = fields.get("action").cloned().unwrap_or_default();
+        let status = fields
+            .get("status")
+            .cloned()
+            .unwrap_or_else(|| status.to_string());
+
+        *counts.entry((archive_id, action, status)).or_default() += 1;
+    }
+
+    counts
+        .into_iter()
+        .map(|((archive_id, action, status), count)| HsmActionCount {
+            archive_id,
+            action: action.as_str().into(),
+            status: status.as_str().into(),
+            count,
+        })
+        .collect()
+}
+
+pub(crate) fn hsm_control<I>() -> impl Parser<I, Output = HsmControl>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    word().skip(newline()).map(|x| x.as_str().into())
+}
+
+pub(crate) fn hsm_actions<I>() -> impl Parser<I, Output = Vec<HsmActionCount>>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    lines("lrh=").map(|xs| count_actions(xs, "UNKNOWN"))
+}
+
+pub(crate) fn hsm_active_requests<I>() -> impl Parser<I, Output = Vec<HsmActionCount>>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    lines("fid=").map(|xs| count_actions(xs, "STARTED"))
+}
+
+pub(crate) fn hsm_agents<I>() -> impl Parser<I, Output = Vec<HsmAgent>>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    lines("uuid=").map(|xs| {
+        xs.iter()
+            .map(|x| {
+                let fields = fields(x);
+                let number = |k| {
+                    fields
+                        .get(k)
+                        .and_then(|x| x.parse().ok())
+                        .unwrap_or_default()
+                };
+
+                HsmAgent {
+                    uuid: fields.get("uuid").cloned().unwrap_or_default(),
+                    archive_id: fields.get("archive_id").cloned().unwrap_or_default(),
+                    current: number("current"),
+                    ok: number("ok"),
+                    errors: number("errors"),
+                }
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{HsmAction, HsmActionStatus};
+
+    #[test]
+    fn test_fields() {
+        let x = fields("uuid=abc archive_id=1,2 requests=[current:0 ok:5 errors:1]");
+
+        assert_eq!(x["uuid"], "abc");
+        assert_eq!(x["archive_id"], "1,2");
+        assert_eq!(x["current"], "0");
+        assert_eq!(x["ok"], "5");
+        assert_eq!(x["errors"], "1");
+    }
+
+    #[test]
+    fn test_fields_fid() {
+        let x = fields("lrh=[type=10680000 len=192 idx=1/3] fid=[0x200000400:0x1:0x0] dfid=[0x200000400:0x1:0x0] compound/cookie=0x5a3fe63e/0x5a3fe63e action=ARCHIVE archive#=1 flags=0x0 extent=0x0-0xffffffffffffffff gid=0x0 datalen=0 status=SUCCEED data=[]");
+
+        assert_eq!(x["fid"], "0x200000400:0x1:0x0");
+        assert_eq!(x["dfid"], "0x200000400:0x1:0x0");
+        assert_eq!(x["lrh"], "type=10680000 len=192 idx=1/3");
+        assert_eq!(x["idx"], "1/3");
+        assert_eq!(x["action"], "ARCHIVE");
+        assert_eq!(x["archive#"], "1");
+        assert_eq!(x["data"], "");
+        assert!(!x.contains_key("0x200000400"));
+    }
+
+    #[test]
+    fn test_hsm_actions() {
+        let x = r#"
+lrh=[type=10680000 len=192 idx=1/3] fid=[0x200000400:0x1:0x0] dfid=[0x200000400:0x1:0x0] compound/cookie=0x5a3fe63e/0x5a3fe63e action=ARCHIVE archive#=1 flags=0x0 extent=0x0-0xffffffffffffffff gid=0x0 datalen=0 status=SUCCEED data=[]
+lrh=[type=10680000 len=192 idx=1/4] fid=[0x200000400:0x2:0x0] dfid=[0x200000400:0x2:0x0] compound/cookie=0x5a3fe63f/0x5a3fe63f action=ARCHIVE archive#=1 flags=0x0 extent=0x0-0xffffffffffffffff gid=0x0 datalen=0 status=SUCCEED data=[]
+lrh=[type=10680000 len=192 idx=1/5] fid=[0x200000400:0x3:0x0] dfid=[0x200000400:0x3:0x0] cookie=0x5a3fe640 action=RESTORE archive#=2 flags=0x0 extent=0x0-0xffffffffffffffff gid=0x0 datalen=0 status=WAITING data=[]
+mdt.fs-MDT0000.hsm.agents=
+"#;
+
+        assert_eq!(
+            hsm_actions().parse(x),
+            Ok((
+                vec![
+                    HsmActionCount {
+                        archive_id: 1,
+                        action: HsmAction::Archive,
+                        status: HsmActionStatus::Succeed,
+                        count: 2,
+                    },
+                    HsmActionCount {
+                        archive_id: 2,
+                        action: HsmAction::Restore,
+                        status: HsmActionStatus::Waiting,
+                        count: 1,
+                    },
+                ],
+                "mdt.fs-MDT0000.hsm.agents=\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_hsm_active_requests() {
+        let x = r#"
+fid=[0x200000400:0x2:0x0] dfid=[0x200000400:0x2:0x0] compound/cookie=0/0x5a3fe63f action=RESTORE archive#=1 flags=0x0 extent=0x0-0xffffffffffffffff gid=0x0 data=[] canceled=0 uuid=72b8d0a0-3ea5-4a43-8a9c-0f3b3c8a4b19 done=0
+"#;
+
+        assert_eq!(
+            hsm_active_requests().parse(x),
+            Ok((
+                vec![HsmActionCount {
+                    archive_id: 1,
+                    action: HsmAction::Restore,
+                    status: HsmActionStatus::Started,
+                    count: 1,
+                }],
+                ""
+            ))
+        );
+    }
+
+    #[test]
+    fn test_hsm_agents() {
+        let x = r#"
+uuid=72b8d0a0-3ea5-4a43-8a9c-0f3b3c8a4b19 archive_id=ANY requests=[current:1 ok:12 errors:2]
+"#;
+
+        assert_eq!(
+            hsm_agents().parse(x),
+            Ok((
+                vec![HsmAgent {
+                    uuid: "72b8d0a0-3ea5-4a43-8a9c-0f3b3c8a4b19".to_string(),
+                    archive_id: "ANY".to_string(),
+                    current: 1,
+                    ok: 12,
+                    errors: 2,
+                }],
+                ""
+            ))
+        );
+    }
+}
@@ -2,15 +2,9 @@
 // Use of this source code is governed by a MIT-style
 // license that can be found in the LICENSE file.
 
-use crate::types::{JobStatMdt, JobStatsMdt};
+use crate::{job_stats_parser, types::JobStatMdt};
 use combine::{
-    attempt,
     error::{ParseError, StreamError},
-    optional,
-    parser::{
-        char::{alpha_num, newline},
-        repeat::take_until,
-    },
     stream::{Stream, StreamErrorFor},
     Parser,
 };
@@ -20,22 +14,19 @@ where
     I: Stream<Token = char>,
     I::Error: ParseError<I::Token, I::Range, I::Position>,
 {
-    (
-        optional(newline()), // If Jobstats are present, the whole yaml blob will be on a newline
-        take_until(attempt((newline(), alpha_num()))),
-    )
-        .skip(newline())
-        .and_then(|(_, x): (_, String)| {
-            serde_yaml::from_str(&x)
-                .map(|x: JobStatsMdt| x.job_stats)
-                .map_err(StreamErrorFor::<I>::other)
-        })
+    job_stats_parser::parse().and_then(|xs| {
+        xs.map(|xs| xs.into_iter().map(JobStatMdt::try_from).collect())
+            .transpose()
+            .map_err(StreamErrorFor::<I>::other)
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::JobStatsMdt;
     use crate::{types::BytesStat, UnsignedLustreTimestamp};
+    use combine::EasyParser;
 
     #[test]
     fn test_yaml_deserialize() {
@@ -203,9 +194,11 @@ mod tests {
                 },
                 parallel_rename_dir: None,
                 parallel_rename_file: None,
+                job_id_fields: None,
             }]),
         };
 
-        assert_eq!(serde_yaml::from_str::<JobStatsMdt>(x).unwrap(), expected)
+        assert_eq!(serde_yaml::from_str::<JobStatsMdt>(x).unwrap(), expected);
+        assert_eq!(parse().easy_parse(x).unwrap(), (expected.job_stats, ""));
     }
 }
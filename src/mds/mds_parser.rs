@@ -5,7 +5,7 @@
 use crate::{
     base_parsers::{equals, period, target},
     stats_parser::stats,
-    types::{Param, Record, Stat, Target, TargetStats},
+    types::{Param, Record, Stat, Target, TargetStats, UnsignedLustreTimestamp},
     MdsStat,
 };
 use combine::{attempt, choice, error::ParseError, parser::char::string, stream::Stream, Parser};
@@ -64,7 +64,7 @@ where
         .message("while parsing `mds_suffix`")
 }
 
-fn mds_stat<I>() -> impl Parser<I, Output = (Param, Vec<Stat>)>
+fn mds_stat<I>() -> impl Parser<I, Output = (Param, (Option<UnsignedLustreTimestamp>, Vec<Stat>))>
 where
     I: Stream<Token = char>,
     I::Error: ParseError<I::Token, I::Range, I::Position>,
@@ -92,7 +92,13 @@ where
 {
     mds_prefix()
         .with(mds_stat())
-        .map(|(param, stats)| TargetStats::Mds(MdsStat { param, stats }))
+        .map(|(param, (snapshot_time, stats))| {
+            TargetStats::Mds(MdsStat {
+                param,
+                stats,
+                snapshot_time,
+            })
+        })
         .map(Record::Target)
         .message("while parsing mds")
 }
@@ -5,10 +5,19 @@
 use crate::{
     base_parsers::{digits, param, param_period, period, target},
     exports_parser::exports_stats,
-    mds::job_stats,
+    mds::{
+        hsm_parser::{
+            hsm_actions, hsm_active_requests, hsm_agents, hsm_control, HSM_ACTIONS,
+            HSM_ACTIVE_REQUESTS, HSM_AGENTS, HSM_CONTROL,
+        },
+        job_stats,
+    },
     oss::obdfilter_parser::{EXPORTS, EXPORTS_PARAMS},
     stats_parser::stats,
-    types::{JobStatMdt, Param, Record, Stat, Target, TargetStat, TargetStats, TargetVariant},
+    types::{
+        HsmActionCount, HsmAgent, HsmControl, JobStatMdt, Param, Record, Stat, Target, TargetStat,
+        TargetStats, TargetVariant, UnsignedLustreTimestamp,
+    },
     ExportStats,
 };
 use combine::{
@@ -25,9 +34,13 @@ pub(crate) const NUM_EXPORTS: &str = "num_exports";
 
 enum MdtStat {
     JobStats(Option<Vec<JobStatMdt>>),
-    Stats(Vec<Stat>),
+    Stats(Option<UnsignedLustreTimestamp>, Vec<Stat>),
     NumExports(u64),
     ExportStats(Vec<ExportStats>),
+    HsmControl(HsmControl),
+    HsmActions(Vec<HsmActionCount>),
+    HsmActiveRequests(Vec<HsmActionCount>),
+    HsmAgents(Vec<HsmAgent>),
 }
 
 fn mdt_stat<I>() -> impl Parser<I, Output = (Param, MdtStat)>
@@ -40,13 +53,24 @@ where
             param(NUM_EXPORTS),
             digits().skip(newline()).map(MdtStat::NumExports),
         ),
-        (param(STATS), stats().map(MdtStat::Stats)).message("while parsing mdt_stat"),
+        (
+            param(STATS),
+            stats().map(|(time, xs)| MdtStat::Stats(time, xs)),
+        )
+            .message("while parsing mdt_stat"),
         (param(JOB_STATS), job_stats::parse().map(MdtStat::JobStats))
             .message("while parsing job_stats"),
         (
             param_period(EXPORTS),
             exports_stats().map(MdtStat::ExportStats),
         ),
+        (param(HSM_CONTROL), hsm_control().map(MdtStat::HsmControl)),
+        (param(HSM_ACTIONS), hsm_actions().map(MdtStat::HsmActions)),
+        (
+            param(HSM_ACTIVE_REQUESTS),
+            hsm_active_requests().map(MdtStat::HsmActiveRequests),
+        ),
+        (param(HSM_AGENTS), hsm_agents().map(MdtStat::HsmAgents)),
     ))
 }
 
@@ -56,6 +80,10 @@ pub(crate) fn params() -> Vec<String> {
         format!("mdt.*.{STATS}"),
         format!("mdt.*MDT*.{NUM_EXPORTS}"),
         format!("mdt.*MDT*.{EXPORTS_PARAMS}"),
+        format!("mdt.*.{HSM_CONTROL}"),
+        format!("mdt.*.{HSM_ACTIONS}"),
+        format!("mdt.*.{HSM_ACTIVE_REQUESTS}"),
+        format!("mdt.*.{HSM_AGENTS}"),
     ]
     .into_iter()
     .collect()
@@ -86,24 +114,56 @@ where
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
-            MdtStat::Stats(value) => TargetStats::Stats(TargetStat {
+            MdtStat::Stats(snapshot_time, value) => TargetStats::Stats(TargetStat {
                 kind: TargetVariant::Mdt,
                 target,
                 param,
                 value,
+                snapshot_time,
             }),
             MdtStat::NumExports(value) => TargetStats::NumExports(TargetStat {
                 kind: TargetVariant::Mdt,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             MdtStat::ExportStats(value) => TargetStats::ExportStats(TargetStat {
                 kind: TargetVariant::Mdt,
                 target,
                 param,
                 value,
+                snapshot_time: None,
+            }),
+            MdtStat::HsmControl(value) => TargetStats::HsmControl(TargetStat {
+                kind: TargetVariant::Mdt,
+                target,
+                param,
+                value,
+                snapshot_time: None,
+            }),
+            MdtStat::HsmActions(value) => TargetStats::HsmActions(TargetStat {
+                kind: TargetVariant::Mdt,
+                target,
+                param,
+                value,
+                snapshot_time: None,
+            }),
+            MdtStat::HsmActiveRequests(value) => TargetStats::HsmActiveRequests(TargetStat {
+                kind: TargetVariant::Mdt,
+                target,
+                param,
+                value,
+                snapshot_time: None,
+            }),
+            MdtStat::HsmAgents(value) => TargetStats::HsmAgents(TargetStat {
+                kind: TargetVariant::Mdt,
+                target,
+                param,
+                value,
+                snapshot_time: None,
             }),
         })
         .map(Record::Target)
@@ -3,6 +3,7 @@
 // license that can be found in the LICENSE file.
 
 pub(crate) mod client_count_parser;
+pub(crate) mod hsm_parser;
 pub(crate) mod job_stats;
 pub(crate) mod mds_parser;
 pub(crate) mod mdt_parser;
@@ -114,4 +115,23 @@ snapshot_time             1689062826.416952373 secs.nsecs
 
         assert_debug_snapshot!(result)
     }
+
+    #[test]
+    fn test_hsm() {
+        let x = r#"mdt.fs-MDT0000.hsm_control=enabled
+mdt.fs-MDT0000.hsm.actions=
+lrh=[type=10680000 len=192 idx=1/3] fid=[0x200000400:0x1:0x0] dfid=[0x200000400:0x1:0x0] compound/cookie=0x5a3fe63e/0x5a3fe63e action=ARCHIVE archive#=1 flags=0x0 extent=0x0-0xffffffffffffffff gid=0x0 datalen=0 status=SUCCEED data=[]
+lrh=[type=10680000 len=192 idx=1/4] fid=[0x200000400:0x2:0x0] dfid=[0x200000400:0x2:0x0] compound/cookie=0x5a3fe63f/0x5a3fe63f action=ARCHIVE archive#=1 flags=0x0 extent=0x0-0xffffffffffffffff gid=0x0 datalen=0 status=FAILED data=[]
+mdt.fs-MDT0000.hsm.active_requests=
+mdt.fs-MDT0000.hsm.agents=
+uuid=72b8d0a0-3ea5-4a43-8a9c-0f3b3c8a4b19 archive_id=1 requests=[current:0 ok:1 errors:1]
+mdt.fs-MDT0000.md_stats=
+snapshot_time             1566017453.009677077 secs.nsecs
+statfs                    20318 samples [reqs]
+"#;
+
+        let result: (Vec<_>, _) = many(parse()).easy_parse(x).unwrap();
+
+        assert_debug_snapshot!(result)
+    }
 }
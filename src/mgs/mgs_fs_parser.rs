@@ -90,6 +90,7 @@ where
                             target,
                             param: param.clone(),
                             value: fs_name,
+                            snapshot_time: None,
                         })
                     })
                     .map(Record::Target)
@@ -119,6 +120,7 @@ mgs.MGS.live.params
                 param: Param("fsnames".into()),
                 target: Target("MGS".into()),
                 value: vec![FsName("fs".into())],
+                snapshot_time: None,
             }))],
             records
         );
@@ -140,6 +142,7 @@ mgs.MGS.live.params
                 param: Param("fsnames".into()),
                 target: Target("MGS".into()),
                 value: vec![FsName("fs".into()), FsName("fs2".into())],
+                snapshot_time: None,
             }))],
             records
         );
@@ -180,12 +183,14 @@ mgs.MGS2.live.mgs2fs2
                     param: Param("fsnames".into()),
                     target: Target("MGS".into()),
                     value: vec![FsName("fs".into()), FsName("fs2".into())],
+                    snapshot_time: None,
                 })),
                 Record::Target(TargetStats::FsNames(TargetStat {
                     kind: TargetVariant::Mgt,
                     param: Param("fsnames".into()),
                     target: Target("MGS2".into()),
                     value: vec![FsName("mgs2fs1".into()), FsName("mgs2fs2".into())],
+                    snapshot_time: None,
                 }))
             ],
             records
@@ -5,7 +5,10 @@
 use crate::{
     base_parsers::{digits, param, period, target},
     stats_parser::stats,
-    types::{Param, Record, Stat, Target, TargetStat, TargetStats, TargetVariant},
+    types::{
+        Param, Record, Stat, Target, TargetStat, TargetStats, TargetVariant,
+        UnsignedLustreTimestamp,
+    },
 };
 use combine::{
     attempt, choice,
@@ -36,7 +39,7 @@ pub fn params() -> Vec<String> {
 
 #[derive(Debug)]
 enum MgsStat {
-    Stats(Vec<Stat>),
+    Stats(Option<UnsignedLustreTimestamp>, Vec<Stat>),
     ThreadsMin(u64),
     ThreadsMax(u64),
     ThreadsStarted(u64),
@@ -70,7 +73,10 @@ where
         (
             string("mgs").skip(period()),
             choice((
-                (param(STATS), stats().map(MgsStat::Stats)),
+                (
+                    param(STATS),
+                    stats().map(|(time, xs)| MgsStat::Stats(time, xs)),
+                ),
                 (
                     param(THREADS_MIN),
                     digits().skip(newline()).map(MgsStat::ThreadsMin),
@@ -97,35 +103,40 @@ where
 {
     (target_name(), mgs_stat())
         .map(|(target, (param, value))| match value {
-            MgsStat::Stats(value) => TargetStats::Stats(TargetStat {
+            MgsStat::Stats(snapshot_time, value) => TargetStats::Stats(TargetStat {
                 kind: TargetVariant::Mgt,
                 target,
                 param,
                 value,
+                snapshot_time,
             }),
             MgsStat::NumExports(value) => TargetStats::NumExports(TargetStat {
                 kind: TargetVariant::Mgt,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             MgsStat::ThreadsMin(value) => TargetStats::ThreadsMin(TargetStat {
                 kind: TargetVariant::Mgt,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             MgsStat::ThreadsMax(value) => TargetStats::ThreadsMax(TargetStat {
                 kind: TargetVariant::Mgt,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             MgsStat::ThreadsStarted(value) => TargetStats::ThreadsStarted(TargetStat {
                 kind: TargetVariant::Mgt,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
         })
         .map(Record::Target)
@@ -4,6 +4,7 @@
 
 use crate::{
     base_parsers::{digits, string_to, till_newline},
+    check_output, parse_error,
     types::{NodeStat, Param, Record},
     LustreCollectorError, NodeStats,
 };
@@ -19,26 +20,15 @@ use combine::{
     stream::Stream,
     token, Parser,
 };
-use std::io;
 
 pub fn parse_cpustats_output(output: &[u8]) -> Result<Vec<Record>, LustreCollectorError> {
     let output = std::str::from_utf8(output)?;
 
     let (stats, state) = parse_cpustats()
         .easy_parse(output)
-        .map_err(|err| err.map_position(|p| p.translate_position(output)))?;
+        .map_err(|err| parse_error(output, err))?;
 
-    let params = crate::parser::params().join(" ");
-
-    if !state.is_empty() {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            format!("Content left in input buffer. Please run and supply output to support: `lctl get_param {params}`"),
-        )
-        .into());
-    }
-
-    Ok(stats)
+    check_output(stats, output, state)
 }
 
 fn parse_cpustats<I>() -> impl Parser<I, Output = Vec<Record>>
@@ -80,19 +70,9 @@ pub fn parse_meminfo_output(output: &[u8]) -> Result<Vec<Record>, LustreCollecto
 
     let (mem_stats, state) = parse_meminfo()
         .easy_parse(output)
-        .map_err(|err| err.map_position(|p| p.translate_position(output)))?;
-
-    let params = crate::parser::params().join(" ");
-
-    if !state.is_empty() {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            format!("Content left in input buffer. Please run and supply output to support: `lctl get_param {params}`"),
-        )
-        .into());
-    }
+        .map_err(|err| parse_error(output, err))?;
 
-    Ok(mem_stats)
+    check_output(mem_stats, output, state)
 }
 
 fn parse_meminfo<I>() -> impl Parser<I, Output = Vec<Record>>
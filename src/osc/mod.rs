@@ -0,0 +1,250 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+use crate::{
+    base_parsers::{client_device, digits, param, period},
+    import_parser::{import, state},
+    rpc_stats_parser::rpc_stats,
+    stats_parser::stats,
+    types::{
+        ClientImport, ClientTargetStat, ImportStateHistory, RpcStats, UnsignedLustreTimestamp,
+    },
+    Param, Record, Stat, Target, TargetStats, TargetVariant,
+};
+use combine::{
+    attempt, choice,
+    parser::char::{newline, string},
+    ParseError, Parser, Stream,
+};
+
+pub(crate) const OSC: &str = "osc";
+pub(crate) const STATS: &str = "stats";
+pub(crate) const RPC_STATS: &str = "rpc_stats";
+pub(crate) const CUR_DIRTY_BYTES: &str = "cur_dirty_bytes";
+pub(crate) const CUR_GRANT_BYTES: &str = "cur_grant_bytes";
+pub(crate) const MAX_RPCS_IN_FLIGHT: &str = "max_rpcs_in_flight";
+pub(crate) const IMPORT: &str = "import";
+pub(crate) const STATE: &str = "state";
+
+pub(crate) fn params() -> Vec<String> {
+    [
+        STATS,
+        RPC_STATS,
+        CUR_DIRTY_BYTES,
+        CUR_GRANT_BYTES,
+        MAX_RPCS_IN_FLIGHT,
+        IMPORT,
+        STATE,
+    ]
+    .into_iter()
+    .map(|x| format!("{OSC}.*.{x}"))
+    .collect()
+}
+
+fn target_name<I>() -> impl Parser<I, Output = (Target, String)>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        attempt(string(OSC)).skip(period()),
+        client_device(OSC).skip(period()),
+    )
+        .map(|(_, x)| x)
+        .message("while parsing osc target_name")
+}
+
+enum OscStat {
+    Stats(Option<UnsignedLustreTimestamp>, Vec<Stat>),
+    RpcStats(Option<UnsignedLustreTimestamp>, RpcStats),
+    CurDirtyBytes(u64),
+    CurGrantBytes(u64),
+    MaxRpcsInFlight(u64),
+    Import(ClientImport),
+    State(ImportStateHistory),
+}
+
+fn osc_stat<I>() -> impl Parser<I, Output = (Param, OscStat)>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    choice((
+        (
+            param(STATS),
+            stats().map(|(time, xs)| OscStat::Stats(time, xs)),
+        ),
+        (
+            param(RPC_STATS),
+            rpc_stats().map(|(time, x)| OscStat::RpcStats(time, x)),
+        ),
+        (
+            param(CUR_DIRTY_BYTES),
+            digits().skip(newline()).map(OscStat::CurDirtyBytes),
+        ),
+        (
+            param(CUR_GRANT_BYTES),
+            digits().skip(newline()).map(OscStat::CurGrantBytes),
+        ),
+        (
+            param(MAX_RPCS_IN_FLIGHT),
+            digits().skip(newline()).map(OscStat::MaxRpcsInFlight),
+        ),
+        (param(IMPORT), import().map(OscStat::Import)),
+        (param(STATE), state().map(OscStat::State)),
+    ))
+    .message("while parsing osc_stat")
+}
+
+pub(crate) fn parse<I>() -> impl Parser<I, Output = Record>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (target_name(), osc_stat())
+        .map(|((target, client), (param, value))| match value {
+            OscStat::Stats(snapshot_time, value) => TargetStats::ClientStats(ClientTargetStat {
+                kind: TargetVariant::Ost,
+                target,
+                client,
+                param,
+                value,
+                snapshot_time,
+            }),
+            OscStat::RpcStats(snapshot_time, value) => {
+                TargetStats::ClientRpcStats(ClientTargetStat {
+                    kind: TargetVariant::Ost,
+                    target,
+                    client,
+                    param,
+                    value,
+                    snapshot_time,
+                })
+            }
+            OscStat::CurDirtyBytes(value) => TargetStats::CurDirtyBytes(ClientTargetStat {
+                kind: TargetVariant::Ost,
+                target,
+                client,
+                param,
+                value,
+                snapshot_time: None,
+            }),
+            OscStat::CurGrantBytes(value) => TargetStats::CurGrantBytes(ClientTargetStat {
+                kind: TargetVariant::Ost,
+                target,
+                client,
+                param,
+                value,
+                snapshot_time: None,
+            }),
+            OscStat::MaxRpcsInFlight(value) => TargetStats::MaxRpcsInFlight(ClientTargetStat {
+                kind: TargetVariant::Ost,
+                target,
+                client,
+                param,
+                value,
+                snapshot_time: None,
+            }),
+            OscStat::Import(value) => TargetStats::ClientImport(ClientTargetStat {
+                kind: TargetVariant::Ost,
+                target,
+                client,
+                param,
+                value,
+                snapshot_time: None,
+            }),
+            OscStat::State(value) => TargetStats::ClientImportState(ClientTargetStat {
+                kind: TargetVariant::Ost,
+                target,
+                client,
+                param,
+                value,
+                snapshot_time: None,
+            }),
+        })
+        .map(Record::Target)
+        .message("while parsing osc")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use combine::many;
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn test_parse() {
+        let x = r#"osc.fs-OST0000-osc-ffff8dc8f8d4b000.stats=
+snapshot_time             1700000000.123456789 secs.nsecs
+start_time                1690000000.000000000 secs.nsecs
+elapsed_time              10000000.123456789 secs.nsecs
+req_waittime              1893 samples [usec] 30 5391 231904 88420156
+req_active                1893 samples [reqs] 1 8 2644 6018
+read_bytes                10 samples [bytes] 4096 1048576 5287936 2757754880
+write_bytes               10 samples [bytes] 1048576 4194304 25165824 75866302464
+ost_read                  10 samples [usec] 301 5391 14102 40114209
+ost_write                 10 samples [usec] 820 4991 21093 64011452
+ost_connect               1 samples [usec] 201 201 201 40401
+obd_ping                  1862 samples [usec] 30 1122 186211 21402251
+osc.fs-OST0000-osc-ffff8dc8f8d4b000.rpc_stats=
+snapshot_time:         1700000000.123456789 secs.nsecs
+read RPCs in flight:  0
+write RPCs in flight: 1
+pending write pages:  12
+pending read pages:   0
+
+			read			write
+pages per rpc         rpcs   % cum % |       rpcs   % cum %
+1:		         4  40  40   |          1  10  10
+256:		         6  60 100   |          9  90 100
+
+			read			write
+rpcs in flight        rpcs   % cum % |       rpcs   % cum %
+1:		        10 100 100   |          8  80  80
+2:		         0   0 100   |          2  20 100
+
+			read			write
+offset                rpcs   % cum % |       rpcs   % cum %
+0:		        10 100 100   |         10 100 100
+osc.fs-OST0000-osc-ffff8dc8f8d4b000.cur_dirty_bytes=4194304
+osc.fs-OST0000-osc-ffff8dc8f8d4b000.cur_grant_bytes=276824064
+osc.fs-OST0000-osc-ffff8dc8f8d4b000.max_rpcs_in_flight=8
+osc.fs-OST0001-osc-ffff8dc8f8d4b000.max_rpcs_in_flight=8
+osc.fs-OST0000-osc-ffff8dc8f8d4b000.import=
+import:
+    name: fs-OST0000-osc-ffff8dc8f8d4b000
+    target: fs-OST0000_UUID
+    state: FULL
+    import_flags: [ replayable, pingable, connect_tried ]
+    connection:
+       failover_nids: [ 10.0.0.1@tcp ]
+       current_connection: 10.0.0.1@tcp
+       connection_attempts: 2
+       generation: 2
+       in-progress_invalidations: 0
+       idle: 3 sec
+    rpcs:
+       inflight: 0
+       unregistering: 0
+       timeouts: 1
+       avg_waittime: 1437 usec
+    service_estimates:
+       services: 1 sec
+       network: 1 sec
+    transactions:
+       last_replay: 0
+       peer_committed: 0
+       last_checked: 0
+osc.fs-OST0000-osc-ffff8dc8f8d4b000.state=
+current_state: FULL
+state_history:
+ - [ 1700000000, CONNECTING ]
+ - [ 1700000000, FULL ]
+"#;
+
+        let result: (Vec<_>, _) = many(parse()).parse(x).unwrap();
+
+        assert_debug_snapshot!(result)
+    }
+}
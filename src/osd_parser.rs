@@ -6,7 +6,10 @@ use crate::{
     base_parsers::{digits, param, period, target, till_newline, till_period},
     brw_stats_parser::brw_stats,
     quota::quota_parser::quota_stats_osd,
-    types::{BrwStats, Param, Record, Target, TargetStat, TargetStats, TargetVariant},
+    types::{
+        BrwStats, Param, Record, Target, TargetStat, TargetStats, TargetVariant,
+        UnsignedLustreTimestamp,
+    },
     QuotaKind, QuotaStatsOsd,
 };
 use combine::{
@@ -59,7 +62,7 @@ enum OsdStat {
     KBytesFree(u64),
     /// Total disk space
     KBytesTotal(u64),
-    BrwStats(Vec<BrwStats>),
+    BrwStats(Option<UnsignedLustreTimestamp>, Vec<BrwStats>),
     QuotaStats(QuotaStatsOsd),
 }
 
@@ -89,7 +92,10 @@ where
     I::Error: ParseError<I::Token, I::Range, I::Position>,
 {
     choice((
-        (param(BRW_STATS), brw_stats().map(OsdStat::BrwStats)),
+        (
+            param(BRW_STATS),
+            brw_stats().map(|(time, x)| OsdStat::BrwStats(time, x)),
+        ),
         (
             param(FILES_FREE),
             digits().skip(newline()).map(OsdStat::FilesFree),
@@ -159,48 +165,56 @@ where
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             OsdStat::FilesTotal(value) => TargetStats::FilesTotal(TargetStat {
                 kind,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             OsdStat::FsType(value) => TargetStats::FsType(TargetStat {
                 kind,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             OsdStat::KBytesAvail(value) => TargetStats::KBytesAvail(TargetStat {
                 kind,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             OsdStat::KBytesFree(value) => TargetStats::KBytesFree(TargetStat {
                 kind,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             OsdStat::KBytesTotal(value) => TargetStats::KBytesTotal(TargetStat {
                 kind,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
-            OsdStat::BrwStats(value) => TargetStats::BrwStats(TargetStat {
+            OsdStat::BrwStats(snapshot_time, value) => TargetStats::BrwStats(TargetStat {
                 kind,
                 target,
                 param,
                 value,
+                snapshot_time,
             }),
             OsdStat::QuotaStats(value) => TargetStats::QuotaStatsOsd(TargetStat {
                 kind,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
         })
         .map(Record::Target)
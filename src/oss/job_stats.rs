@@ -2,15 +2,9 @@
 // Use of this source code is governed by a MIT-style
 // license that can be found in the LICENSE file.
 
-use crate::types::{JobStatOst, JobStatsOst};
+use crate::{job_stats_parser, types::JobStatOst};
 use combine::{
-    attempt, eof,
     error::{ParseError, StreamError},
-    optional,
-    parser::{
-        char::{alpha_num, newline},
-        repeat::take_until,
-    },
     stream::{Stream, StreamErrorFor},
     Parser,
 };
@@ -20,26 +14,22 @@ where
     I: Stream<Token = char>,
     I::Error: ParseError<I::Token, I::Range, I::Position>,
 {
-    (
-        optional(newline()), // If Jobstats are present, the whole yaml blob will be on a newline
-        take_until(attempt((newline(), alpha_num()).map(drop).or(eof()))),
-    )
-        .skip(optional(newline()))
-        .skip(optional(eof()))
-        .and_then(|(_, x): (_, String)| {
-            serde_yaml::from_str(&x)
-                .map(|x: JobStatsOst| x.job_stats)
-                .map_err(StreamErrorFor::<I>::other)
-        })
+    job_stats_parser::parse().and_then(|xs| {
+        xs.map(|xs| xs.into_iter().map(JobStatOst::try_from).collect())
+            .transpose()
+            .map_err(StreamErrorFor::<I>::other)
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::JobStatsOst;
     use crate::{
         types::{BytesStat, ReqsStat},
         UnsignedLustreTimestamp,
     };
+    use combine::EasyParser;
 
     #[test]
     fn test_yaml_deserialize() {
@@ -119,10 +109,12 @@ mod tests {
                     samples: 0,
                     unit: "reqs".to_string(),
                 },
+                job_id_fields: None,
             }]),
         };
 
-        assert_eq!(serde_yaml::from_str::<JobStatsOst>(x).unwrap(), expected)
+        assert_eq!(serde_yaml::from_str::<JobStatsOst>(x).unwrap(), expected);
+        assert_eq!(parse().easy_parse(x).unwrap(), (expected.job_stats, ""));
     }
 
     #[test]
@@ -148,6 +140,10 @@ mod tests {
   quotactl:        { samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }
   prealloc:        { samples:           0, unit: usecs, min:        0, max:        0, sum:                0, sumsq:                  0 }"#;
 
-        insta::assert_debug_snapshot!(serde_yaml::from_str::<JobStatsOst>(x).unwrap());
+        let expected = serde_yaml::from_str::<JobStatsOst>(x).unwrap();
+
+        assert_eq!(parse().easy_parse(x).unwrap().0, expected.job_stats);
+
+        insta::assert_debug_snapshot!(expected);
     }
 }
@@ -5,9 +5,13 @@
 use crate::{
     base_parsers::{digits, param, param_period, period, target},
     exports_parser::exports_stats,
+    lfsck_parser::lfsck,
     oss::job_stats,
     stats_parser::stats,
-    types::{JobStatOst, Param, Record, Stat, Target, TargetStat, TargetStats, TargetVariant},
+    types::{
+        JobStatOst, LfsckStats, Param, Record, Stat, Target, TargetStat, TargetStats,
+        TargetVariant, UnsignedLustreTimestamp,
+    },
     ExportStats,
 };
 use combine::{
@@ -25,11 +29,12 @@ pub(crate) const NUM_EXPORTS: &str = "num_exports";
 pub(crate) const TOT_DIRTY: &str = "tot_dirty";
 pub(crate) const TOT_GRANTED: &str = "tot_granted";
 pub(crate) const TOT_PENDING: &str = "tot_pending";
+pub(crate) const LFSCK_LAYOUT: &str = "lfsck_layout";
 
 pub(crate) const EXPORTS: &str = "exports";
 pub(crate) const EXPORTS_PARAMS: &str = "exports.*.stats";
 
-pub(crate) const OBD_STATS: [&str; 7] = [
+pub(crate) const OBD_STATS: [&str; 8] = [
     JOBSTATS,
     STATS,
     NUM_EXPORTS,
@@ -37,6 +42,7 @@ pub(crate) const OBD_STATS: [&str; 7] = [
     TOT_GRANTED,
     TOT_PENDING,
     EXPORTS_PARAMS,
+    LFSCK_LAYOUT,
 ];
 
 /// Takes OBD_STATS and produces a list of params for
@@ -62,12 +68,13 @@ where
 #[derive(Debug)]
 enum ObdfilterStat {
     JobStats(Option<Vec<JobStatOst>>),
-    Stats(Vec<Stat>),
+    Stats(Option<UnsignedLustreTimestamp>, Vec<Stat>),
     ExportStats(Vec<ExportStats>),
     NumExports(u64),
     TotDirty(u64),
     TotGranted(u64),
     TotPending(u64),
+    LfsckLayout(LfsckStats),
 }
 
 fn obdfilter_stat<I>() -> impl Parser<I, Output = (Param, ObdfilterStat)>
@@ -80,7 +87,10 @@ where
             param(JOBSTATS),
             job_stats::parse().map(ObdfilterStat::JobStats),
         ),
-        (param(STATS), stats().map(ObdfilterStat::Stats)),
+        (
+            param(STATS),
+            stats().map(|(time, xs)| ObdfilterStat::Stats(time, xs)),
+        ),
         (
             param(NUM_EXPORTS),
             digits().skip(newline()).map(ObdfilterStat::NumExports),
@@ -101,6 +111,7 @@ where
             param_period(EXPORTS),
             exports_stats().map(ObdfilterStat::ExportStats),
         ),
+        (param(LFSCK_LAYOUT), lfsck().map(ObdfilterStat::LfsckLayout)),
     ))
     .message("while parsing obdfilter")
 }
@@ -117,42 +128,56 @@ where
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
-            ObdfilterStat::Stats(value) => TargetStats::Stats(TargetStat {
+            ObdfilterStat::Stats(snapshot_time, value) => TargetStats::Stats(TargetStat {
                 kind: TargetVariant::Ost,
                 target,
                 param,
                 value,
+                snapshot_time,
             }),
             ObdfilterStat::NumExports(value) => TargetStats::NumExports(TargetStat {
                 kind: TargetVariant::Ost,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             ObdfilterStat::TotDirty(value) => TargetStats::TotDirty(TargetStat {
                 kind: TargetVariant::Ost,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             ObdfilterStat::TotGranted(value) => TargetStats::TotGranted(TargetStat {
                 kind: TargetVariant::Ost,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             ObdfilterStat::TotPending(value) => TargetStats::TotPending(TargetStat {
                 kind: TargetVariant::Ost,
                 target,
                 param,
                 value,
+                snapshot_time: None,
             }),
             ObdfilterStat::ExportStats(value) => TargetStats::ExportStats(TargetStat {
                 kind: TargetVariant::Ost,
                 target,
                 param,
                 value,
+                snapshot_time: None,
+            }),
+            ObdfilterStat::LfsckLayout(value) => TargetStats::LfsckLayout(TargetStat {
+                kind: TargetVariant::Ost,
+                target,
+                param,
+                value,
+                snapshot_time: None,
             }),
         })
         .map(Record::Target)
@@ -162,6 +187,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::LfsckStatus;
 
     #[test]
     fn test_target_name() {
@@ -172,4 +198,59 @@ mod tests {
             Ok((Target("fs-OST0000".to_string()), "num_exports="))
         );
     }
+
+    #[test]
+    fn test_lfsck_layout() {
+        let x = r#"obdfilter.fs-OST0000.lfsck_layout=
+name: lfsck_layout
+magic: 0xb1732fed
+version: 2
+status: scanning-phase2
+flags:
+param:
+last_completed_time: N/A
+time_since_last_completed: N/A
+latest_start_time: 1700000000
+time_since_latest_start: 8 seconds
+last_checkpoint_time: N/A
+time_since_last_checkpoint: N/A
+latest_start_position: 0
+last_checkpoint_position: 0
+first_failure_position: 0
+success_count: 0
+repaired_dangling: 0
+repaired_unmatched_pair: 0
+repaired_multiple_referenced: 0
+repaired_orphan: 4
+repaired_inconsistent_owner: 0
+repaired_others: 0
+skipped: 0
+failed_phase1: 0
+failed_phase2: 0
+checked_phase1: 8812
+checked_phase2: 120
+run_time_phase1: 7 seconds
+run_time_phase2: 1 seconds
+average_speed_phase1: 1258 items/sec
+average_speed_phase2: N/A
+real_time_speed_phase1: N/A
+real_time_speed_phase2: 120 items/sec
+current_position: 3012
+"#;
+
+        let (record, rest) = parse().parse(x).unwrap();
+
+        assert_eq!(rest, "");
+
+        let Record::Target(TargetStats::LfsckLayout(x)) = record else {
+            panic!("Expected LfsckLayout, got {record:?}");
+        };
+
+        assert_eq!(x.target, Target("fs-OST0000".to_string()));
+        assert_eq!(x.value.status, LfsckStatus::ScanningPhase2);
+        assert_eq!(x.value.checked_phase1, 8812);
+        assert_eq!(x.value.repaired.get("repaired_orphan"), Some(&4));
+        assert_eq!(x.value.average_speed_phase2, None);
+        assert_eq!(x.value.real_time_speed_phase2, Some(120));
+    }
 }
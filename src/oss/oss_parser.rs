@@ -5,7 +5,7 @@
 use crate::{
     base_parsers::{equals, period},
     stats_parser::stats,
-    types::{Param, Record, Stat, TargetStats},
+    types::{Param, Record, Stat, TargetStats, UnsignedLustreTimestamp},
     OssStat,
 };
 use combine::{attempt, choice, error::ParseError, parser::char::string, stream::Stream, Parser};
@@ -52,7 +52,7 @@ where
         .message("while parsing `oss_suffix`")
 }
 
-fn oss_stat<I>() -> impl Parser<I, Output = (Param, Vec<Stat>)>
+fn oss_stat<I>() -> impl Parser<I, Output = (Param, (Option<UnsignedLustreTimestamp>, Vec<Stat>))>
 where
     I: Stream<Token = char>,
     I::Error: ParseError<I::Token, I::Range, I::Position>,
@@ -77,7 +77,13 @@ where
 {
     oss_prefix()
         .with(oss_stat())
-        .map(|(param, stats)| TargetStats::Oss(OssStat { param, stats }))
+        .map(|(param, (snapshot_time, stats))| {
+            TargetStats::Oss(OssStat {
+                param,
+                stats,
+                snapshot_time,
+            })
+        })
         .map(Record::Target)
         .message("while parsing oss")
 }
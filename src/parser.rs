@@ -3,11 +3,11 @@
 // license that can be found in the LICENSE file.
 
 use crate::{
-    ldlm, llite, mdd_parser,
+    ldlm, llite, lod_parser, mdc, mdd_parser,
     mds::{self, client_count_parser},
     mgs::mgs_parser,
-    osd_parser, oss, quota, top_level_parser,
-    types::Record,
+    osc, osd_parser, oss, quota, top_level_parser,
+    types::{Diagnostic, Param, Record},
 };
 use combine::{choice, error::ParseError, many, Parser, Stream};
 
@@ -21,29 +21,101 @@ pub fn params() -> Vec<String> {
         .chain(mds::params())
         .chain(ldlm::params())
         .chain(llite::params())
+        .chain(osc::params())
+        .chain(mdc::params())
         .chain(mdd_parser::params())
+        .chain(lod_parser::params())
         .chain(quota::params())
         .collect()
 }
 
-pub fn parse<I>() -> impl Parser<I, Output = Vec<Record>>
+/// Parses a single param block.
+pub(crate) fn block<I>() -> impl Parser<I, Output = Vec<Record>>
 where
     I: Stream<Token = char>,
     I::Error: ParseError<I::Token, I::Range, I::Position>,
 {
-    many(choice((
+    choice((
         top_level_parser::parse().map(|x| vec![x]),
+        top_level_parser::version().map(|x| x.into_iter().collect()),
         client_count_parser::parse(),
         osd_parser::parse().map(|x| vec![x]),
         mgs_parser::parse().map(|x| vec![x]),
         oss::parse().map(|x| vec![x]),
         mds::parse().map(|x| vec![x]),
         ldlm::parse().map(|x| vec![x]),
+        lod_parser::parse().map(|x| vec![x]),
         llite::parse().map(|x| vec![x]),
+        osc::parse().map(|x| vec![x]),
+        mdc::parse().map(|x| vec![x]),
         mdd_parser::parse().map(|x| vec![x]),
         quota::parse().map(|x| vec![x]),
-    )))
-    .map(|xs: Vec<_>| xs.into_iter().flatten().collect())
+    ))
+}
+
+pub fn parse<I>() -> impl Parser<I, Output = Vec<Record>>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    many(block()).map(|xs: Vec<_>| xs.into_iter().flatten().collect())
+}
+
+/// Returns the param name if `x` starts with a `param=` line.
+pub(crate) fn param_line(x: &str) -> Option<&str> {
+    let (name, _) = x.lines().next()?.split_once('=')?;
+
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._-@*".contains(c))
+        && (name.contains('.') || top_level_parser::TOP_LEVEL_PARAMS.contains(&name));
+
+    valid.then_some(name)
+}
+
+/// Returns the offset of the next `param=` line after the first line of `x`,
+/// or the length of `x` if there is none.
+fn next_param_line(x: &str) -> usize {
+    x.match_indices('\n')
+        .map(|(i, _)| i + 1)
+        .find(|&i| param_line(&x[i..]).is_some())
+        .unwrap_or(x.len())
+}
+
+/// Like [`parse`], but instead of stopping at the first param block that
+/// doesn't parse, skips to the next `param=` line and carries on.
+///
+/// Every skipped block is returned as a [`Record::Diagnostic`].
+pub fn parse_lenient(input: &str) -> Vec<Record> {
+    let mut records = vec![];
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        match block().parse(rest) {
+            Ok((mut xs, tail)) if tail.len() < rest.len() => {
+                records.append(&mut xs);
+                rest = tail;
+            }
+            _ => {
+                let (skipped, tail) = rest.split_at(next_param_line(rest));
+
+                if !skipped.trim().is_empty() {
+                    let offset = input.len() - rest.len();
+
+                    records.push(Record::Diagnostic(Diagnostic {
+                        param: param_line(skipped).map(|x| Param(x.to_string())),
+                        line: input[..offset].matches('\n').count() + 1,
+                        text: skipped.to_string(),
+                    }));
+                }
+
+                rest = tail;
+            }
+        }
+    }
+
+    records
 }
 
 #[cfg(test)]
@@ -76,6 +148,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_lenient_valid_fixtures() {
+        for file in VALID_FIXTURES
+            .find("**/*")
+            .unwrap()
+            .filter_map(|x| x.as_file())
+        {
+            let contents = file.contents_utf8().unwrap();
+
+            let (strict, _) = parse().easy_parse(contents).unwrap();
+
+            assert_eq!(parse_lenient(contents), strict, "{:?}", file.path());
+        }
+    }
+
+    #[test]
+    fn test_lenient_skips_unknown() {
+        let x = r#"memused=1
+obdfilter.fs-OST0000.stats=
+snapshot_time             1700000000.000000000 secs.nsecs
+new_field, not a stat
+obdfilter.fs-OST0000.new_param=42
+memused_max=2
+"#;
+
+        assert!(crate::parse_lctl_output(x.as_bytes()).is_err());
+
+        assert_debug_snapshot!(parse_lenient(x));
+    }
+
     #[test]
     fn test_params() {
         assert_debug_snapshot!(params());
@@ -0,0 +1,2326 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Renders parsed [`Record`]s in the Prometheus text exposition format.
+
+use crate::{
+    jobid::{JobIdFields, JobIdFormat},
+    rates::Rate,
+    report::FilesystemReport,
+    types::{
+        lnet_exports, BrwStats, BytesStat, HostStats, HsmAction, HsmActionCount, HsmActionStatus,
+        HsmControl, ImportState, JobStatMdt, JobStatOst, LNetNiStatus, LNetPeerStat, LNetRouteStat,
+        LNetStats, LfsckStats, LfsckStatus, LustreServiceStats, NodeStats, ObdDeviceStatus,
+        QuotaKind, Record, RecoveryStatus, ReqsStat, Stat, TargetStats, TargetVariant,
+    },
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MetricType {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricType {
+    fn as_str(self) -> &'static str {
+        match self {
+            MetricType::Counter => "counter",
+            MetricType::Gauge => "gauge",
+            MetricType::Histogram => "histogram",
+        }
+    }
+}
+
+type Labels = Vec<(&'static str, String)>;
+
+struct Sample {
+    suffix: &'static str,
+    labels: Labels,
+    value: String,
+}
+
+struct Family {
+    name: &'static str,
+    help: &'static str,
+    kind: MetricType,
+    samples: Vec<Sample>,
+}
+
+/// Collects samples grouped by metric family, preserving the order
+/// in which families were first seen.
+#[derive(Default)]
+struct Registry {
+    families: Vec<Family>,
+    index: HashMap<&'static str, usize>,
+}
+
+impl Registry {
+    fn family(&mut self, name: &'static str, help: &'static str, kind: MetricType) -> &mut Family {
+        let idx = *self.index.entry(name).or_insert_with(|| {
+            self.families.push(Family {
+                name,
+                help,
+                kind,
+                samples: vec![],
+            });
+
+            self.families.len() - 1
+        });
+
+        &mut self.families[idx]
+    }
+
+    fn gauge(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        labels: Labels,
+        value: impl ToString,
+    ) {
+        self.family(name, help, MetricType::Gauge)
+            .samples
+            .push(Sample {
+                suffix: "",
+                labels,
+                value: value.to_string(),
+            });
+    }
+
+    fn counter(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        labels: Labels,
+        value: impl ToString,
+    ) {
+        self.family(name, help, MetricType::Counter)
+            .samples
+            .push(Sample {
+                suffix: "",
+                labels,
+                value: value.to_string(),
+            });
+    }
+
+    /// Adds a cumulative histogram built from non-cumulative `(lower bound, count)` buckets.
+    ///
+    /// Lustre tallies values into buckets named after their lower bound (see
+    /// `lprocfs_oh_tally_log2`), so a bucket's `le` is the lower bound of the
+    /// next one and the last bucket only counts towards `+Inf`. Lustre doesn't
+    /// report the sum of the values, so there is no `_sum`.
+    fn histogram(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        labels: Labels,
+        buckets: impl IntoIterator<Item = (u64, u64)>,
+    ) {
+        let family = self.family(name, help, MetricType::Histogram);
+
+        let mut total = 0;
+        let mut buckets = buckets.into_iter().peekable();
+
+        while let Some((_, count)) = buckets.next() {
+            total += count;
+
+            let Some((le, _)) = buckets.peek() else {
+                break;
+            };
+
+            family.samples.push(Sample {
+                suffix: "_bucket",
+                labels: with_label(labels.clone(), "le", le),
+                value: total.to_string(),
+            });
+        }
+
+        family.samples.push(Sample {
+            suffix: "_bucket",
+            labels: with_label(labels.clone(), "le", "+Inf"),
+            value: total.to_string(),
+        });
+        family.samples.push(Sample {
+            suffix: "_count",
+            labels,
+            value: total.to_string(),
+        });
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+
+        for family in &self.families {
+            let _ = writeln!(out, "# HELP {} {}", family.name, family.help);
+            let _ = writeln!(out, "# TYPE {} {}", family.name, family.kind.as_str());
+
+            for sample in &family.samples {
+                out.push_str(family.name);
+                out.push_str(sample.suffix);
+
+                if !sample.labels.is_empty() {
+                    let labels = sample
+                        .labels
+                        .iter()
+                        .map(|(k, v)| format!("{k}=\"{}\"", escape_label_value(v)))
+                        .collect::<Vec<_>>()
+                        .join(",");
+
+                    let _ = write!(out, "{{{labels}}}");
+                }
+
+                let _ = writeln!(out, " {}", sample.value);
+            }
+        }
+
+        out
+    }
+}
+
+fn escape_label_value(x: &str) -> String {
+    x.replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+fn target_labels(kind: TargetVariant, target: &str) -> Labels {
+    vec![("kind", kind.to_string()), ("target", target.to_string())]
+}
+
+fn client_labels(kind: TargetVariant, target: &str, client: &str) -> Labels {
+    with_label(target_labels(kind, target), "client", client)
+}
+
+fn with_label(mut labels: Labels, name: &'static str, value: impl ToString) -> Labels {
+    labels.push((name, value.to_string()));
+
+    labels
+}
+
+/// Emits `<name>_samples_total` and, when present, `<name>_sum_total`
+/// for every line of a Lustre stats block.
+fn stats_families(
+    registry: &mut Registry,
+    samples: (&'static str, &'static str),
+    sum: (&'static str, &'static str),
+    labels: &Labels,
+    stats: &[Stat],
+) {
+    for stat in stats {
+        let labels = with_label(labels.clone(), "operation", &stat.name);
+        let labels = with_label(labels, "units", &stat.units);
+
+        registry.counter(samples.0, samples.1, labels.clone(), stat.samples);
+
+        if let Some(x) = stat.sum {
+            registry.counter(sum.0, sum.1, labels, x);
+        }
+    }
+}
+
+/// Emits a read and a write histogram for every section of a
+/// `brw_stats` style block, in the family `family` returns for the section.
+fn rw_histograms(
+    registry: &mut Registry,
+    family: fn(&str) -> (&'static str, &'static str),
+    labels: &Labels,
+    sections: &[BrwStats],
+) {
+    for section in sections {
+        let (name, help) = family(&section.name);
+
+        let labels = with_label(labels.clone(), "name", &section.name);
+        let labels = with_label(labels, "units", &section.unit);
+
+        registry.histogram(
+            name,
+            help,
+            with_label(labels.clone(), "operation", "read"),
+            section.buckets.iter().map(|x| (x.name, x.read)),
+        );
+        registry.histogram(
+            name,
+            help,
+            with_label(labels, "operation", "write"),
+            section.buckets.iter().map(|x| (x.name, x.write)),
+        );
+    }
+}
+
+/// The `brw_stats` sections count different things, one family per unit.
+fn brw_stats_family(name: &str) -> (&'static str, &'static str) {
+    match name {
+        "pages" | "discont_pages" => (
+            "lustre_brw_pages",
+            "Pages per bulk read/write and discontiguous pages per I/O, from brw_stats.",
+        ),
+        "discont_blocks" => (
+            "lustre_brw_blocks",
+            "Discontiguous blocks per I/O, from brw_stats.",
+        ),
+        "dio_frags" => (
+            "lustre_brw_fragments",
+            "Disk fragments per I/O, from brw_stats.",
+        ),
+        "rpc_hist" => (
+            "lustre_brw_ios_in_flight",
+            "Disk I/Os in flight when an I/O is started, from brw_stats.",
+        ),
+        "io_time" | "block_maps_msec" => (
+            "lustre_brw_time_milliseconds",
+            "I/O completion and block mapping times, from brw_stats.",
+        ),
+        "disk_iosize" => (
+            "lustre_brw_io_size_bytes",
+            "Disk I/O sizes, from brw_stats.",
+        ),
+        _ => (
+            "lustre_brw_other",
+            "Other block read/write histograms from brw_stats.",
+        ),
+    }
+}
+
+/// Like [`brw_stats_family`] for the sections of `rpc_stats`.
+fn rpc_stats_family(name: &str) -> (&'static str, &'static str) {
+    match name {
+        "rpcs_in_flight" => (
+            "lustre_client_rpcs_in_flight",
+            "RPCs in flight when an RPC is sent, from rpc_stats.",
+        ),
+        _ => (
+            "lustre_client_rpc_pages",
+            "Pages per RPC and RPC offsets in pages, from rpc_stats.",
+        ),
+    }
+}
+
+fn lfsck_stats(registry: &mut Registry, labels: Labels, x: &LfsckStats) {
+    registry.gauge(
+        "lustre_lfsck_status",
+        "Current status of the LFSCK component.",
+        with_label(labels.clone(), "status", lfsck_status_name(x.status)),
+        1,
+    );
+    registry.counter(
+        "lustre_lfsck_success_total",
+        "Number of completed LFSCK runs.",
+        labels.clone(),
+        x.success_count,
+    );
+
+    for (phase, checked, failed, run_time, average_speed, real_time_speed) in [
+        (
+            "1",
+            x.checked_phase1,
+            x.failed_phase1,
+            x.run_time_phase1,
+            x.average_speed_phase1,
+            x.real_time_speed_phase1,
+        ),
+        (
+            "2",
+            x.checked_phase2,
+            x.failed_phase2,
+            x.run_time_phase2,
+            x.average_speed_phase2,
+            x.real_time_speed_phase2,
+        ),
+    ] {
+        let labels = with_label(labels.clone(), "phase", phase);
+
+        registry.gauge(
+            "lustre_lfsck_checked",
+            "Objects checked by the latest LFSCK run.",
+            labels.clone(),
+            checked,
+        );
+        registry.gauge(
+            "lustre_lfsck_failed",
+            "Objects the latest LFSCK run failed to check or repair.",
+            labels.clone(),
+            failed,
+        );
+
+        if let Some(x) = run_time {
+            registry.gauge(
+                "lustre_lfsck_run_time_seconds",
+                "Run time of the latest LFSCK run.",
+                labels.clone(),
+                x,
+            );
+        }
+
+        if let Some(x) = average_speed {
+            registry.gauge(
+                "lustre_lfsck_average_speed",
+                "Average LFSCK speed in items per second.",
+                labels.clone(),
+                x,
+            );
+        }
+
+        if let Some(x) = real_time_speed {
+            registry.gauge(
+                "lustre_lfsck_real_time_speed",
+                "Current LFSCK speed in items per second.",
+                labels,
+                x,
+            );
+        }
+    }
+
+    for (name, value) in &x.repaired {
+        registry.gauge(
+            "lustre_lfsck_repaired",
+            "Inconsistencies repaired by the latest LFSCK run.",
+            with_label(labels.clone(), "type", name),
+            *value,
+        );
+    }
+
+    if let Some(x) = x.time_since_last_completed {
+        registry.gauge(
+            "lustre_lfsck_time_since_last_completed_seconds",
+            "Time since LFSCK last completed.",
+            labels,
+            x,
+        );
+    }
+}
+
+/// Labels of a job, taking its parts from `job_id_fields` when the record was
+/// annotated and decomposing `job_id` with `jobid` otherwise.
+fn job_labels(
+    labels: &Labels,
+    jobid: &JobIdFormat,
+    job_id: &str,
+    fields: Option<&JobIdFields>,
+) -> Labels {
+    let mut labels = with_label(labels.clone(), "job_id", job_id);
+
+    if let Some(x) = fields.cloned().or_else(|| jobid.decompose(job_id)) {
+        labels.extend(x.labels());
+    }
+
+    labels
+}
+
+fn job_op_bytes(registry: &mut Registry, labels: &Labels, op: &str, x: &BytesStat) {
+    let labels = with_label(labels.clone(), "operation", op);
+    let labels = with_label(labels, "units", &x.unit);
+
+    registry.counter(
+        "lustre_job_stats_samples_total",
+        "Number of operations performed per job.",
+        labels.clone(),
+        x.samples,
+    );
+    registry.counter(
+        "lustre_job_stats_sum_total",
+        "Sum of the operation values per job (bytes or usecs, see units).",
+        labels.clone(),
+        x.sum,
+    );
+    registry.gauge(
+        "lustre_job_stats_min",
+        "Minimum operation value per job.",
+        labels.clone(),
+        x.min,
+    );
+    registry.gauge(
+        "lustre_job_stats_max",
+        "Maximum operation value per job.",
+        labels,
+        x.max,
+    );
+}
+
+fn job_op_reqs(registry: &mut Registry, labels: &Labels, op: &str, x: &ReqsStat) {
+    let labels = with_label(labels.clone(), "operation", op);
+    let labels = with_label(labels, "units", &x.unit);
+
+    registry.counter(
+        "lustre_job_stats_samples_total",
+        "Number of operations performed per job.",
+        labels,
+        x.samples,
+    );
+}
+
+fn job_stats_ost(registry: &mut Registry, labels: &Labels, jobid: &JobIdFormat, xs: &[JobStatOst]) {
+    for x in xs {
+        let labels = job_labels(labels, jobid, &x.job_id, x.job_id_fields.as_ref());
+
+        job_op_bytes(registry, &labels, "read_bytes", &x.read_bytes);
+        job_op_bytes(registry, &labels, "write_bytes", &x.write_bytes);
+
+        for (op, stat) in [
+            ("getattr", &x.getattr),
+            ("setattr", &x.setattr),
+            ("punch", &x.punch),
+            ("sync", &x.sync),
+            ("destroy", &x.destroy),
+            ("create", &x.create),
+            ("statfs", &x.statfs),
+            ("get_info", &x.get_info),
+            ("set_info", &x.set_info),
+            ("quotactl", &x.quotactl),
+        ] {
+            job_op_reqs(registry, &labels, op, stat);
+        }
+    }
+}
+
+fn job_stats_mdt(registry: &mut Registry, labels: &Labels, jobid: &JobIdFormat, xs: &[JobStatMdt]) {
+    for x in xs {
+        let labels = job_labels(labels, jobid, &x.job_id, x.job_id_fields.as_ref());
+
+        for (op, stat) in [
+            ("open", Some(&x.open)),
+            ("close", Some(&x.close)),
+            ("mknod", Some(&x.mknod)),
+            ("link", Some(&x.link)),
+            ("unlink", Some(&x.unlink)),
+            ("mkdir", Some(&x.mkdir)),
+            ("rmdir", Some(&x.rmdir)),
+            ("rename", Some(&x.rename)),
+            ("getattr", Some(&x.getattr)),
+            ("setattr", Some(&x.setattr)),
+            ("getxattr", Some(&x.getxattr)),
+            ("setxattr", Some(&x.setxattr)),
+            ("statfs", Some(&x.statfs)),
+            ("sync", Some(&x.sync)),
+            ("samedir_rename", Some(&x.samedir_rename)),
+            ("crossdir_rename", Some(&x.crossdir_rename)),
+            ("read_bytes", Some(&x.read_bytes)),
+            ("write_bytes", Some(&x.write_bytes)),
+            ("punch", Some(&x.punch)),
+            ("parallel_rename_dir", x.parallel_rename_dir.as_ref()),
+            ("parallel_rename_file", x.parallel_rename_file.as_ref()),
+        ] {
+            if let Some(stat) = stat {
+                job_op_bytes(registry, &labels, op, stat);
+            }
+        }
+    }
+}
+
+fn recovery_status_name(x: RecoveryStatus) -> &'static str {
+    match x {
+        RecoveryStatus::Complete => "complete",
+        RecoveryStatus::Inactive => "inactive",
+        RecoveryStatus::Waiting => "waiting",
+        RecoveryStatus::WaitingForClients => "waiting_for_clients",
+        RecoveryStatus::Recovering => "recovering",
+        RecoveryStatus::Unknown => "unknown",
+    }
+}
+
+fn import_state_name(x: ImportState) -> &'static str {
+    match x {
+        ImportState::Closed => "CLOSED",
+        ImportState::New => "NEW",
+        ImportState::Disconn => "DISCONN",
+        ImportState::Connecting => "CONNECTING",
+        ImportState::Replay => "REPLAY",
+        ImportState::ReplayLocks => "REPLAY_LOCKS",
+        ImportState::ReplayWait => "REPLAY_WAIT",
+        ImportState::Recover => "RECOVER",
+        ImportState::Full => "FULL",
+        ImportState::Evicted => "EVICTED",
+        ImportState::Idle => "IDLE",
+        ImportState::Unknown => "UNKNOWN",
+    }
+}
+
+fn lfsck_status_name(x: LfsckStatus) -> &'static str {
+    match x {
+        LfsckStatus::Init => "init",
+        LfsckStatus::ScanningPhase1 => "scanning-phase1",
+        LfsckStatus::ScanningPhase2 => "scanning-phase2",
+        LfsckStatus::Completed => "completed",
+        LfsckStatus::Failed => "failed",
+        LfsckStatus::Stopped => "stopped",
+        LfsckStatus::Paused => "paused",
+        LfsckStatus::Crashed => "crashed",
+        LfsckStatus::Partial => "partial",
+        LfsckStatus::CoFailed => "co-failed",
+        LfsckStatus::CoStopped => "co-stopped",
+        LfsckStatus::CoPaused => "co-paused",
+        LfsckStatus::Unknown => "unknown",
+    }
+}
+
+fn hsm_control_name(x: HsmControl) -> &'static str {
+    match x {
+        HsmControl::Enabled => "enabled",
+        HsmControl::Disabled => "disabled",
+        HsmControl::Stopped => "stopped",
+        HsmControl::Shutdown => "shutdown",
+        HsmControl::Unknown => "unknown",
+    }
+}
+
+fn hsm_action_name(x: HsmAction) -> &'static str {
+    match x {
+        HsmAction::Noop => "noop",
+        HsmAction::Archive => "archive",
+        HsmAction::Restore => "restore",
+        HsmAction::Remove => "remove",
+        HsmAction::Cancel => "cancel",
+        HsmAction::Unknown => "unknown",
+    }
+}
+
+fn hsm_action_status_name(x: HsmActionStatus) -> &'static str {
+    match x {
+        HsmActionStatus::Waiting => "waiting",
+        HsmActionStatus::Started => "started",
+        HsmActionStatus::Succeed => "succeed",
+        HsmActionStatus::Failed => "failed",
+        HsmActionStatus::Canceled => "canceled",
+        HsmActionStatus::Unknown => "unknown",
+    }
+}
+
+fn hsm_action_labels(labels: &Labels, x: &HsmActionCount) -> Labels {
+    let labels = with_label(labels.clone(), "archive_id", x.archive_id);
+
+    with_label(labels, "action", hsm_action_name(x.action))
+}
+
+fn obd_device_status_name(x: ObdDeviceStatus) -> &'static str {
+    match x {
+        ObdDeviceStatus::Up => "up",
+        ObdDeviceStatus::Stopping => "stopping",
+        ObdDeviceStatus::Inactive => "inactive",
+        ObdDeviceStatus::Attached => "attached",
+        ObdDeviceStatus::Unknown => "unknown",
+    }
+}
+
+fn quota_kind_name(x: &QuotaKind) -> &'static str {
+    match x {
+        QuotaKind::Usr => "user",
+        QuotaKind::Grp => "group",
+        QuotaKind::Prj => "project",
+    }
+}
+
+fn host_stats(registry: &mut Registry, x: &HostStats) {
+    match x {
+        HostStats::Memused(x) => registry.gauge(
+            "lustre_mem_used_bytes",
+            "Bytes of memory currently used by Lustre.",
+            vec![],
+            x.value,
+        ),
+        HostStats::MemusedMax(x) => registry.gauge(
+            "lustre_mem_used_max_bytes",
+            "Maximum bytes of memory used by Lustre since load.",
+            vec![],
+            x.value,
+        ),
+        HostStats::LNetMemUsed(x) => registry.gauge(
+            "lustre_lnet_mem_used_bytes",
+            "Bytes of memory currently used by LNet.",
+            vec![],
+            x.value,
+        ),
+        HostStats::HealthCheck(x) => {
+            registry.gauge(
+                "lustre_health_healthy",
+                "Whether the node reports itself healthy (1) or not (0).",
+                vec![],
+                u8::from(x.value.healthy),
+            );
+
+            for target in &x.value.targets {
+                registry.gauge(
+                    "lustre_health_unhealthy_target",
+                    "Targets reported unhealthy by health_check.",
+                    vec![("target", target.to_string())],
+                    1,
+                );
+            }
+        }
+        HostStats::Version(x) => registry.gauge(
+            "lustre_version_info",
+            "The running Lustre version.",
+            vec![
+                ("version", x.value.version.clone()),
+                ("major", x.value.major.to_string()),
+                ("minor", x.value.minor.to_string()),
+                ("patch", x.value.patch.to_string()),
+                ("vendor", x.value.vendor.clone().unwrap_or_default()),
+                ("lnet", x.value.lnet.clone().unwrap_or_default()),
+            ],
+            1,
+        ),
+        HostStats::ObdDevice(x) => {
+            let x = &x.value;
+
+            registry.gauge(
+                "lustre_device_info",
+                "OBD devices set up on this node, as listed by lctl dl.",
+                vec![
+                    ("index", x.index.to_string()),
+                    ("status", obd_device_status_name(x.status).to_string()),
+                    ("type", x.kind.clone()),
+                    ("name", x.name.clone()),
+                    ("uuid", x.uuid.clone()),
+                    ("nid", x.nid.clone().unwrap_or_default()),
+                ],
+                1,
+            );
+            registry.gauge(
+                "lustre_device_refcount",
+                "Number of references held on an OBD device.",
+                vec![("type", x.kind.clone()), ("name", x.name.clone())],
+                x.refcount,
+            );
+        }
+        HostStats::JobIdName(x) => registry.gauge(
+            "lustre_jobid_name_info",
+            "The jobid_name format job IDs are built from.",
+            vec![("jobid_name", x.value.clone())],
+            1,
+        ),
+        HostStats::JobIdVar(x) => registry.gauge(
+            "lustre_jobid_var_info",
+            "The source of job IDs, procname_uid, disable or an environment variable.",
+            vec![("jobid_var", x.value.clone())],
+            1,
+        ),
+    }
+}
+
+fn target_stats(registry: &mut Registry, jobid: &JobIdFormat, x: &TargetStats) {
+    match x {
+        TargetStats::JobStatsOst(x) => {
+            job_stats_ost(
+                registry,
+                &target_labels(x.kind, &x.target),
+                jobid,
+                x.value.as_deref().unwrap_or_default(),
+            );
+        }
+        TargetStats::JobStatsMdt(x) => {
+            job_stats_mdt(
+                registry,
+                &target_labels(x.kind, &x.target),
+                jobid,
+                x.value.as_deref().unwrap_or_default(),
+            );
+        }
+        TargetStats::Stats(x) => stats_families(
+            registry,
+            (
+                "lustre_target_stats_samples_total",
+                "Number of samples per target operation.",
+            ),
+            (
+                "lustre_target_stats_sum_total",
+                "Sum of sampled values per target operation.",
+            ),
+            &with_label(target_labels(x.kind, &x.target), "param", &*x.param),
+            &x.value,
+        ),
+        TargetStats::BrwStats(x) => {
+            rw_histograms(
+                registry,
+                brw_stats_family,
+                &target_labels(x.kind, &x.target),
+                &x.value,
+            );
+        }
+        TargetStats::FilesFree(x) => registry.gauge(
+            "lustre_inodes_free",
+            "Number of free inodes.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::FilesTotal(x) => registry.gauge(
+            "lustre_inodes_maximum",
+            "Total number of inodes.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::FsType(x) => registry.gauge(
+            "lustre_fs_type_info",
+            "Backing filesystem type of the target.",
+            with_label(target_labels(x.kind, &x.target), "fstype", &x.value),
+            1,
+        ),
+        TargetStats::KBytesAvail(x) => registry.gauge(
+            "lustre_available_kilobytes",
+            "Kilobytes available to non-root users.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::KBytesFree(x) => registry.gauge(
+            "lustre_free_kilobytes",
+            "Kilobytes free.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::KBytesTotal(x) => registry.gauge(
+            "lustre_capacity_kilobytes",
+            "Total capacity in kilobytes.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::NumExports(x) => registry.gauge(
+            "lustre_exports",
+            "Number of exports (connected clients and targets).",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::TotDirty(x) => registry.gauge(
+            "lustre_exports_dirty_bytes",
+            "Total bytes of dirty data held by clients.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::TotGranted(x) => registry.gauge(
+            "lustre_exports_granted_bytes",
+            "Total bytes of space granted to clients.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::TotPending(x) => registry.gauge(
+            "lustre_exports_pending_bytes",
+            "Total bytes of pending writes.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::ContendedLocks(x) => registry.gauge(
+            "lustre_lock_contended",
+            "Number of locks at which a resource is considered contended.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::ContentionSeconds(x) => registry.gauge(
+            "lustre_lock_contention_seconds",
+            "Seconds a resource stays in contended state.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::ConnectedClients(x) => registry.gauge(
+            "lustre_connected_clients",
+            "Number of connected clients.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::CtimeAgeLimit(x) => registry.gauge(
+            "lustre_ctime_age_limit_seconds",
+            "ctime age limit of the lock namespace.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::EarlyLockCancel(x) => registry.gauge(
+            "lustre_early_lock_cancel",
+            "Whether early lock cancel is enabled.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::FsNames(x) => {
+            for fs_name in &x.value {
+                registry.gauge(
+                    "lustre_mgs_filesystem_info",
+                    "Filesystems known to the MGS.",
+                    with_label(target_labels(x.kind, &x.target), "fsname", &fs_name.0),
+                    1,
+                );
+            }
+        }
+        TargetStats::LockCount(x) => registry.gauge(
+            "lustre_lock_count",
+            "Number of locks held.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::LockTimeouts(x) => registry.counter(
+            "lustre_lock_timeouts_total",
+            "Number of lock timeouts.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::LockUnusedCount(x) => registry.gauge(
+            "lustre_lock_unused_count",
+            "Number of unused locks.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::LruMaxAge(x) => registry.gauge(
+            "lustre_lock_lru_max_age",
+            "Maximum age of unused locks in the LRU.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::LruSize(x) => registry.gauge(
+            "lustre_lock_lru_size",
+            "Size of the lock LRU.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::MaxNolockBytes(x) => registry.gauge(
+            "lustre_max_nolock_bytes",
+            "Maximum bytes of lockless I/O.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::MaxParallelAst(x) => registry.gauge(
+            "lustre_max_parallel_ast",
+            "Maximum number of parallel ASTs.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::ResourceCount(x) => registry.gauge(
+            "lustre_resource_count",
+            "Number of lock resources.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::ThreadsMin(x) => registry.gauge(
+            "lustre_threads_min",
+            "Minimum number of service threads.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::ThreadsMax(x) => registry.gauge(
+            "lustre_threads_max",
+            "Maximum number of service threads.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::ThreadsStarted(x) => registry.gauge(
+            "lustre_threads_started",
+            "Number of started service threads.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::RecoveryStatus(x) => registry.gauge(
+            "lustre_recovery_status",
+            "Recovery status of the target.",
+            with_label(
+                target_labels(x.kind, &x.target),
+                "status",
+                recovery_status_name(x.value),
+            ),
+            1,
+        ),
+        TargetStats::Oss(x) => stats_families(
+            registry,
+            (
+                "lustre_oss_stats_samples_total",
+                "Number of samples per OSS service operation.",
+            ),
+            (
+                "lustre_oss_stats_sum_total",
+                "Sum of sampled values per OSS service operation.",
+            ),
+            &vec![("service", x.param.to_string())],
+            &x.stats,
+        ),
+        TargetStats::RecoveryConnectedClients(x) => registry.gauge(
+            "lustre_recovery_connected_clients",
+            "Number of clients connected during recovery.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::RecoveryCompletedClients(x) => registry.gauge(
+            "lustre_recovery_completed_clients",
+            "Number of clients that completed recovery.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::RecoveryEvictedClients(x) => registry.gauge(
+            "lustre_recovery_evicted_clients",
+            "Number of clients evicted during recovery.",
+            target_labels(x.kind, &x.target),
+            x.value,
+        ),
+        TargetStats::Llite(x) => stats_families(
+            registry,
+            (
+                "lustre_client_stats_samples_total",
+                "Number of samples per client (llite) operation.",
+            ),
+            (
+                "lustre_client_stats_sum_total",
+                "Sum of sampled values per client (llite) operation.",
+            ),
+            &vec![("target", x.target.to_string())],
+            &x.stats,
+        ),
+        TargetStats::LliteReadAheadStats(x) => stats_families(
+            registry,
+            (
+                "lustre_client_read_ahead_samples_total",
+                "Number of samples per client read-ahead event.",
+            ),
+            (
+                "lustre_client_read_ahead_sum_total",
+                "Sum of sampled values per client read-ahead event.",
+            ),
+            &vec![("target", x.target.to_string())],
+            &x.value,
+        ),
+        TargetStats::LliteExtentsStats(x) => {
+            if let Some(extents) = &x.value {
+                rw_histograms(
+                    registry,
+                    |_| {
+                        (
+                            "lustre_client_extents_stats",
+                            "Client read/write extent size histograms from extents_stats.",
+                        )
+                    },
+                    &vec![("target", x.target.to_string())],
+                    std::slice::from_ref(extents),
+                );
+            }
+        }
+        // Per-process offsets are too high cardinality to export as metrics.
+        TargetStats::LliteOffsetStats(_) => {}
+        TargetStats::LliteMaxCachedMb(x) => {
+            let labels = vec![("target", x.target.to_string())];
+
+            registry.gauge(
+                "lustre_client_cache_users",
+                "Number of clients sharing the page cache limit.",
+                labels.clone(),
+                x.value.users,
+            );
+            registry.gauge(
+                "lustre_client_cache_max_megabytes",
+                "Maximum page cache the client may use.",
+                labels.clone(),
+                x.value.max_cached_mb,
+            );
+            registry.gauge(
+                "lustre_client_cache_used_megabytes",
+                "Page cache used by the client.",
+                labels.clone(),
+                x.value.used_mb,
+            );
+            registry.gauge(
+                "lustre_client_cache_unused_megabytes",
+                "Page cache available to the client.",
+                labels.clone(),
+                x.value.unused_mb,
+            );
+
+            if let Some(reclaim_count) = x.value.reclaim_count {
+                registry.counter(
+                    "lustre_client_cache_reclaim_total",
+                    "Number of times the client reclaimed page cache.",
+                    labels,
+                    reclaim_count,
+                );
+            }
+        }
+        TargetStats::LliteMaxReadAheadMb(x) => registry.gauge(
+            "lustre_client_max_read_ahead_megabytes",
+            "Maximum read-ahead the client may have outstanding.",
+            vec![("target", x.target.to_string())],
+            x.value,
+        ),
+        TargetStats::LfsckNamespace(x) | TargetStats::LfsckLayout(x) => lfsck_stats(
+            registry,
+            with_label(target_labels(x.kind, &x.target), "component", &*x.param),
+            &x.value,
+        ),
+        TargetStats::HsmControl(x) => registry.gauge(
+            "lustre_hsm_coordinator_state",
+            "State of the HSM coordinator.",
+            with_label(
+                target_labels(x.kind, &x.target),
+                "state",
+                hsm_control_name(x.value),
+            ),
+            1,
+        ),
+        TargetStats::HsmActions(x) => {
+            let labels = target_labels(x.kind, &x.target);
+
+            for action in &x.value {
+                registry.gauge(
+                    "lustre_hsm_actions",
+                    "HSM actions in the coordinator log per archive, action and status.",
+                    with_label(
+                        hsm_action_labels(&labels, action),
+                        "status",
+                        hsm_action_status_name(action.status),
+                    ),
+                    action.count,
+                );
+            }
+        }
+        TargetStats::HsmActiveRequests(x) => {
+            let labels = target_labels(x.kind, &x.target);
+
+            for action in &x.value {
+                registry.gauge(
+                    "lustre_hsm_active_requests",
+                    "HSM requests currently handled by copytools per archive and action.",
+                    hsm_action_labels(&labels, action),
+                    action.count,
+                );
+            }
+        }
+        TargetStats::HsmAgents(x) => {
+            for agent in &x.value {
+                let labels = with_label(target_labels(x.kind, &x.target), "uuid", &agent.uuid);
+                let labels = with_label(labels, "archive_id", &agent.archive_id);
+
+                registry.gauge(
+                    "lustre_hsm_agent_requests_current",
+                    "Requests currently processed by the HSM agent.",
+                    labels.clone(),
+                    agent.current,
+                );
+                registry.counter(
+                    "lustre_hsm_agent_requests_total",
+                    "Requests completed by the HSM agent.",
+                    with_label(labels.clone(), "result", "ok"),
+                    agent.ok,
+                );
+                registry.counter(
+                    "lustre_hsm_agent_requests_total",
+                    "Requests completed by the HSM agent.",
+                    with_label(labels, "result", "errors"),
+                    agent.errors,
+                );
+            }
+        }
+        TargetStats::OstPool(x) => registry.gauge(
+            "lustre_ost_pool_osts",
+            "Number of OSTs in the pool.",
+            with_label(target_labels(x.kind, &x.target), "pool", &x.value.name),
+            x.value.osts.len(),
+        ),
+        TargetStats::ExportStats(x) => {
+            for export in &x.value {
+                stats_families(
+                    registry,
+                    (
+                        "lustre_export_stats_samples_total",
+                        "Number of samples per export operation.",
+                    ),
+                    (
+                        "lustre_export_stats_sum_total",
+                        "Sum of sampled values per export operation.",
+                    ),
+                    &with_label(target_labels(x.kind, &x.target), "nid", &export.nid),
+                    &export.stats,
+                );
+            }
+        }
+        TargetStats::Mds(x) => stats_families(
+            registry,
+            (
+                "lustre_mds_stats_samples_total",
+                "Number of samples per MDS service operation.",
+            ),
+            (
+                "lustre_mds_stats_sum_total",
+                "Sum of sampled values per MDS service operation.",
+            ),
+            &vec![("service", x.param.to_string())],
+            &x.stats,
+        ),
+        TargetStats::Changelog(x) => {
+            let labels = target_labels(x.kind, &x.target);
+
+            registry.gauge(
+                "lustre_changelog_current_index",
+                "Current changelog index.",
+                labels.clone(),
+                x.value.current_index,
+            );
+
+            for user in &x.value.users {
+                let labels = with_label(labels.clone(), "user", &user.user);
+
+                registry.gauge(
+                    "lustre_changelog_user_index",
+                    "Changelog index consumed by the user.",
+                    labels.clone(),
+                    user.index,
+                );
+                registry.gauge(
+                    "lustre_changelog_user_idle_seconds",
+                    "Seconds since the changelog user was last active.",
+                    labels,
+                    user.idle_secs,
+                );
+            }
+        }
+        TargetStats::QuotaStats(x) => {
+            let labels = vec![
+                ("target", x.target.to_string()),
+                ("manager", x.manager.to_string()),
+                ("pool", x.pool.to_string()),
+                ("quota_type", quota_kind_name(&x.value.kind).to_string()),
+            ];
+
+            for stat in &x.value.stats {
+                let labels = with_label(labels.clone(), "id", stat.id);
+
+                registry.gauge(
+                    "lustre_quota_hard",
+                    "Hard quota limit.",
+                    labels.clone(),
+                    stat.limits.hard,
+                );
+                registry.gauge(
+                    "lustre_quota_soft",
+                    "Soft quota limit.",
+                    labels.clone(),
+                    stat.limits.soft,
+                );
+                registry.gauge(
+                    "lustre_quota_granted",
+                    "Quota granted.",
+                    labels.clone(),
+                    stat.limits.granted,
+                );
+                registry.gauge(
+                    "lustre_quota_time",
+                    "Quota grace time.",
+                    labels,
+                    stat.limits.time,
+                );
+            }
+        }
+        TargetStats::QuotaStatsOsd(x) => {
+            let labels = with_label(
+                target_labels(x.kind, &x.target),
+                "quota_type",
+                quota_kind_name(&x.value.kind),
+            );
+
+            for stat in &x.value.stats {
+                let labels = with_label(labels.clone(), "id", stat.id);
+
+                registry.gauge(
+                    "lustre_quota_used_inodes",
+                    "Inodes used by the quota id.",
+                    labels.clone(),
+                    stat.usage.inodes,
+                );
+                registry.gauge(
+                    "lustre_quota_used_kilobytes",
+                    "Kilobytes used by the quota id.",
+                    labels,
+                    stat.usage.kbytes,
+                );
+            }
+        }
+        TargetStats::ClientStats(x) => stats_families(
+            registry,
+            (
+                "lustre_client_target_stats_samples_total",
+                "Number of samples per client (osc/mdc) operation.",
+            ),
+            (
+                "lustre_client_target_stats_sum_total",
+                "Sum of sampled values per client (osc/mdc) operation.",
+            ),
+            &client_labels(x.kind, &x.target, &x.client),
+            &x.value,
+        ),
+        TargetStats::ClientRpcStats(x) => {
+            let labels = client_labels(x.kind, &x.target, &x.client);
+
+            for counter in &x.value.counters {
+                registry.gauge(
+                    "lustre_client_rpcs_pending",
+                    "RPCs or pages currently in flight or pending on the client connection.",
+                    with_label(labels.clone(), "name", &counter.name),
+                    counter.value,
+                );
+            }
+
+            rw_histograms(registry, rpc_stats_family, &labels, &x.value.histograms);
+        }
+        TargetStats::CurDirtyBytes(x) => registry.gauge(
+            "lustre_client_dirty_bytes",
+            "Dirty bytes cached by the client for the target.",
+            client_labels(x.kind, &x.target, &x.client),
+            x.value,
+        ),
+        TargetStats::CurGrantBytes(x) => registry.gauge(
+            "lustre_client_grant_bytes",
+            "Grant held by the client from the target.",
+            client_labels(x.kind, &x.target, &x.client),
+            x.value,
+        ),
+        TargetStats::MaxRpcsInFlight(x) => registry.gauge(
+            "lustre_client_max_rpcs_in_flight",
+            "Maximum RPCs in flight from the client to the target.",
+            client_labels(x.kind, &x.target, &x.client),
+            x.value,
+        ),
+        TargetStats::ClientImport(x) => {
+            let labels = client_labels(x.kind, &x.target, &x.client);
+            let import = &x.value;
+
+            registry.gauge(
+                "lustre_client_import_state",
+                "Current state of the client import.",
+                with_label(labels.clone(), "state", import_state_name(import.state)),
+                1,
+            );
+            registry.counter(
+                "lustre_client_import_connection_attempts_total",
+                "Connection attempts made by the client import.",
+                labels.clone(),
+                import.connection_attempts,
+            );
+            registry.gauge(
+                "lustre_client_import_rpcs_inflight",
+                "RPCs in flight on the client import.",
+                labels.clone(),
+                import.inflight,
+            );
+            registry.counter(
+                "lustre_client_import_timeouts_total",
+                "RPC timeouts on the client import.",
+                labels.clone(),
+                import.timeouts,
+            );
+
+            if let Some(x) = import.avg_waittime {
+                registry.gauge(
+                    "lustre_client_import_avg_waittime_usec",
+                    "Average RPC wait time on the client import.",
+                    labels.clone(),
+                    x,
+                );
+            }
+
+            if let Some(x) = import.service_estimate {
+                registry.gauge(
+                    "lustre_client_import_service_estimate_seconds",
+                    "Adaptive timeout service estimate of the client import.",
+                    labels.clone(),
+                    x,
+                );
+            }
+
+            if let Some(x) = import.network_estimate {
+                registry.gauge(
+                    "lustre_client_import_network_estimate_seconds",
+                    "Adaptive timeout network estimate of the client import.",
+                    labels,
+                    x,
+                );
+            }
+        }
+        TargetStats::ClientImportState(x) => {
+            let labels = client_labels(x.kind, &x.target, &x.client);
+
+            // Only the latest entry per state is kept, as the history is in time order.
+            let mut latest: Vec<(ImportState, i64)> = vec![];
+
+            for change in &x.value.state_history {
+                match latest.iter_mut().find(|(state, _)| *state == change.state) {
+                    Some((_, time)) => *time = change.time,
+                    None => latest.push((change.state, change.time)),
+                }
+            }
+
+            for (state, time) in latest {
+                registry.gauge(
+                    "lustre_client_import_state_time_seconds",
+                    "When the client import last entered the state, from its state history.",
+                    with_label(labels.clone(), "state", import_state_name(state)),
+                    time,
+                );
+            }
+        }
+    }
+}
+
+fn lnet_msg_stats(
+    registry: &mut Registry,
+    name: &'static str,
+    help: &'static str,
+    nid: &str,
+    x: &lnet_exports::Stats,
+) {
+    for (kind, value) in [
+        ("put", x.put),
+        ("get", x.get),
+        ("reply", x.reply),
+        ("ack", x.ack),
+        ("hello", x.hello),
+    ] {
+        registry.counter(
+            name,
+            help,
+            vec![("nid", nid.to_string()), ("type", kind.to_string())],
+            value,
+        );
+    }
+}
+
+fn lnet_ni_status_name(x: LNetNiStatus) -> &'static str {
+    match x {
+        LNetNiStatus::Up => "up",
+        LNetNiStatus::Down => "down",
+        LNetNiStatus::Unknown => "unknown",
+    }
+}
+
+fn peer_labels<T>(x: &LNetPeerStat<T>) -> Labels {
+    vec![
+        ("primary_nid", x.primary_nid.to_string()),
+        ("nid", x.nid.to_string()),
+    ]
+}
+
+fn route_labels<T>(x: &LNetRouteStat<T>) -> Labels {
+    vec![
+        ("net", x.net.to_string()),
+        ("gateway", x.gateway.to_string()),
+        ("hop", x.hop.to_string()),
+    ]
+}
+
+fn lnet_stats(registry: &mut Registry, x: &LNetStats) {
+    match x {
+        LNetStats::SendCount(x) => registry.counter(
+            "lustre_lnet_send_count_total",
+            "Number of messages sent by the NI.",
+            vec![("nid", x.nid.to_string())],
+            x.value,
+        ),
+        LNetStats::RecvCount(x) => registry.counter(
+            "lustre_lnet_receive_count_total",
+            "Number of messages received by the NI.",
+            vec![("nid", x.nid.to_string())],
+            x.value,
+        ),
+        LNetStats::DropCount(x) => registry.counter(
+            "lustre_lnet_drop_count_total",
+            "Number of messages dropped by the NI.",
+            vec![("nid", x.nid.to_string())],
+            x.value,
+        ),
+        LNetStats::SendLength(x) => registry.counter(
+            "lustre_lnet_send_length_bytes_total",
+            "Number of bytes sent by LNet.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::RecvLength(x) => registry.counter(
+            "lustre_lnet_receive_length_bytes_total",
+            "Number of bytes received by LNet.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::DropLength(x) => registry.counter(
+            "lustre_lnet_drop_length_bytes_total",
+            "Number of bytes dropped by LNet.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::MsgsAlloc(x) => registry.gauge(
+            "lustre_lnet_msgs_alloc",
+            "Number of LNet messages currently allocated.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::MsgsMax(x) => registry.gauge(
+            "lustre_lnet_msgs_max",
+            "Highest number of LNet messages allocated at once.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::RstAlloc(x) => registry.gauge(
+            "lustre_lnet_rst_alloc",
+            "Number of response trackers currently allocated.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::Errors(x) => registry.counter(
+            "lustre_lnet_errors_total",
+            "Number of LNet errors.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::GlobalSendCount(x) => registry.counter(
+            "lustre_lnet_global_send_count_total",
+            "Number of messages sent by LNet.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::ResendCount(x) => registry.counter(
+            "lustre_lnet_resend_count_total",
+            "Number of messages resent by LNet.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::ResponseTimeoutCount(x) => registry.counter(
+            "lustre_lnet_response_timeout_count_total",
+            "Number of response timeouts.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::LocalInterruptCount(x) => registry.counter(
+            "lustre_lnet_local_interrupt_count_total",
+            "Number of local interrupts.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::LocalDroppedCount(x) => registry.counter(
+            "lustre_lnet_local_dropped_count_total",
+            "Number of messages dropped locally.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::LocalAbortedCount(x) => registry.counter(
+            "lustre_lnet_local_aborted_count_total",
+            "Number of messages aborted locally.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::LocalNoRouteCount(x) => registry.counter(
+            "lustre_lnet_local_no_route_count_total",
+            "Number of local sends without a route.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::LocalTimeoutCount(x) => registry.counter(
+            "lustre_lnet_local_timeout_count_total",
+            "Number of local timeouts.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::LocalErrorCount(x) => registry.counter(
+            "lustre_lnet_local_error_count_total",
+            "Number of local errors.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::RemoteDroppedCount(x) => registry.counter(
+            "lustre_lnet_remote_dropped_count_total",
+            "Number of messages dropped by the remote.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::RemoteErrorCount(x) => registry.counter(
+            "lustre_lnet_remote_error_count_total",
+            "Number of remote errors.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::RemoteTimeoutCount(x) => registry.counter(
+            "lustre_lnet_remote_timeout_count_total",
+            "Number of remote timeouts.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::NetworkTimeoutCount(x) => registry.counter(
+            "lustre_lnet_network_timeout_count_total",
+            "Number of network timeouts.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::GlobalRecvCount(x) => registry.counter(
+            "lustre_lnet_global_receive_count_total",
+            "Number of messages received by LNet.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::RouteCount(x) => registry.counter(
+            "lustre_lnet_route_count_total",
+            "Number of messages routed by LNet.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::GlobalDropCount(x) => registry.counter(
+            "lustre_lnet_global_drop_count_total",
+            "Number of messages dropped by LNet.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::RouteLength(x) => registry.counter(
+            "lustre_lnet_route_length_bytes_total",
+            "Number of bytes routed by LNet.",
+            vec![],
+            x.value,
+        ),
+        LNetStats::SentStats(x) => lnet_msg_stats(
+            registry,
+            "lustre_lnet_sent_messages_total",
+            "Number of messages sent by the NI, per message type.",
+            &x.nid,
+            &x.value,
+        ),
+        LNetStats::ReceivedStats(x) => lnet_msg_stats(
+            registry,
+            "lustre_lnet_received_messages_total",
+            "Number of messages received by the NI, per message type.",
+            &x.nid,
+            &x.value,
+        ),
+        LNetStats::DroppedStats(x) => lnet_msg_stats(
+            registry,
+            "lustre_lnet_dropped_messages_total",
+            "Number of messages dropped by the NI, per message type.",
+            &x.nid,
+            &x.value,
+        ),
+        LNetStats::Health(x) => {
+            let labels = vec![("nid", x.nid.to_string())];
+
+            registry.gauge(
+                "lustre_lnet_health_value",
+                "Health value of the NI.",
+                labels.clone(),
+                x.value.health_value,
+            );
+
+            for (error, value) in [
+                ("interrupts", x.value.interrupts),
+                ("dropped", x.value.dropped),
+                ("aborted", x.value.aborted),
+                ("no_route", x.value.no_route),
+                ("timeouts", x.value.timeouts),
+                ("error", x.value.error),
+            ] {
+                registry.counter(
+                    "lustre_lnet_health_errors_total",
+                    "Number of health errors on the NI, per error type.",
+                    with_label(labels.clone(), "error", error),
+                    value,
+                );
+            }
+        }
+        LNetStats::Status(x) => registry.gauge(
+            "lustre_lnet_ni_status",
+            "Status of the NI.",
+            vec![
+                ("nid", x.nid.to_string()),
+                ("status", lnet_ni_status_name(x.value).to_string()),
+            ],
+            1,
+        ),
+        LNetStats::PeerMaxNiTxCredits(x) => registry.gauge(
+            "lustre_lnet_peer_max_ni_tx_credits",
+            "Maximum transmit credits of the peer NI.",
+            peer_labels(x),
+            x.value,
+        ),
+        LNetStats::PeerAvailableTxCredits(x) => registry.gauge(
+            "lustre_lnet_peer_available_tx_credits",
+            "Available transmit credits of the peer NI.",
+            peer_labels(x),
+            x.value,
+        ),
+        LNetStats::PeerMinTxCredits(x) => registry.gauge(
+            "lustre_lnet_peer_min_tx_credits",
+            "Lowest transmit credits of the peer NI, negative when messages were queued.",
+            peer_labels(x),
+            x.value,
+        ),
+        LNetStats::PeerTxQNumOfBuf(x) => registry.gauge(
+            "lustre_lnet_peer_tx_q_num_of_buf",
+            "Number of messages queued for transmit to the peer NI.",
+            peer_labels(x),
+            x.value,
+        ),
+        LNetStats::PeerAvailableRtrCredits(x) => registry.gauge(
+            "lustre_lnet_peer_available_rtr_credits",
+            "Available router credits of the peer NI.",
+            peer_labels(x),
+            x.value,
+        ),
+        LNetStats::PeerMinRtrCredits(x) => registry.gauge(
+            "lustre_lnet_peer_min_rtr_credits",
+            "Lowest router credits of the peer NI.",
+            peer_labels(x),
+            x.value,
+        ),
+        LNetStats::PeerState(x) => registry.gauge(
+            "lustre_lnet_peer_state",
+            "State of the peer NI.",
+            with_label(peer_labels(x), "status", lnet_ni_status_name(x.value)),
+            1,
+        ),
+        LNetStats::PeerHealth(x) => {
+            let labels = peer_labels(x);
+
+            registry.gauge(
+                "lustre_lnet_peer_health_value",
+                "Health value of the peer NI.",
+                labels.clone(),
+                x.value.health_value,
+            );
+
+            for (error, value) in [
+                ("dropped", x.value.dropped),
+                ("timeout", x.value.timeout),
+                ("error", x.value.error),
+                ("network_timeout", x.value.network_timeout),
+            ] {
+                registry.counter(
+                    "lustre_lnet_peer_health_errors_total",
+                    "Number of health errors on the peer NI, per error type.",
+                    with_label(labels.clone(), "error", error),
+                    value,
+                );
+            }
+        }
+        LNetStats::RouteState(x) => registry.gauge(
+            "lustre_lnet_route_state",
+            "State of the route.",
+            with_label(route_labels(x), "status", lnet_ni_status_name(x.value)),
+            1,
+        ),
+        LNetStats::RoutePriority(x) => registry.gauge(
+            "lustre_lnet_route_priority",
+            "Priority of the route.",
+            route_labels(x),
+            x.value,
+        ),
+        LNetStats::RoutingEnabled(x) => registry.gauge(
+            "lustre_lnet_routing_enabled",
+            "Whether routing is enabled on this node.",
+            vec![],
+            u8::from(x.value),
+        ),
+        LNetStats::RouterBuffers(x) => {
+            let labels = vec![("cpt", x.cpt.to_string()), ("pool", x.pool.to_string())];
+
+            registry.gauge(
+                "lustre_lnet_router_buffer_pages",
+                "Number of pages per router buffer in the pool.",
+                labels.clone(),
+                x.value.npages,
+            );
+            registry.gauge(
+                "lustre_lnet_router_buffers",
+                "Number of router buffers in the pool.",
+                labels.clone(),
+                x.value.nbuffers,
+            );
+            registry.gauge(
+                "lustre_lnet_router_buffer_credits",
+                "Available router buffer credits in the pool.",
+                labels.clone(),
+                x.value.credits,
+            );
+            registry.gauge(
+                "lustre_lnet_router_buffer_min_credits",
+                "Lowest router buffer credits in the pool, negative when messages were queued.",
+                labels,
+                x.value.mincredits,
+            );
+        }
+        LNetStats::Tunables(x) => {
+            let labels = vec![("nid", x.nid.to_string())];
+
+            registry.gauge(
+                "lustre_lnet_peer_timeout_seconds",
+                "Time after which a silent peer is considered dead.",
+                labels.clone(),
+                x.value.peer_timeout,
+            );
+            registry.gauge(
+                "lustre_lnet_peer_credits",
+                "Number of concurrent sends allowed to a single peer.",
+                labels.clone(),
+                x.value.peer_credits,
+            );
+            registry.gauge(
+                "lustre_lnet_peer_buffer_credits",
+                "Number of router buffer credits per peer.",
+                labels.clone(),
+                x.value.peer_buffer_credits,
+            );
+            registry.gauge(
+                "lustre_lnet_credits",
+                "Number of concurrent sends allowed on the NI.",
+                labels,
+                x.value.credits,
+            );
+        }
+        LNetStats::Cpt(x) => {
+            let labels = vec![("nid", x.nid.to_string())];
+
+            if let Some(cpt) = x.value.dev_cpt {
+                registry.gauge(
+                    "lustre_lnet_ni_dev_cpt",
+                    "CPU partition of the NI's network device.",
+                    labels.clone(),
+                    cpt,
+                );
+            }
+
+            for cpt in &x.value.cpts {
+                registry.gauge(
+                    "lustre_lnet_ni_cpt",
+                    "CPU partitions the NI is bound to.",
+                    with_label(labels.clone(), "cpt", cpt),
+                    1,
+                );
+            }
+        }
+    }
+}
+
+fn lustre_service_stats(registry: &mut Registry, x: &LustreServiceStats) {
+    let (service, stats) = match x {
+        LustreServiceStats::LdlmCanceld(x) => ("ldlm_canceld", x),
+        LustreServiceStats::LdlmCbd(x) => ("ldlm_cbd", x),
+    };
+
+    stats_families(
+        registry,
+        (
+            "lustre_ldlm_service_stats_samples_total",
+            "Number of samples per LDLM service operation.",
+        ),
+        (
+            "lustre_ldlm_service_stats_sum_total",
+            "Sum of sampled values per LDLM service operation.",
+        ),
+        &vec![("service", service.to_string())],
+        stats,
+    );
+}
+
+fn node_stats(registry: &mut Registry, x: &NodeStats) {
+    match x {
+        NodeStats::CpuUser(x) => registry.counter(
+            "lustre_node_cpu_user_total",
+            "CPU time spent in user mode, in USER_HZ.",
+            vec![],
+            x.value,
+        ),
+        NodeStats::CpuSystem(x) => registry.counter(
+            "lustre_node_cpu_system_total",
+            "CPU time spent in system mode, in USER_HZ.",
+            vec![],
+            x.value,
+        ),
+        NodeStats::CpuIowait(x) => registry.counter(
+            "lustre_node_cpu_iowait_total",
+            "CPU time spent waiting for I/O, in USER_HZ.",
+            vec![],
+            x.value,
+        ),
+        NodeStats::CpuTotal(x) => registry.counter(
+            "lustre_node_cpu_total",
+            "Total CPU time, in USER_HZ.",
+            vec![],
+            x.value,
+        ),
+        NodeStats::MemTotal(x) => registry.gauge(
+            "lustre_node_mem_total_kilobytes",
+            "Total memory.",
+            vec![],
+            x.value,
+        ),
+        NodeStats::MemFree(x) => registry.gauge(
+            "lustre_node_mem_free_kilobytes",
+            "Free memory.",
+            vec![],
+            x.value,
+        ),
+        NodeStats::SwapTotal(x) => registry.gauge(
+            "lustre_node_swap_total_kilobytes",
+            "Total swap.",
+            vec![],
+            x.value,
+        ),
+        NodeStats::SwapFree(x) => registry.gauge(
+            "lustre_node_swap_free_kilobytes",
+            "Free swap.",
+            vec![],
+            x.value,
+        ),
+    }
+}
+
+/// Renders `records` as Prometheus text exposition format.
+///
+/// Every metric family is emitted once with its `HELP` and `TYPE` lines,
+/// followed by all of its samples.
+///
+/// Job stats are labelled with the parts of their job ID, split according to
+/// the `jobid_name` and `jobid_var` found in `records`.
+pub fn render(records: &[Record]) -> String {
+    let mut registry = Registry::default();
+
+    let jobid = JobIdFormat::from_records(records);
+
+    let mut skipped: BTreeMap<&str, u64> = BTreeMap::new();
+
+    for record in records {
+        match record {
+            Record::Host(x) => host_stats(&mut registry, x),
+            Record::LNetStat(x) => lnet_stats(&mut registry, x),
+            Record::LustreService(x) => lustre_service_stats(&mut registry, x),
+            Record::Node(x) => node_stats(&mut registry, x),
+            Record::Target(x) => target_stats(&mut registry, &jobid, x),
+            Record::Diagnostic(x) => {
+                *skipped
+                    .entry(x.param.as_deref().unwrap_or_default())
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    for (param, count) in skipped {
+        registry.gauge(
+            "lustre_collector_skipped_params",
+            "Number of param blocks that could not be parsed and were skipped.",
+            vec![("param", param.to_string())],
+            count,
+        );
+    }
+
+    registry.render()
+}
+
+/// Renders `rates` as a single `lustre_rate_per_second` gauge family, labelled by counter.
+pub fn render_rates(rates: &[Rate]) -> String {
+    let mut registry = Registry::default();
+
+    for x in rates {
+        let key = &x.key;
+
+        let labels = [
+            ("kind", key.kind.map(|x| x.to_string())),
+            ("target", key.target.as_ref().map(|x| x.to_string())),
+            ("client", key.client.clone()),
+            ("param", Some(key.param.to_string())),
+            ("nid", key.nid.clone()),
+            ("job_id", key.job_id.clone()),
+            ("name", Some(key.name.clone())),
+            ("bucket", key.bucket.map(|x| x.to_string())),
+            ("field", Some(key.field.clone())),
+        ]
+        .into_iter()
+        .filter_map(|(k, v)| Some((k, v?)))
+        .collect();
+
+        registry.gauge(
+            "lustre_rate_per_second",
+            "Per-second rate of a cumulative counter between two snapshots.",
+            labels,
+            x.per_second,
+        );
+    }
+
+    registry.render()
+}
+
+/// Renders a utilisation `report` as per filesystem and per target gauges.
+pub fn render_report(report: &[FilesystemReport]) -> String {
+    let mut registry = Registry::default();
+
+    for fs in report {
+        let labels = vec![("fs", fs.name.clone())];
+
+        if let Some(x) = fs.kbytes_used_pct {
+            registry.gauge(
+                "lustre_filesystem_used_percent",
+                "Percentage of filesystem space in use, as reported by df.",
+                labels.clone(),
+                x,
+            );
+        }
+
+        if let Some(x) = fs.files_used_pct {
+            registry.gauge(
+                "lustre_filesystem_inodes_used_percent",
+                "Percentage of filesystem inodes in use.",
+                labels.clone(),
+                x,
+            );
+        }
+
+        if let Some(x) = fs.ost_mean_used_pct {
+            registry.gauge(
+                "lustre_filesystem_ost_mean_used_percent",
+                "Mean fill level of the filesystem's OSTs.",
+                labels.clone(),
+                x,
+            );
+        }
+
+        registry.gauge(
+            "lustre_filesystem_imbalanced_osts",
+            "Number of OSTs whose fill level deviates from the mean of their pool, or of the filesystem, beyond the threshold.",
+            labels.clone(),
+            fs.imbalanced_osts.len(),
+        );
+
+        for x in &fs.pools {
+            let labels = with_label(labels.clone(), "pool", &x.name);
+
+            if let Some(mean) = x.ost_mean_used_pct {
+                registry.gauge(
+                    "lustre_pool_ost_mean_used_percent",
+                    "Mean fill level of the pool's OSTs.",
+                    labels.clone(),
+                    mean,
+                );
+            }
+
+            registry.gauge(
+                "lustre_pool_imbalanced_osts",
+                "Number of OSTs whose fill level deviates from the pool mean beyond the threshold.",
+                labels,
+                x.imbalanced_osts.len(),
+            );
+        }
+
+        for x in &fs.targets {
+            let labels = with_label(labels.clone(), "kind", x.kind);
+            let labels = with_label(labels, "target", &*x.target);
+
+            if let Some(pct) = x.kbytes_used_pct {
+                registry.gauge(
+                    "lustre_target_used_percent",
+                    "Percentage of target space in use.",
+                    labels.clone(),
+                    pct,
+                );
+            }
+
+            if let Some(pct) = x.files_used_pct {
+                registry.gauge(
+                    "lustre_target_inodes_used_percent",
+                    "Percentage of target inodes in use.",
+                    labels.clone(),
+                    pct,
+                );
+            }
+
+            if let Some(deviation) = x.deviation {
+                registry.gauge(
+                    "lustre_ost_fill_deviation_percent",
+                    "Percentage points the OST fill level is above or below the mean of its pool, or of the filesystem.",
+                    labels.clone(),
+                    deviation,
+                );
+                registry.gauge(
+                    "lustre_ost_imbalanced",
+                    "Whether the OST fill level deviates from the mean of its pool, or of the filesystem, beyond the threshold (1) or not (0).",
+                    labels,
+                    u8::from(x.imbalanced),
+                );
+            }
+        }
+    }
+
+    registry.render()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse_device_list_output, parse_lctl_output, parse_lnetctl_output, parse_lnetctl_stats,
+        parser::param_line, BrwStatsBucket, HostStat, LNetStatGlobal, Param, Target, TargetStat,
+    };
+    use include_dir::{include_dir, Dir};
+    use insta::assert_snapshot;
+    use std::collections::HashSet;
+
+    static VALID_FIXTURES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src/fixtures/valid/");
+
+    #[test]
+    fn test_render_rates() {
+        let send_length = |value| {
+            vec![Record::LNetStat(LNetStats::SendLength(LNetStatGlobal {
+                param: Param("send_length".to_string()),
+                value,
+            }))]
+        };
+
+        let (prev, cur) = (send_length(100), send_length(400));
+
+        let rates = crate::rates::rates(&prev, &cur, std::time::Duration::from_secs(30));
+
+        assert_snapshot!(render_rates(&rates));
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape_label_value(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape_label_value("a\nb"), r"a\nb");
+    }
+
+    #[test]
+    fn test_families_are_grouped() {
+        let records = vec![
+            Record::Target(TargetStats::NumExports(TargetStat {
+                kind: TargetVariant::Ost,
+                param: Param("num_exports".to_string()),
+                target: Target("fs-OST0000".to_string()),
+                value: 2,
+                snapshot_time: None,
+            })),
+            Record::Host(HostStats::Memused(HostStat {
+                param: Param("memused".to_string()),
+                value: 10,
+            })),
+            Record::Target(TargetStats::NumExports(TargetStat {
+                kind: TargetVariant::Mdt,
+                param: Param("num_exports".to_string()),
+                target: Target("fs-MDT0000".to_string()),
+                value: 3,
+                snapshot_time: None,
+            })),
+        ];
+
+        assert_eq!(
+            render(&records),
+            r#"# HELP lustre_exports Number of exports (connected clients and targets).
+# TYPE lustre_exports gauge
+lustre_exports{kind="OST",target="fs-OST0000"} 2
+lustre_exports{kind="MDT",target="fs-MDT0000"} 3
+# HELP lustre_mem_used_bytes Bytes of memory currently used by Lustre.
+# TYPE lustre_mem_used_bytes gauge
+lustre_mem_used_bytes 10
+"#
+        );
+    }
+
+    #[test]
+    fn test_brw_stats_histogram() {
+        let records = vec![Record::Target(TargetStats::BrwStats(TargetStat {
+            kind: TargetVariant::Ost,
+            param: Param("brw_stats".to_string()),
+            target: Target("fs-OST0000".to_string()),
+            value: vec![BrwStats {
+                name: "pages".to_string(),
+                unit: "rpcs".to_string(),
+                buckets: vec![
+                    BrwStatsBucket {
+                        name: 1,
+                        read: 2,
+                        write: 0,
+                    },
+                    BrwStatsBucket {
+                        name: 2,
+                        read: 3,
+                        write: 1,
+                    },
+                ],
+            }],
+            snapshot_time: None,
+        }))];
+
+        assert_snapshot!(render(&records));
+    }
+
+    /// Drops param blocks already printed earlier in `x`.
+    ///
+    /// Some fixtures were captured with overlapping `lctl get_param` patterns
+    /// and list the same block twice, which the collector never asks for.
+    fn first_blocks(x: &str) -> String {
+        let mut seen = HashSet::new();
+        let mut keep = true;
+
+        x.split_inclusive('\n')
+            .filter(|x| {
+                if let Some(param) = param_line(x) {
+                    keep = seen.insert(param.to_string());
+                }
+
+                keep
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_no_duplicate_series() {
+        for file in VALID_FIXTURES
+            .find("**/*")
+            .unwrap()
+            .filter_map(|x| x.as_file())
+        {
+            let x = first_blocks(file.contents_utf8().unwrap());
+            let records = parse_lctl_output(x.as_bytes()).unwrap();
+
+            let x = render(&records);
+            let mut seen = HashSet::new();
+
+            for series in x
+                .lines()
+                .filter(|x| !x.starts_with('#'))
+                .filter_map(|x| x.rsplit_once(' '))
+                .map(|(series, _)| series)
+            {
+                assert!(seen.insert(series), "{:?}: {series}", file.path());
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_lctl_fixture() {
+        let xs = first_blocks(include_str!("./fixtures/valid/2.14.0_ddn144_mds.txt"));
+        let records = parse_lctl_output(xs.as_bytes()).unwrap();
+
+        assert_snapshot!(render(&records));
+    }
+
+    #[test]
+    fn test_render_client_fixture() {
+        let xs = include_bytes!("./fixtures/valid/lustre-2-14-0/client/osc_mdc_client.txt");
+        let records = parse_lctl_output(xs).unwrap();
+
+        assert_snapshot!(render(&records));
+    }
+
+    #[test]
+    fn test_render_job_id_labels() {
+        let xs = r#"jobid_name=%j.%e.%u
+jobid_var=SLURM_JOB_ID
+obdfilter.fs-OST0000.job_stats=
+job_stats:
+- job_id:          4242.ior.1000
+  snapshot_time:   1700000000
+  read_bytes:      { samples:           1, unit: bytes, min: 4096, max: 4096, sum: 4096 }
+  write_bytes:     { samples:           0, unit: bytes, min: 0, max: 0, sum: 0 }
+  getattr:         { samples:           0, unit:  reqs }
+  setattr:         { samples:           0, unit:  reqs }
+  punch:           { samples:           0, unit:  reqs }
+  sync:            { samples:           0, unit:  reqs }
+  destroy:         { samples:           0, unit:  reqs }
+  create:          { samples:           0, unit:  reqs }
+  statfs:          { samples:           0, unit:  reqs }
+  get_info:        { samples:           0, unit:  reqs }
+  set_info:        { samples:           0, unit:  reqs }
+  quotactl:        { samples:           0, unit:  reqs }
+"#;
+        let records = parse_lctl_output(xs.as_bytes()).unwrap();
+
+        let x = render(&records);
+
+        assert!(x.contains(r#"lustre_job_stats_sum_total{kind="OST",target="fs-OST0000",job_id="4242.ior.1000",job_executable="ior",job_uid="1000",job_scheduler_id="4242",operation="read_bytes",units="bytes"} 4096"#));
+    }
+
+    #[test]
+    fn test_render_report() {
+        let xs = include_bytes!("./fixtures/valid/2.14.0_ddn144_mds.txt");
+        let records = parse_lctl_output(xs).unwrap();
+
+        assert_snapshot!(render_report(&crate::report::report(&records, 10.0)));
+    }
+
+    #[test]
+    fn test_render_device_list() {
+        let xs = include_bytes!("../fixtures/device_list.txt");
+        let records = parse_device_list_output(xs).unwrap();
+
+        assert_snapshot!(render(&records));
+    }
+
+    #[test]
+    fn test_render_lnetctl_stats() {
+        let records = parse_lnetctl_stats(
+            r#"statistics:
+    msgs_alloc: 0
+    msgs_max: 2578
+    rst_alloc: 20
+    errors: 0
+    send_count: 171344551
+    resend_count: 0
+    response_timeout_count: 0
+    local_interrupt_count: 0
+    local_dropped_count: 0
+    local_aborted_count: 0
+    local_no_route_count: 0
+    local_timeout_count: 0
+    local_error_count: 0
+    remote_dropped_count: 4
+    remote_error_count: 0
+    remote_timeout_count: 0
+    network_timeout_count: 0
+    recv_count: 171609513
+    route_count: 0
+    drop_count: 1185
+    send_length: 62502714567608
+    recv_length: 17084716480056
+    route_length: 0
+    drop_length: 568792
+"#,
+        )
+        .unwrap();
+
+        assert_snapshot!(render(&records));
+    }
+
+    #[test]
+    fn test_render_lnetctl_net() {
+        let records = parse_lnetctl_output(
+            r#"net:
+    - net type: tcp
+      local NI(s):
+        - nid: 10.73.20.11@tcp
+          status: up
+          interfaces:
+              0: eth1
+          statistics:
+              send_count: 3825
+              recv_count: 3736
+              drop_count: 30
+          sent_stats:
+              put: 3821
+              get: 4
+              reply: 0
+              ack: 0
+              hello: 0
+          received_stats:
+              put: 3698
+              get: 1
+              reply: 3
+              ack: 34
+              hello: 0
+          dropped_stats:
+              put: 30
+              get: 0
+              reply: 0
+              ack: 0
+              hello: 0
+          health stats:
+              health value: 1000
+              interrupts: 0
+              dropped: 0
+              aborted: 0
+              no route: 0
+              timeouts: 2
+              error: 0
+          tunables:
+              peer_timeout: 180
+              peer_credits: 8
+              peer_buffer_credits: 0
+              credits: 256
+          dev cpt: -1
+          tcp bonding: 0
+          CPT: "[0]"
+"#,
+        )
+        .unwrap();
+
+        assert_snapshot!(render(&records));
+    }
+}
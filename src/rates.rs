@@ -0,0 +1,761 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Computes deltas and per-second rates for cumulative counters between two snapshots.
+//!
+//! Elapsed time is taken from the `snapshot_time` Lustre reports with each stats block
+//! where available, falling back to the collection interval otherwise (e.g. for LNet).
+
+use crate::types::{
+    BrwStats, BytesStat, JobStatMdt, JobStatOst, LNetStats, LustreServiceStats, Param, Record,
+    Stat, Target, TargetStats, TargetVariant, UnsignedLustreTimestamp,
+};
+use std::{collections::HashMap, time::Duration};
+
+/// Identifies a single cumulative counter across snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct CounterKey {
+    pub kind: Option<TargetVariant>,
+    pub target: Option<Target>,
+    /// The client instance of an `osc`/`mdc` connection.
+    pub client: Option<String>,
+    pub param: Param,
+    pub nid: Option<String>,
+    pub job_id: Option<String>,
+    /// The operation, histogram or LNet counter name.
+    pub name: String,
+    /// Lower bound of the `brw_stats` or `rpc_stats` histogram bucket.
+    pub bucket: Option<u64>,
+    /// Which value of `name` this is, e.g. `samples`, `sum`, `read` or `write`.
+    pub field: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Rate {
+    #[serde(flatten)]
+    pub key: CounterKey,
+    pub delta: u64,
+    /// Seconds between the two samples.
+    pub elapsed: f64,
+    pub per_second: f64,
+}
+
+struct Counter {
+    key: CounterKey,
+    value: u64,
+    /// Sample time, in milliseconds.
+    time: Option<i64>,
+    /// When the counter started from zero, in milliseconds.
+    start: Option<i64>,
+}
+
+#[derive(Clone)]
+struct Labels<'a> {
+    kind: Option<TargetVariant>,
+    target: Option<&'a Target>,
+    client: Option<&'a str>,
+    param: &'a Param,
+    nid: Option<&'a str>,
+    job_id: Option<&'a str>,
+}
+
+impl<'a> Labels<'a> {
+    fn new(param: &'a Param) -> Self {
+        Self {
+            kind: None,
+            target: None,
+            client: None,
+            param,
+            nid: None,
+            job_id: None,
+        }
+    }
+
+    fn target(kind: TargetVariant, target: &'a Target, param: &'a Param) -> Self {
+        Self {
+            kind: Some(kind),
+            target: Some(target),
+            ..Self::new(param)
+        }
+    }
+
+    fn counter(
+        &self,
+        name: &str,
+        bucket: Option<u64>,
+        field: &str,
+        value: u64,
+        time: Option<i64>,
+    ) -> Counter {
+        Counter {
+            key: CounterKey {
+                kind: self.kind,
+                target: self.target.cloned(),
+                client: self.client.map(String::from),
+                param: self.param.clone(),
+                nid: self.nid.map(String::from),
+                job_id: self.job_id.map(String::from),
+                name: name.to_string(),
+                bucket,
+                field: field.to_string(),
+            },
+            value,
+            time,
+            start: None,
+        }
+    }
+}
+
+fn stats_counters(labels: &Labels, xs: &[Stat], time: Option<i64>, out: &mut Vec<Counter>) {
+    for x in xs {
+        out.push(labels.counter(&x.name, None, "samples", x.samples, time));
+
+        if let Some(sum) = x.sum {
+            out.push(labels.counter(&x.name, None, "sum", sum, time));
+        }
+    }
+}
+
+fn brw_stats_counters(labels: &Labels, xs: &[BrwStats], time: Option<i64>, out: &mut Vec<Counter>) {
+    for x in xs {
+        for b in &x.buckets {
+            out.push(labels.counter(&x.name, Some(b.name), "read", b.read, time));
+            out.push(labels.counter(&x.name, Some(b.name), "write", b.write, time));
+        }
+    }
+}
+
+fn job_counters(
+    labels: &Labels,
+    job_id: &str,
+    time: &UnsignedLustreTimestamp,
+    start: Option<&UnsignedLustreTimestamp>,
+    ops: &[(&str, &BytesStat)],
+    out: &mut Vec<Counter>,
+) {
+    let labels = Labels {
+        job_id: Some(job_id),
+        ..labels.clone()
+    };
+
+    let time = Some(time.0);
+    let start = start.map(|x| x.0);
+
+    for (name, stat) in ops {
+        for (field, value) in [("samples", stat.samples), ("sum", stat.sum)] {
+            if let Ok(value) = u64::try_from(value) {
+                out.push(Counter {
+                    start,
+                    ..labels.counter(name, None, field, value, time)
+                });
+            }
+        }
+    }
+}
+
+fn job_stats_ost_counters(labels: &Labels, xs: &[JobStatOst], out: &mut Vec<Counter>) {
+    for x in xs {
+        let ops: [(&str, &BytesStat); 2] = [
+            ("read_bytes", &x.read_bytes),
+            ("write_bytes", &x.write_bytes),
+        ];
+
+        job_counters(
+            labels,
+            &x.job_id,
+            &x.snapshot_time,
+            x.start_time.as_ref(),
+            &ops,
+            out,
+        );
+    }
+}
+
+fn job_stats_mdt_counters(labels: &Labels, xs: &[JobStatMdt], out: &mut Vec<Counter>) {
+    for x in xs {
+        let mut ops: Vec<(&str, &BytesStat)> = vec![
+            ("open", &x.open),
+            ("close", &x.close),
+            ("mknod", &x.mknod),
+            ("link", &x.link),
+            ("unlink", &x.unlink),
+            ("mkdir", &x.mkdir),
+            ("rmdir", &x.rmdir),
+            ("rename", &x.rename),
+            ("getattr", &x.getattr),
+            ("setattr", &x.setattr),
+            ("getxattr", &x.getxattr),
+            ("setxattr", &x.setxattr),
+            ("statfs", &x.statfs),
+            ("sync", &x.sync),
+            ("samedir_rename", &x.samedir_rename),
+            ("crossdir_rename", &x.crossdir_rename),
+            ("read_bytes", &x.read_bytes),
+            ("write_bytes", &x.write_bytes),
+            ("punch", &x.punch),
+        ];
+
+        if let Some(stat) = &x.parallel_rename_dir {
+            ops.push(("parallel_rename_dir", stat));
+        }
+
+        if let Some(stat) = &x.parallel_rename_file {
+            ops.push(("parallel_rename_file", stat));
+        }
+
+        job_counters(
+            labels,
+            &x.job_id,
+            &x.snapshot_time,
+            x.start_time.as_ref(),
+            &ops,
+            out,
+        );
+    }
+}
+
+fn lnet_counters(x: &LNetStats, out: &mut Vec<Counter>) {
+    let (nid, param, values): (_, _, Vec<(&str, i64)>) = match x {
+        LNetStats::SendCount(x) | LNetStats::RecvCount(x) | LNetStats::DropCount(x) => {
+            (Some(x.nid.as_str()), &x.param, vec![("value", x.value)])
+        }
+        LNetStats::SendLength(x)
+        | LNetStats::RecvLength(x)
+        | LNetStats::DropLength(x)
+        | LNetStats::Errors(x)
+        | LNetStats::GlobalSendCount(x)
+        | LNetStats::ResendCount(x)
+        | LNetStats::ResponseTimeoutCount(x)
+        | LNetStats::LocalInterruptCount(x)
+        | LNetStats::LocalDroppedCount(x)
+        | LNetStats::LocalAbortedCount(x)
+        | LNetStats::LocalNoRouteCount(x)
+        | LNetStats::LocalTimeoutCount(x)
+        | LNetStats::LocalErrorCount(x)
+        | LNetStats::RemoteDroppedCount(x)
+        | LNetStats::RemoteErrorCount(x)
+        | LNetStats::RemoteTimeoutCount(x)
+        | LNetStats::NetworkTimeoutCount(x)
+        | LNetStats::GlobalRecvCount(x)
+        | LNetStats::RouteCount(x)
+        | LNetStats::GlobalDropCount(x)
+        | LNetStats::RouteLength(x) => (None, &x.param, vec![("value", x.value)]),
+        LNetStats::SentStats(x) | LNetStats::ReceivedStats(x) | LNetStats::DroppedStats(x) => (
+            Some(x.nid.as_str()),
+            &x.param,
+            vec![
+                ("put", x.value.put),
+                ("get", x.value.get),
+                ("reply", x.value.reply),
+                ("ack", x.value.ack),
+                ("hello", x.value.hello),
+            ],
+        ),
+        LNetStats::Health(x) => (
+            Some(x.nid.as_str()),
+            &x.param,
+            vec![
+                ("interrupts", x.value.interrupts),
+                ("dropped", x.value.dropped),
+                ("aborted", x.value.aborted),
+                ("no_route", x.value.no_route),
+                ("timeouts", x.value.timeouts),
+                ("error", x.value.error),
+            ],
+        ),
+        LNetStats::PeerHealth(x) => (
+            Some(x.nid.as_str()),
+            &x.param,
+            vec![
+                ("dropped", x.value.dropped),
+                ("timeout", x.value.timeout),
+                ("error", x.value.error),
+                ("network_timeout", x.value.network_timeout),
+            ],
+        ),
+        LNetStats::Status(_)
+        | LNetStats::MsgsAlloc(_)
+        | LNetStats::MsgsMax(_)
+        | LNetStats::RstAlloc(_)
+        | LNetStats::Tunables(_)
+        | LNetStats::Cpt(_)
+        | LNetStats::PeerMaxNiTxCredits(_)
+        | LNetStats::PeerAvailableTxCredits(_)
+        | LNetStats::PeerMinTxCredits(_)
+        | LNetStats::PeerTxQNumOfBuf(_)
+        | LNetStats::PeerAvailableRtrCredits(_)
+        | LNetStats::PeerMinRtrCredits(_)
+        | LNetStats::PeerState(_)
+        | LNetStats::RouteState(_)
+        | LNetStats::RoutePriority(_)
+        | LNetStats::RoutingEnabled(_)
+        | LNetStats::RouterBuffers(_) => return,
+    };
+
+    let labels = Labels {
+        nid,
+        ..Labels::new(param)
+    };
+
+    for (field, value) in values {
+        if let Ok(value) = u64::try_from(value) {
+            out.push(labels.counter(param, None, field, value, None));
+        }
+    }
+}
+
+/// The sample time of a stats block, in milliseconds.
+fn time(x: &Option<UnsignedLustreTimestamp>) -> Option<i64> {
+    x.as_ref().map(|x| x.0)
+}
+
+/// Flattens all cumulative counters out of `records`.
+fn counters(records: &[Record]) -> Vec<Counter> {
+    let mut out = vec![];
+
+    for record in records {
+        match record {
+            Record::Target(TargetStats::Stats(x)) => stats_counters(
+                &Labels::target(x.kind, &x.target, &x.param),
+                &x.value,
+                time(&x.snapshot_time),
+                &mut out,
+            ),
+            Record::Target(TargetStats::ExportStats(x)) => {
+                for export in &x.value {
+                    let labels = Labels {
+                        nid: Some(&export.nid),
+                        ..Labels::target(x.kind, &x.target, &x.param)
+                    };
+
+                    stats_counters(
+                        &labels,
+                        &export.stats,
+                        time(&export.snapshot_time),
+                        &mut out,
+                    );
+                }
+            }
+            Record::Target(TargetStats::Oss(x)) => stats_counters(
+                &Labels::new(&x.param),
+                &x.stats,
+                time(&x.snapshot_time),
+                &mut out,
+            ),
+            Record::Target(TargetStats::Mds(x)) => stats_counters(
+                &Labels::new(&x.param),
+                &x.stats,
+                time(&x.snapshot_time),
+                &mut out,
+            ),
+            Record::Target(TargetStats::Llite(x)) => {
+                let labels = Labels {
+                    target: Some(&x.target),
+                    ..Labels::new(&x.param)
+                };
+
+                stats_counters(&labels, &x.stats, time(&x.snapshot_time), &mut out);
+            }
+            Record::Target(TargetStats::LliteReadAheadStats(x)) => {
+                let labels = Labels {
+                    target: Some(&x.target),
+                    ..Labels::new(&x.param)
+                };
+
+                stats_counters(&labels, &x.value, time(&x.snapshot_time), &mut out);
+            }
+            Record::Target(TargetStats::LliteExtentsStats(x)) => {
+                let labels = Labels {
+                    target: Some(&x.target),
+                    ..Labels::new(&x.param)
+                };
+
+                brw_stats_counters(
+                    &labels,
+                    x.value.as_slice(),
+                    time(&x.snapshot_time),
+                    &mut out,
+                );
+            }
+            Record::Target(TargetStats::BrwStats(x)) => brw_stats_counters(
+                &Labels::target(x.kind, &x.target, &x.param),
+                &x.value,
+                time(&x.snapshot_time),
+                &mut out,
+            ),
+            Record::Target(TargetStats::ClientStats(x)) => {
+                let labels = Labels {
+                    client: Some(&x.client),
+                    ..Labels::target(x.kind, &x.target, &x.param)
+                };
+
+                stats_counters(&labels, &x.value, time(&x.snapshot_time), &mut out);
+            }
+            Record::Target(TargetStats::ClientRpcStats(x)) => {
+                let labels = Labels {
+                    client: Some(&x.client),
+                    ..Labels::target(x.kind, &x.target, &x.param)
+                };
+
+                brw_stats_counters(
+                    &labels,
+                    &x.value.histograms,
+                    time(&x.snapshot_time),
+                    &mut out,
+                );
+            }
+            Record::Target(TargetStats::ClientImport(x)) => {
+                let labels = Labels {
+                    client: Some(&x.client),
+                    ..Labels::target(x.kind, &x.target, &x.param)
+                };
+
+                for (name, value) in [
+                    ("connection_attempts", x.value.connection_attempts),
+                    ("timeouts", x.value.timeouts),
+                ] {
+                    out.push(labels.counter(name, None, "value", value, None));
+                }
+            }
+            Record::Target(TargetStats::JobStatsOst(x)) => job_stats_ost_counters(
+                &Labels::target(x.kind, &x.target, &x.param),
+                x.value.as_deref().unwrap_or_default(),
+                &mut out,
+            ),
+            Record::Target(TargetStats::JobStatsMdt(x)) => job_stats_mdt_counters(
+                &Labels::target(x.kind, &x.target, &x.param),
+                x.value.as_deref().unwrap_or_default(),
+                &mut out,
+            ),
+            Record::LustreService(x) => {
+                let (param, xs) = match x {
+                    LustreServiceStats::LdlmCanceld(xs) => ("ldlm_canceld", xs),
+                    LustreServiceStats::LdlmCbd(xs) => ("ldlm_cbd", xs),
+                };
+
+                stats_counters(&Labels::new(&Param(param.to_string())), xs, None, &mut out);
+            }
+            Record::LNetStat(x) => lnet_counters(x, &mut out),
+            _ => {}
+        }
+    }
+
+    out
+}
+
+fn rate(prev: Option<&Counter>, cur: Counter, interval: Duration) -> Option<Rate> {
+    let reset = prev.map_or(true, |prev| {
+        cur.value < prev.value
+            || matches!((prev.time, cur.time), (Some(p), Some(c)) if c < p)
+            || matches!((prev.time, cur.start), (Some(p), Some(s)) if s > p)
+    });
+
+    // After a reset the counter restarted from zero. That is only usable if we know
+    // when, as for job stats; otherwise the sample is skipped.
+    let (base, base_time) = match prev {
+        Some(prev) if !reset => (prev.value, prev.time),
+        _ => (0, Some(cur.start?)),
+    };
+
+    let elapsed = match (base_time, cur.time) {
+        (Some(b), Some(c)) => (c - b) as f64 / 1_000.0,
+        _ => interval.as_secs_f64(),
+    };
+
+    if elapsed <= 0.0 {
+        return None;
+    }
+
+    let delta = cur.value.checked_sub(base)?;
+
+    Some(Rate {
+        key: cur.key,
+        delta,
+        elapsed,
+        per_second: delta as f64 / elapsed,
+    })
+}
+
+/// Computes per-second rates for all cumulative counters present in both `prev` and `cur`.
+///
+/// `interval` is the time between the two collections and is only used for counters
+/// without a Lustre `snapshot_time`. Counters that went backwards, or whose
+/// `snapshot_time` did, are treated as reset and skipped, unless they carry a
+/// `start_time` (job stats) to measure from. Job stats entries that expired
+/// are dropped.
+pub fn rates(prev: &[Record], cur: &[Record], interval: Duration) -> Vec<Rate> {
+    let prev: HashMap<_, _> = counters(prev)
+        .into_iter()
+        .map(|x| (x.key.clone(), x))
+        .collect();
+
+    counters(cur)
+        .into_iter()
+        .filter_map(|x| rate(prev.get(&x.key), x, interval))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_lctl_output, parse_lnetctl_stats, TargetStat};
+
+    fn obdfilter_stats(time: &str, write_samples: u64) -> Vec<Record> {
+        let x = format!(
+            "obdfilter.fs-OST0000.stats=
+snapshot_time             {time} secs.nsecs
+write_bytes               {write_samples} samples [bytes] 4096 4194304 {}
+statfs                    100 samples [reqs]
+",
+            write_samples * 4096
+        );
+
+        parse_lctl_output(x.as_bytes()).unwrap()
+    }
+
+    fn find<'a>(xs: &'a [Rate], name: &str, field: &str) -> Option<&'a Rate> {
+        xs.iter()
+            .find(|x| x.key.name == name && x.key.field == field)
+    }
+
+    #[test]
+    fn test_stats_rates() {
+        let prev = obdfilter_stats("1700000000.000000000", 10);
+        let cur = obdfilter_stats("1700000010.000000000", 30);
+
+        let xs = rates(&prev, &cur, Duration::from_secs(60));
+
+        let x = find(&xs, "write_bytes", "samples").unwrap();
+
+        assert_eq!(x.delta, 20);
+        assert_eq!(x.elapsed, 10.0);
+        assert_eq!(x.per_second, 2.0);
+        assert_eq!(x.key.target, Some(Target("fs-OST0000".to_string())));
+
+        let x = find(&xs, "write_bytes", "sum").unwrap();
+
+        assert_eq!(x.per_second, 20.0 * 4096.0 / 10.0);
+
+        let x = find(&xs, "statfs", "samples").unwrap();
+
+        assert_eq!(x.per_second, 0.0);
+    }
+
+    #[test]
+    fn test_counter_reset() {
+        let prev = obdfilter_stats("1700000000.000000000", 30);
+        let cur = obdfilter_stats("1700000010.000000000", 10);
+
+        let xs = rates(&prev, &cur, Duration::from_secs(10));
+
+        assert!(find(&xs, "write_bytes", "samples").is_none());
+        assert!(find(&xs, "statfs", "samples").is_some());
+    }
+
+    #[test]
+    fn test_snapshot_time_backwards() {
+        let prev = obdfilter_stats("1700000010.000000000", 10);
+        let cur = obdfilter_stats("1700000000.000000000", 30);
+
+        assert_eq!(rates(&prev, &cur, Duration::from_secs(10)), vec![]);
+    }
+
+    fn job_stats(time: i64, xs: &[(&str, i64, i64)]) -> Vec<Record> {
+        let xs = xs
+            .iter()
+            .map(|(job_id, start, write)| {
+                format!(
+                    r#"- job_id:          {job_id}
+  snapshot_time:   {time}.000000000 secs.nsecs
+  start_time:      {start}.000000000 secs.nsecs
+  elapsed_time:    0
+  read_bytes:      {{ samples:           0, unit: bytes, min:       0, max:       0, sum:               0 }}
+  write_bytes:     {{ samples:           1, unit: bytes, min:       0, max:       0, sum:         {write} }}
+  getattr:         {{ samples:           0, unit:  reqs }}
+  setattr:         {{ samples:           0, unit:  reqs }}
+  punch:           {{ samples:           0, unit:  reqs }}
+  sync:            {{ samples:           0, unit:  reqs }}
+  destroy:         {{ samples:           0, unit:  reqs }}
+  create:          {{ samples:           0, unit:  reqs }}
+  statfs:          {{ samples:           0, unit:  reqs }}
+  get_info:        {{ samples:           0, unit:  reqs }}
+  set_info:        {{ samples:           0, unit:  reqs }}
+  quotactl:        {{ samples:           0, unit:  reqs }}"#
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let x = format!("obdfilter.fs-OST0000.job_stats=\njob_stats:\n{xs}\n");
+
+        parse_lctl_output(x.as_bytes()).unwrap()
+    }
+
+    fn job_rate<'a>(xs: &'a [Rate], job_id: &str) -> Option<&'a Rate> {
+        xs.iter().find(|x| {
+            x.key.job_id.as_deref() == Some(job_id)
+                && x.key.name == "write_bytes"
+                && x.key.field == "sum"
+        })
+    }
+
+    #[test]
+    fn test_job_stats_rates() {
+        let prev = job_stats(
+            100,
+            &[
+                ("kept", 50, 1_000),
+                ("expired", 50, 1_000),
+                ("restarted", 50, 9_000),
+            ],
+        );
+        let cur = job_stats(
+            110,
+            &[
+                ("kept", 50, 3_000),
+                ("new", 105, 500),
+                ("restarted", 108, 400),
+            ],
+        );
+
+        let xs = rates(&prev, &cur, Duration::from_secs(60));
+
+        assert_eq!(job_rate(&xs, "kept").unwrap().per_second, 200.0);
+        assert_eq!(job_rate(&xs, "new").unwrap().per_second, 100.0);
+        assert_eq!(job_rate(&xs, "restarted").unwrap().per_second, 200.0);
+        assert!(job_rate(&xs, "expired").is_none());
+
+        let Record::Target(TargetStats::JobStatsOst(TargetStat {
+            value: Some(jobs), ..
+        })) = &cur[0]
+        else {
+            panic!("Expected job stats");
+        };
+
+        assert_eq!(jobs[0].snapshot_time, UnsignedLustreTimestamp(110_000));
+    }
+
+    fn mdt_job_stats(time: i64, opens: i64) -> Vec<Record> {
+        let x = format!(
+            r#"mdt.fs-MDT0000.job_stats=
+job_stats:
+- job_id:          touch.0
+  snapshot_time:   {time}.000000000 secs.nsecs
+  start_time:      50.000000000 secs.nsecs
+  elapsed_time:    0
+  open:            {{ samples: {opens:>11}, unit: usecs, min:     315, max:     315, sum:             315, sumsq:              99225 }}
+  close:           {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  mknod:           {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  link:            {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  unlink:          {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  mkdir:           {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  rmdir:           {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  rename:          {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  getattr:         {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  setattr:         {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  getxattr:        {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  setxattr:        {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  statfs:          {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  sync:            {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  samedir_rename:  {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  crossdir_rename: {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  read_bytes:      {{ samples:           0, unit: bytes, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  write_bytes:     {{ samples:           0, unit: bytes, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+  punch:           {{ samples:           0, unit: usecs, min:       0, max:       0, sum:               0, sumsq:                  0 }}
+"#
+        );
+
+        parse_lctl_output(x.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_mdt_job_stats_rates() {
+        let prev = mdt_job_stats(100, 10);
+        let cur = mdt_job_stats(110, 60);
+
+        let xs = rates(&prev, &cur, Duration::from_secs(60));
+
+        let open = xs
+            .iter()
+            .find(|x| x.key.name == "open" && x.key.field == "samples")
+            .unwrap();
+
+        assert_eq!(open.key.kind, Some(TargetVariant::Mdt));
+        assert_eq!(open.key.job_id.as_deref(), Some("touch.0"));
+        assert_eq!(open.delta, 50);
+        assert_eq!(open.per_second, 5.0);
+    }
+
+    #[test]
+    fn test_lnet_rates() {
+        let x = |send_length: u64| {
+            parse_lnetctl_stats(&format!(
+                "statistics:
+    msgs_alloc: 0
+    msgs_max: 0
+    rst_alloc: 0
+    errors: 0
+    send_count: 0
+    resend_count: 0
+    response_timeout_count: 0
+    local_interrupt_count: 0
+    local_dropped_count: 0
+    local_aborted_count: 0
+    local_no_route_count: 0
+    local_timeout_count: 0
+    local_error_count: 0
+    remote_dropped_count: 0
+    remote_error_count: 0
+    remote_timeout_count: 0
+    network_timeout_count: 0
+    recv_count: 0
+    route_count: 0
+    drop_count: 0
+    send_length: {send_length}
+    recv_length: 0
+    route_length: 0
+    drop_length: 0
+"
+            ))
+            .unwrap()
+        };
+
+        let xs = rates(&x(100), &x(400), Duration::from_secs(30));
+
+        let x = find(&xs, "send_length", "value").unwrap();
+
+        assert_eq!(x.delta, 300);
+        assert_eq!(x.elapsed, 30.0);
+        assert_eq!(x.per_second, 10.0);
+    }
+
+    #[test]
+    fn test_brw_stats_rates() {
+        let brw = |time: &str, writes: u64| {
+            let x = format!(
+                "osd-ldiskfs.fs-OST0000.brw_stats=
+snapshot_time:         {time} (secs.nsecs)
+
+                           read      |     write
+pages per bulk r/w     rpcs  % cum % |  rpcs        % cum %
+1:		         0   0   0   |  {writes}  100 100
+"
+            );
+
+            parse_lctl_output(x.as_bytes()).unwrap()
+        };
+
+        let xs = rates(
+            &brw("1700000000.000000000", 5),
+            &brw("1700000000.500000000", 10),
+            Duration::from_secs(1),
+        );
+
+        let x = find(&xs, "pages", "write").unwrap();
+
+        assert_eq!(x.key.bucket, Some(1));
+        assert_eq!(x.elapsed, 0.5);
+        assert_eq!(x.per_second, 10.0);
+    }
+}
@@ -151,6 +151,7 @@ where
                         param: param.clone(),
                         target: target.clone(),
                         value: *value,
+                        snapshot_time: None,
                     }),
                     RecoveryStat::Completed(value) => {
                         TargetStats::RecoveryCompletedClients(TargetStat {
@@ -158,6 +159,7 @@ where
                             param: param.clone(),
                             target: target.clone(),
                             value: *value,
+                            snapshot_time: None,
                         })
                     }
                     RecoveryStat::Connected(value) => {
@@ -166,6 +168,7 @@ where
                             param: param.clone(),
                             target: target.clone(),
                             value: *value,
+                            snapshot_time: None,
                         })
                     }
                     RecoveryStat::Evicted(value) => {
@@ -174,6 +177,7 @@ where
                             param: param.clone(),
                             target: target.clone(),
                             value: *value,
+                            snapshot_time: None,
                         })
                     }
                 })
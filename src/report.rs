@@ -0,0 +1,356 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Capacity and inode utilisation per filesystem and target, with OST
+//! imbalance detection.
+//!
+//! Percentages are computed like `df`: used space over used plus available
+//! space, so blocks reserved for root don't count as free.
+//!
+//! An OST is flagged as imbalanced when its fill level deviates from the mean
+//! of its group by more than a threshold in percentage points. The groups are
+//! the OST pools from `lod.*.pools.*`, as files striped over a pool only fill
+//! its OSTs. OSTs in no pool, or all OSTs when no pools were collected, are
+//! compared with the mean of all OSTs in the filesystem.
+
+use crate::{
+    topology::{filesystems, fsname, Filesystem, FilesystemTotals, TargetNode},
+    types::{Record, Target, TargetStats, TargetVariant},
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Default imbalance threshold, in percentage points.
+pub const DEFAULT_IMBALANCE_THRESHOLD: f64 = 10.0;
+
+/// `used / (used + avail)` as a percentage, `None` when there is no capacity.
+fn used_pct(used: u64, avail: u64) -> Option<f64> {
+    let (used, avail) = (used as f64, avail as f64);
+
+    (used + avail > 0.0).then(|| used / (used + avail) * 100.0)
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TargetUtilisation {
+    pub kind: TargetVariant,
+    pub target: Target,
+    pub kbytes_total: Option<u64>,
+    pub kbytes_used: Option<u64>,
+    pub kbytes_avail: Option<u64>,
+    pub kbytes_used_pct: Option<f64>,
+    pub files_total: Option<u64>,
+    pub files_used: Option<u64>,
+    pub files_used_pct: Option<f64>,
+    /// Percentage points above (positive) or below the mean of the OST's pool,
+    /// or of the filesystem for OSTs in no pool. For OSTs in several pools the
+    /// largest deviation. OSTs only
+    pub deviation: Option<f64>,
+    /// Whether `deviation` exceeds the imbalance threshold
+    pub imbalanced: bool,
+}
+
+impl TargetUtilisation {
+    fn new(x: &TargetNode) -> Self {
+        let kbytes_used = x
+            .kbytes_total()
+            .zip(x.kbytes_free())
+            .map(|(total, free)| total.saturating_sub(free));
+        let files_used = x
+            .files_total()
+            .zip(x.files_free())
+            .map(|(total, free)| total.saturating_sub(free));
+
+        Self {
+            kind: x.kind,
+            target: x.target.clone(),
+            kbytes_total: x.kbytes_total(),
+            kbytes_used,
+            kbytes_avail: x.kbytes_avail(),
+            kbytes_used_pct: kbytes_used
+                .zip(x.kbytes_avail())
+                .and_then(|(used, avail)| used_pct(used, avail)),
+            files_total: x.files_total(),
+            files_used,
+            files_used_pct: files_used
+                .zip(x.files_free())
+                .and_then(|(used, free)| used_pct(used, free)),
+            deviation: None,
+            imbalanced: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PoolReport {
+    pub name: String,
+    pub osts: Vec<Target>,
+    /// Mean fill level of the pool's OSTs
+    pub ost_mean_used_pct: Option<f64>,
+    /// OSTs whose fill level deviates from the pool mean by more than the threshold
+    pub imbalanced_osts: Vec<Target>,
+}
+
+/// Mean of the OST fill levels in `targets` that `f` selects.
+fn mean_used_pct(targets: &[TargetUtilisation], f: impl Fn(&Target) -> bool) -> Option<f64> {
+    let fills: Vec<f64> = targets
+        .iter()
+        .filter(|x| x.kind == TargetVariant::Ost && f(&x.target))
+        .filter_map(|x| x.kbytes_used_pct)
+        .collect();
+
+    (!fills.is_empty()).then(|| fills.iter().sum::<f64>() / fills.len() as f64)
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FilesystemReport {
+    pub name: String,
+    pub totals: FilesystemTotals,
+    pub kbytes_used_pct: Option<f64>,
+    pub files_used_pct: Option<f64>,
+    /// Mean fill level of the filesystem's OSTs
+    pub ost_mean_used_pct: Option<f64>,
+    /// OSTs whose fill level deviates from the mean of their pool, or of the
+    /// filesystem, by more than the threshold
+    pub imbalanced_osts: Vec<Target>,
+    /// OST pools, empty when none were collected
+    pub pools: Vec<PoolReport>,
+    pub targets: Vec<TargetUtilisation>,
+}
+
+impl FilesystemReport {
+    fn new(x: &Filesystem, pools: &BTreeMap<&str, BTreeSet<&Target>>, threshold: f64) -> Self {
+        let totals = x.totals.clone();
+
+        let mut targets: Vec<_> = x
+            .mdts
+            .iter()
+            .chain(&x.osts)
+            .map(TargetUtilisation::new)
+            .collect();
+
+        let mean = mean_used_pct(&targets, |_| true);
+
+        let pool_means: Vec<_> = pools
+            .iter()
+            .map(|(name, osts)| (*name, osts, mean_used_pct(&targets, |x| osts.contains(x))))
+            .collect();
+
+        for x in targets.iter_mut().filter(|x| x.kind == TargetVariant::Ost) {
+            let Some(pct) = x.kbytes_used_pct else {
+                continue;
+            };
+
+            let mut means = pool_means
+                .iter()
+                .filter(|(_, osts, _)| osts.contains(&x.target))
+                .map(|(_, _, mean)| *mean)
+                .peekable();
+
+            let deviations: Vec<f64> = if means.peek().is_some() {
+                means.flatten().map(|mean| pct - mean).collect()
+            } else {
+                mean.map(|mean| pct - mean).into_iter().collect()
+            };
+
+            x.deviation = deviations
+                .into_iter()
+                .max_by(|a, b| a.abs().total_cmp(&b.abs()));
+            x.imbalanced = x.deviation.is_some_and(|d| d.abs() > threshold);
+        }
+
+        let pools = pool_means
+            .into_iter()
+            .map(|(name, osts, mean)| PoolReport {
+                name: name.to_string(),
+                osts: osts.iter().map(|x| (*x).clone()).collect(),
+                ost_mean_used_pct: mean,
+                imbalanced_osts: mean
+                    .map(|mean| {
+                        targets
+                            .iter()
+                            .filter(|x| osts.contains(&x.target))
+                            .filter(|x| {
+                                x.kbytes_used_pct
+                                    .is_some_and(|pct| (pct - mean).abs() > threshold)
+                            })
+                            .map(|x| x.target.clone())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        Self {
+            name: x.name.clone(),
+            kbytes_used_pct: used_pct(
+                totals.kbytes_total.saturating_sub(totals.kbytes_free),
+                totals.kbytes_avail,
+            ),
+            files_used_pct: used_pct(
+                totals.files_total.saturating_sub(totals.files_free),
+                totals.files_free,
+            ),
+            totals,
+            ost_mean_used_pct: mean,
+            imbalanced_osts: targets
+                .iter()
+                .filter(|x| x.imbalanced)
+                .map(|x| x.target.clone())
+                .collect(),
+            pools,
+            targets,
+        }
+    }
+}
+
+/// Collects the OST pools of each filesystem from `lod.*.pools.*`.
+///
+/// Every MDT reports the same pools, so their members are merged.
+fn pools(records: &[Record]) -> BTreeMap<&str, BTreeMap<&str, BTreeSet<&Target>>> {
+    let mut fss: BTreeMap<&str, BTreeMap<&str, BTreeSet<&Target>>> = BTreeMap::new();
+
+    for x in records {
+        let Record::Target(TargetStats::OstPool(x)) = x else {
+            continue;
+        };
+
+        let Some(fs) = fsname(&x.target) else {
+            continue;
+        };
+
+        fss.entry(fs)
+            .or_default()
+            .entry(&x.value.name)
+            .or_default()
+            .extend(&x.value.osts);
+    }
+
+    fss
+}
+
+/// Computes the utilisation of every filesystem found in `records`.
+///
+/// `threshold` is the allowed deviation of an OST from the mean fill level of
+/// its pool, or of all OSTs in its filesystem when it is in no pool, in
+/// percentage points.
+pub fn report(records: &[Record], threshold: f64) -> Vec<FilesystemReport> {
+    let pools = pools(records);
+
+    filesystems(records)
+        .iter()
+        .map(|x| {
+            let pools = pools.get(x.name.as_str()).cloned().unwrap_or_default();
+
+            FilesystemReport::new(x, &pools, threshold)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_lctl_output;
+
+    fn ost(name: &str, total: u64, free: u64, avail: u64) -> String {
+        format!(
+            "osd-ldiskfs.{name}.kbytestotal={total}
+osd-ldiskfs.{name}.kbytesfree={free}
+osd-ldiskfs.{name}.kbytesavail={avail}
+"
+        )
+    }
+
+    #[test]
+    fn test_imbalance() {
+        let x = [
+            ost("fs-OST0000", 1000, 900, 900),
+            ost("fs-OST0001", 1000, 800, 800),
+            ost("fs-OST0002", 1000, 100, 100),
+            ost("other-OST0000", 1000, 500, 450),
+        ]
+        .concat();
+
+        let records = parse_lctl_output(x.as_bytes()).unwrap();
+
+        let xs = report(&records, 25.0);
+
+        assert_eq!(
+            xs.iter().map(|x| x.name.as_str()).collect::<Vec<_>>(),
+            ["fs", "other"]
+        );
+
+        let fs = &xs[0];
+
+        assert_eq!(fs.kbytes_used_pct, Some(40.0));
+        assert_eq!(fs.ost_mean_used_pct, Some(40.0));
+        assert_eq!(
+            fs.imbalanced_osts,
+            [
+                Target("fs-OST0000".to_string()),
+                Target("fs-OST0002".to_string())
+            ]
+        );
+        assert_eq!(fs.targets[1].deviation, Some(-20.0));
+        assert!(!fs.targets[1].imbalanced);
+
+        let other = &xs[1];
+
+        assert_eq!(other.targets[0].kbytes_used, Some(500));
+        assert_eq!(
+            other.targets[0].kbytes_used_pct,
+            Some(500.0 / 950.0 * 100.0)
+        );
+        assert!(other.imbalanced_osts.is_empty());
+        assert_eq!(other.files_used_pct, None);
+    }
+
+    #[test]
+    fn test_pool_imbalance() {
+        let x = [
+            ost("fs-OST0000", 1000, 900, 900),
+            ost("fs-OST0001", 1000, 700, 700),
+            ost("fs-OST0002", 1000, 200, 200),
+            ost("fs-OST0003", 1000, 100, 100),
+            ost("fs-OST0004", 1000, 500, 500),
+            "lod.fs-MDT0000-mdtlov.pools.flash=
+fs-OST0000_UUID
+fs-OST0001_UUID
+lod.fs-MDT0000-mdtlov.pools.disk=
+fs-OST0002_UUID
+fs-OST0003_UUID
+"
+            .to_string(),
+        ]
+        .concat();
+
+        let records = parse_lctl_output(x.as_bytes()).unwrap();
+
+        let fs = &report(&records, 15.0)[0];
+
+        let deviation = |name: &str| {
+            fs.targets
+                .iter()
+                .find(|x| *x.target == *name)
+                .and_then(|x| x.deviation)
+        };
+
+        assert_eq!(fs.ost_mean_used_pct, Some(52.0));
+        assert_eq!(
+            fs.pools
+                .iter()
+                .map(|x| (x.name.as_str(), x.ost_mean_used_pct))
+                .collect::<Vec<_>>(),
+            [("disk", Some(85.0)), ("flash", Some(20.0))]
+        );
+
+        // Compared with the 52% filesystem mean, every pooled OST would be
+        // flagged; compared with their pools none is.
+        assert!(fs.pools.iter().all(|x| x.imbalanced_osts.is_empty()));
+        assert_eq!(deviation("fs-OST0000"), Some(-10.0));
+        assert_eq!(deviation("fs-OST0003"), Some(5.0));
+
+        // OSTs in no pool fall back to the filesystem mean.
+        assert_eq!(deviation("fs-OST0004"), Some(-2.0));
+        assert!(fs.imbalanced_osts.is_empty());
+    }
+}
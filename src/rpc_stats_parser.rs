@@ -0,0 +1,159 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+use crate::{
+    base_parsers::{digits, string_to, till_newline, word},
+    brw_stats_parser::bucket,
+    time::{time_triple, to_timestamp},
+    types::{BrwStats, RpcStats, RpcStatsCounter, UnsignedLustreTimestamp},
+};
+use combine::{
+    attempt, choice,
+    error::ParseError,
+    many, many1, none_of,
+    parser::char::{newline, spaces, string},
+    stream::Stream,
+    token, Parser,
+};
+
+/// Parses a header line such as `read RPCs in flight:  0`
+fn counter<I>() -> impl Parser<I, Output = RpcStatsCounter>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    attempt((
+        many1::<String, _, _>(none_of(":\n".chars())),
+        token(':'),
+        spaces().with(digits()),
+        newline(),
+    ))
+    .map(|(name, _, value, _)| RpcStatsCounter {
+        name: name
+            .split_whitespace()
+            .map(|x| x.to_lowercase())
+            .collect::<Vec<_>>()
+            .join("_"),
+        value,
+    })
+}
+
+fn rw_columns<I>() -> impl Parser<I, Output = ()>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (string("read"), spaces(), string("write"), till_newline()).map(|_| ())
+}
+
+fn header<I>() -> impl Parser<I, Output = BrwStats>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    let keys = choice([
+        attempt(string_to("pages per rpc", "pages_per_rpc")),
+        attempt(string_to("rpcs in flight", "rpcs_in_flight")),
+        attempt(string_to("offset", "offset")),
+    ]);
+
+    (keys.skip(spaces()), word().skip(till_newline())).map(|(name, unit)| BrwStats {
+        name,
+        unit,
+        buckets: vec![],
+    })
+}
+
+fn section<I>() -> impl Parser<I, Output = BrwStats>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        rw_columns().skip(newline()),
+        header().skip(newline()),
+        many(bucket().skip(newline())).skip(spaces()),
+    )
+        .map(|(_, stats, xs)| BrwStats {
+            buckets: xs,
+            ..stats
+        })
+}
+
+/// Parses an `rpc_stats` block into its `snapshot_time` and stats.
+pub(crate) fn rpc_stats<I>() -> impl Parser<I, Output = (Option<UnsignedLustreTimestamp>, RpcStats)>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (
+        newline().with(time_triple()),
+        many(counter()),
+        spaces(),
+        many(section()),
+    )
+        .map(|(time, counters, _, histograms)| {
+            (
+                to_timestamp(&time),
+                RpcStats {
+                    counters,
+                    histograms,
+                },
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn test_counter() {
+        let result = counter().parse("write RPCs in flight: 3\n");
+
+        assert_eq!(
+            result,
+            Ok((
+                RpcStatsCounter {
+                    name: "write_rpcs_in_flight".to_string(),
+                    value: 3,
+                },
+                ""
+            ))
+        );
+    }
+
+    #[test]
+    fn test_rpc_stats() {
+        let x = r#"
+snapshot_time:         1700000000.123456789 secs.nsecs
+start_time:            1690000000.000000000 secs.nsecs
+elapsed_time:          10000000.123456789 secs.nsecs
+read RPCs in flight:  0
+write RPCs in flight: 1
+pending write pages:  12
+pending read pages:   0
+
+			read			write
+pages per rpc         rpcs   % cum % |       rpcs   % cum %
+1:		         4  40  40   |          1  10  10
+2:		         0   0  40   |          0   0  10
+4:		         6  60 100   |          9  90 100
+
+			read			write
+rpcs in flight        rpcs   % cum % |       rpcs   % cum %
+1:		        10 100 100   |          8  80  80
+2:		         0   0 100   |          2  20 100
+
+			read			write
+offset                rpcs   % cum % |       rpcs   % cum %
+0:		        10 100 100   |         10 100 100
+"#;
+
+        let result = rpc_stats().parse(x);
+
+        assert_debug_snapshot!(result);
+    }
+}
@@ -0,0 +1,294 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! A minimal HTTP server exposing the latest collected stats on `/metrics`.
+//!
+//! Stats are either collected on every scrape, or periodically in a background
+//! thread with scrapes served from the most recent result.
+
+use crate::LustreCollectorError;
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+use tracing::{debug, warn};
+
+pub const METRICS_PATH: &str = "/metrics";
+
+/// How long a connection may stall reading the request or writing the
+/// response before it is dropped, so one client can't block the accept loop.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The most recent collection result, shared between the collector
+/// thread and request handlers.
+type Latest = Arc<Mutex<Option<Result<String, String>>>>;
+
+pub struct Server<F> {
+    collect: Arc<F>,
+    content_type: &'static str,
+    latest: Option<Latest>,
+    timeout: Duration,
+}
+
+impl<F> Server<F>
+where
+    F: Fn() -> Result<String, LustreCollectorError> + Send + Sync + 'static,
+{
+    /// Creates a server that runs `collect` on every scrape.
+    pub fn new(content_type: &'static str, collect: F) -> Self {
+        Self {
+            collect: Arc::new(collect),
+            content_type,
+            latest: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets the read and write timeout of connections, [`DEFAULT_TIMEOUT`]
+    /// by default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+
+        self
+    }
+
+    /// Runs `collect` every `interval` in a background thread and serves
+    /// the latest result instead of collecting on every scrape.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        let latest: Latest = Arc::new(Mutex::new(None));
+
+        let collect = Arc::clone(&self.collect);
+        let shared = Arc::clone(&latest);
+
+        thread::spawn(move || loop {
+            let result = collect().map_err(|e| e.to_string());
+
+            if let Err(e) = &result {
+                warn!("Collection failed: {e}");
+            }
+
+            if let Ok(mut x) = shared.lock() {
+                *x = Some(result);
+            }
+
+            thread::sleep(interval);
+        });
+
+        self.latest = Some(latest);
+
+        self
+    }
+
+    fn metrics(&self) -> Result<String, String> {
+        if let Some(latest) = &self.latest {
+            let latest = latest.lock().map_err(|e| e.to_string())?;
+
+            return latest
+                .clone()
+                .unwrap_or_else(|| Err("No stats collected yet".to_string()));
+        }
+
+        (self.collect)().map_err(|e| e.to_string())
+    }
+
+    /// Handles a single HTTP request on `stream`.
+    pub fn handle(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        // Drain the request headers, we don't use them.
+        loop {
+            let mut header = String::new();
+
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+        }
+
+        debug!(request = request_line.trim());
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let target = parts.next().unwrap_or_default();
+
+        // Scrapers may add query params, e.g. `/metrics?format=prometheus`.
+        let path = target.split_once('?').map_or(target, |(path, _)| path);
+
+        let (status, content_type, body) = match (method, path) {
+            ("GET", METRICS_PATH) => match self.metrics() {
+                Ok(x) => ("200 OK", self.content_type, x),
+                Err(e) => ("500 Internal Server Error", "text/plain", e),
+            },
+            ("GET", _) => ("404 Not Found", "text/plain", "Not Found".to_string()),
+            _ => (
+                "405 Method Not Allowed",
+                "text/plain",
+                "Method Not Allowed".to_string(),
+            ),
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+
+        stream.flush()
+    }
+
+    /// Serves requests from `listener` until an error occurs accepting connections.
+    pub fn serve(&self, listener: TcpListener) -> Result<(), LustreCollectorError> {
+        for stream in listener.incoming() {
+            if let Err(e) = self.handle(stream?) {
+                warn!("Failed to handle request: {e}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        collector::{collect, ScriptedRunner, LCTL_GET_PARAM},
+        parse_lctl_output, parser, render_prometheus, ParseMode,
+    };
+    use std::{io::Read, net::SocketAddr};
+
+    fn spawn<F>(server: Server<F>) -> SocketAddr
+    where
+        F: Fn() -> Result<String, LustreCollectorError> + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || server.serve(listener));
+
+        addr
+    }
+
+    fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        response
+    }
+
+    fn fake_collect() -> Result<String, LustreCollectorError> {
+        let xs = include_bytes!("./fixtures/valid/valid.txt");
+
+        Ok(render_prometheus(&parse_lctl_output(xs)?))
+    }
+
+    #[test]
+    fn test_metrics_on_scrape() {
+        let addr = spawn(Server::new("text/plain; version=0.0.4", fake_collect));
+
+        let response = get(addr, METRICS_PATH);
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4\r\n"));
+        assert!(response.ends_with(&fake_collect().unwrap()));
+    }
+
+    #[test]
+    fn test_metrics_on_interval() {
+        let server = Server::new("text/plain", fake_collect).with_interval(Duration::from_secs(60));
+
+        let addr = spawn(server);
+
+        let mut response = get(addr, METRICS_PATH);
+
+        for _ in 0..50 {
+            if response.starts_with("HTTP/1.1 200 OK\r\n") {
+                break;
+            }
+
+            thread::sleep(Duration::from_millis(100));
+            response = get(addr, METRICS_PATH);
+        }
+
+        assert!(response.ends_with(&fake_collect().unwrap()));
+    }
+
+    #[test]
+    fn test_collect_error() {
+        let addr = spawn(Server::new("text/plain", || {
+            Err(LustreCollectorError::ConversionError("boom".to_string()))
+        }));
+
+        let response = get(addr, METRICS_PATH);
+
+        assert!(response.starts_with("HTTP/1.1 500 Internal Server Error\r\n"));
+        assert!(response.ends_with("boom"));
+    }
+
+    #[test]
+    fn test_stalled_client() {
+        let addr =
+            spawn(Server::new("text/plain", fake_collect).with_timeout(Duration::from_millis(100)));
+
+        // Connects but never sends a request
+        let _idle = TcpStream::connect(addr).unwrap();
+
+        let response = get(addr, METRICS_PATH);
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+
+    #[test]
+    fn test_metrics_with_query() {
+        let addr = spawn(Server::new("text/plain", fake_collect));
+
+        let response = get(addr, "/metrics?foo=bar");
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(&fake_collect().unwrap()));
+    }
+
+    #[test]
+    fn test_metrics_from_collect() {
+        let lctl = include_str!("./fixtures/valid/valid.txt");
+
+        let runner = ScriptedRunner::new().with_output(LCTL_GET_PARAM, lctl);
+
+        let addr = spawn(Server::new("text/plain", move || {
+            Ok(render_prometheus(&collect(
+                &runner,
+                &parser::params(),
+                ParseMode::Strict,
+            )?))
+        }));
+
+        let response = get(addr, METRICS_PATH);
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(&render_prometheus(
+            &parse_lctl_output(lctl.as_bytes()).unwrap()
+        )));
+    }
+
+    #[test]
+    fn test_not_found() {
+        let addr = spawn(Server::new("text/plain", fake_collect));
+
+        let response = get(addr, "/");
+
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
@@ -6,12 +6,14 @@ use crate::{
     base_parsers::{digits, not_words, word},
     ldlm::LDLM,
     llite::LLITE,
+    mdc::MDC,
     mdd_parser::MDD,
     mds::mds_parser::MDS,
+    osc::OSC,
     oss::oss_parser::OST,
     quota::QMT,
-    time::time_triple,
-    types::Stat,
+    time::{time_triple, to_timestamp},
+    types::{Stat, UnsignedLustreTimestamp},
 };
 use combine::{
     between,
@@ -31,7 +33,20 @@ where
     I::Error: ParseError<I::Token, I::Range, I::Position>,
 {
     (
-        not_words(&["obdfilter", "mgs", "mdt", LDLM, OST, LLITE, MDS, MDD, QMT]).skip(spaces()),
+        not_words(&[
+            "obdfilter",
+            "mgs",
+            "mdt",
+            LDLM,
+            OST,
+            LLITE,
+            MDS,
+            MDD,
+            QMT,
+            OSC,
+            MDC,
+        ])
+        .skip(spaces()),
         digits(),
         spaces().with(string("samples")),
         spaces().with(between(token('['), token(']'), word())),
@@ -107,12 +122,14 @@ where
         )
 }
 
-pub(crate) fn stats<I>() -> impl Parser<I, Output = Vec<Stat>>
+/// Parses a stats block into its `snapshot_time` and stats.
+pub(crate) fn stats<I>() -> impl Parser<I, Output = (Option<UnsignedLustreTimestamp>, Vec<Stat>)>
 where
     I: Stream<Token = char>,
     I::Error: ParseError<I::Token, I::Range, I::Position>,
 {
-    (optional(newline()).with(time_triple()), many(stat())).map(|(_, xs)| xs)
+    (optional(newline()).with(time_triple()), many(stat()))
+        .map(|(time, xs)| (to_timestamp(&time), xs))
 }
 
 #[cfg(test)]
@@ -150,7 +167,7 @@ mod tests {
                     min: Some(1),
                     max: Some(1),
                     sum: Some(21108),
-                    sumsquare: None
+                    sumsquare: None,
                 },
                 ""
             ))
@@ -174,7 +191,7 @@ mod tests {
                     min: Some(15),
                     max: Some(72),
                     sum: Some(47014),
-                    sumsquare: Some(2_156_132)
+                    sumsquare: Some(2_156_132),
                 },
                 ""
             ))
@@ -0,0 +1,399 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Incremental parsing of `lctl get_param` output.
+//!
+//! [`RecordStream`] reads from a [`BufRead`] one param block at a time, so
+//! memory use is bounded by the largest block instead of the whole output.
+//! `job_stats` blocks, which can hold hundreds of megabytes on a busy OSS, are
+//! split further: each job is parsed on its own and yields a separate record
+//! holding just that job.
+//!
+//! This bounds the input held in memory, not the output: callers that collect
+//! all records, like [`crate::collector::collect`], still hold every record.
+
+use crate::{
+    check_lctl_output, parse_error,
+    parser::{self, param_line},
+    types::{Record, TargetStat, TargetStats},
+    LustreCollectorError, ParseMode,
+};
+use combine::parser::EasyParser;
+use std::{collections::VecDeque, io, io::BufRead};
+
+/// A line of input and its line number, starting at 1.
+type Line = (usize, String);
+
+fn is_job(x: &str) -> bool {
+    x.starts_with("- job_id:")
+}
+
+fn is_job_stats(x: &str) -> bool {
+    param_line(x).is_some_and(|x| x.ends_with(".job_stats"))
+}
+
+/// `mdt.*.exports.*.uuid` lines are counted together into connected clients,
+/// so a run of them is parsed as one block.
+fn is_export_uuid(x: &str) -> bool {
+    param_line(x)
+        .is_some_and(|x| x.starts_with("mdt.") && x.contains(".exports.") && x.ends_with(".uuid"))
+}
+
+/// Yields the [`Record`]s of `lctl get_param` output read from `R`.
+///
+/// Parsing follows `mode` like [`crate::parse_lctl_output_with`]. In
+/// [`ParseMode::Strict`] the first error ends the stream.
+pub struct RecordStream<R> {
+    reader: R,
+    mode: ParseMode,
+    /// Number of lines read so far
+    line: usize,
+    /// A line read ahead, the start of the next block or job
+    peeked: Option<Line>,
+    /// The lines preceding the first job while inside a `job_stats` block
+    job_header: Option<Vec<Line>>,
+    records: VecDeque<Record>,
+    done: bool,
+}
+
+impl<R: BufRead> RecordStream<R> {
+    pub fn new(reader: R, mode: ParseMode) -> Self {
+        Self {
+            reader,
+            mode,
+            line: 0,
+            peeked: None,
+            job_header: None,
+            records: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Joins the per-job records of each `job_stats` block back into one
+    /// record per target, as [`crate::parse_lctl_output`] returns them.
+    ///
+    /// Records are still yielded as they are read: only the record of the
+    /// target being read is held back until its last job.
+    pub fn merged(self) -> Merged<Self> {
+        Merged {
+            inner: self,
+            pending: None,
+        }
+    }
+
+    fn read_line(&mut self) -> io::Result<Option<Line>> {
+        if let Some(x) = self.peeked.take() {
+            return Ok(Some(x));
+        }
+
+        let mut x = String::new();
+
+        if self.reader.read_line(&mut x)? == 0 {
+            return Ok(None);
+        }
+
+        if !x.ends_with('\n') {
+            x.push('\n');
+        }
+
+        self.line += 1;
+
+        Ok(Some((self.line, x)))
+    }
+
+    /// Appends lines to `xs` up to, but not including, the next line `stop` matches.
+    fn read_until(&mut self, mut xs: Vec<Line>, stop: fn(&str) -> bool) -> io::Result<Vec<Line>> {
+        while let Some(x) = self.read_line()? {
+            if stop(&x.1) {
+                self.peeked = Some(x);
+
+                break;
+            }
+
+            xs.push(x);
+        }
+
+        Ok(xs)
+    }
+
+    /// Reads the next unit to parse: a param block, or a single job of a
+    /// `job_stats` block prefixed with the block's header.
+    fn next_unit(&mut self) -> io::Result<Option<Vec<Line>>> {
+        let job_end = |x: &str| is_job(x) || param_line(x).is_some();
+
+        if let Some(header) = &self.job_header {
+            if self.peeked.as_ref().is_some_and(|x| is_job(&x.1)) {
+                let mut xs = header.clone();
+
+                if let Some(x) = self.read_line()? {
+                    xs.push(x);
+                }
+
+                return self.read_until(xs, job_end).map(Some);
+            }
+
+            self.job_header = None;
+        }
+
+        let Some(first) = self.read_line()? else {
+            return Ok(None);
+        };
+
+        if is_export_uuid(&first.1) {
+            return self
+                .read_until(vec![first], |x| {
+                    param_line(x).is_some() && !is_export_uuid(x)
+                })
+                .map(Some);
+        }
+
+        if !is_job_stats(&first.1) {
+            return self
+                .read_until(vec![first], |x| param_line(x).is_some())
+                .map(Some);
+        }
+
+        let header = self.read_until(vec![first], job_end)?;
+
+        if self.peeked.as_ref().is_some_and(|x| is_job(&x.1)) {
+            self.job_header = Some(header);
+
+            return self.next_unit();
+        }
+
+        Ok(Some(header))
+    }
+
+    fn parse(&self, unit: Vec<Line>) -> Result<Vec<Record>, LustreCollectorError> {
+        let (lines, text): (Vec<usize>, String) = unit.into_iter().unzip();
+
+        // Maps a line of `text` back to the line of the input
+        let input_line = |x: usize| match lines.get(x.saturating_sub(1)) {
+            Some(x) => *x,
+            None => lines.last().map_or(x, |x| x + 1),
+        };
+
+        match self.mode {
+            ParseMode::Strict => parser::parse()
+                .easy_parse(text.as_str())
+                .map_err(|err| parse_error(&text, err))
+                .and_then(|(records, state)| check_lctl_output(records, &text, state))
+                .map_err(|e| match e {
+                    LustreCollectorError::ParseError(mut x) => {
+                        x.line = input_line(x.line);
+
+                        LustreCollectorError::ParseError(x)
+                    }
+                    e => e,
+                }),
+            ParseMode::Lenient => Ok(parser::parse_lenient(&text)
+                .into_iter()
+                .map(|mut x| {
+                    if let Record::Diagnostic(x) = &mut x {
+                        x.line = input_line(x.line);
+                    }
+
+                    x
+                })
+                .collect()),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for RecordStream<R> {
+    type Item = Result<Record, LustreCollectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(x) = self.records.pop_front() {
+                return Some(Ok(x));
+            }
+
+            if self.done {
+                return None;
+            }
+
+            let unit = match self.next_unit() {
+                Ok(Some(x)) => x,
+                Ok(None) => {
+                    self.done = true;
+
+                    continue;
+                }
+                Err(e) => {
+                    self.done = true;
+
+                    return Some(Err(e.into()));
+                }
+            };
+
+            match self.parse(unit) {
+                Ok(xs) => self.records.extend(xs),
+                Err(e) => {
+                    self.done = true;
+
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+/// See [`RecordStream::merged`].
+pub struct Merged<I> {
+    inner: I,
+    /// The last record read, held back in case the next one continues it
+    pending: Option<Record>,
+}
+
+impl<I> Iterator for Merged<I>
+where
+    I: Iterator<Item = Result<Record, LustreCollectorError>>,
+{
+    type Item = Result<Record, LustreCollectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.inner.next() {
+                Some(Ok(x)) => match &mut self.pending {
+                    Some(pending) => {
+                        if let Some(x) = merge(pending, x) {
+                            return self.pending.replace(x).map(Ok);
+                        }
+                    }
+                    None => self.pending = Some(x),
+                },
+                Some(Err(e)) => return Some(Err(e)),
+                None => return self.pending.take().map(Ok),
+            }
+        }
+    }
+}
+
+/// Appends the jobs of `b` to `a` if both are `job_stats` of the same
+/// target, otherwise hands `b` back.
+fn merge(a: &mut Record, b: Record) -> Option<Record> {
+    match (a, b) {
+        (
+            Record::Target(TargetStats::JobStatsOst(a)),
+            Record::Target(TargetStats::JobStatsOst(b)),
+        ) if same_target(a, &b) => extend(&mut a.value, b.value),
+        (
+            Record::Target(TargetStats::JobStatsMdt(a)),
+            Record::Target(TargetStats::JobStatsMdt(b)),
+        ) if same_target(a, &b) => extend(&mut a.value, b.value),
+        (_, b) => return Some(b),
+    }
+
+    None
+}
+
+fn same_target<T>(a: &TargetStat<T>, b: &TargetStat<T>) -> bool {
+    a.kind == b.kind && a.target == b.target && a.param == b.param
+}
+
+fn extend<T>(a: &mut Option<Vec<T>>, b: Option<Vec<T>>) {
+    if let Some(mut b) = b {
+        a.get_or_insert_with(Vec::new).append(&mut b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_lctl_output, LustreCollectorError};
+    use include_dir::{include_dir, Dir};
+
+    static VALID_FIXTURES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src/fixtures/valid/");
+
+    #[test]
+    fn test_stream_valid_fixtures() {
+        for file in VALID_FIXTURES
+            .find("**/*")
+            .unwrap()
+            .filter_map(|x| x.as_file())
+        {
+            let expected = parse_lctl_output(file.contents()).unwrap();
+
+            for mode in [ParseMode::Strict, ParseMode::Lenient] {
+                let xs: Vec<_> = RecordStream::new(file.contents(), mode)
+                    .merged()
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+
+                assert_eq!(xs, expected, "{:?} {mode:?}", file.path());
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_job_per_record() {
+        let x = include_bytes!("./fixtures/valid/ex8761-lctl.txt");
+
+        let jobs: usize = RecordStream::new(&x[..], ParseMode::Strict)
+            .map(|x| match x.unwrap() {
+                Record::Target(TargetStats::JobStatsMdt(x)) => {
+                    let n = x.value.map_or(0, |x| x.len());
+
+                    assert!(n <= 1);
+
+                    n
+                }
+                _ => 0,
+            })
+            .sum();
+
+        assert_eq!(jobs, 2);
+    }
+
+    /// Fails every read, like a command that died mid-output.
+    struct Broken;
+
+    impl io::Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("broken pipe"))
+        }
+    }
+
+    #[test]
+    fn test_merged_is_incremental() {
+        let x = include_bytes!("./fixtures/valid/ex8761-lctl.txt");
+
+        let reader = io::BufReader::new(io::Read::chain(&x[..], Broken));
+        let mut xs = RecordStream::new(reader, ParseMode::Strict).merged();
+
+        // Records come out, job_stats joined per target, before the input fails
+        let ok: Vec<_> = xs.by_ref().map_while(Result::ok).collect();
+
+        assert!(ok.iter().any(|x| matches!(
+            x,
+            Record::Target(TargetStats::JobStatsMdt(x)) if x.value.as_ref().map(Vec::len) == Some(2)
+        )));
+        assert!(ok.len() > 20);
+    }
+
+    #[test]
+    fn test_stream_error_line() {
+        let x = b"memused=1\nhealth_check=healthy\nfoo.bar=zz\n";
+
+        let mut xs = RecordStream::new(&x[..], ParseMode::Strict);
+
+        assert!(matches!(xs.next(), Some(Ok(_))));
+        assert!(matches!(xs.next(), Some(Ok(_))));
+
+        let Some(Err(LustreCollectorError::ParseError(e))) = xs.next() else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(e.line, 3);
+        assert_eq!(e.param.as_deref(), Some("foo.bar"));
+        assert!(xs.next().is_none());
+
+        let xs: Vec<_> = RecordStream::new(&x[..], ParseMode::Lenient)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert!(matches!(&xs[2], Record::Diagnostic(x) if x.line == 3));
+    }
+}
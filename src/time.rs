@@ -2,15 +2,22 @@
 // Use of this source code is governed by a MIT-style
 // license that can be found in the LICENSE file.
 
-use crate::base_parsers::{digits, till_newline};
+use crate::{
+    base_parsers::{digits, till_newline},
+    types::UnsignedLustreTimestamp,
+};
 use combine::stream::Stream;
 use combine::{
-    attempt,
-    parser::char::{spaces, string},
+    attempt, many1,
+    parser::char::{digit, spaces, string},
 };
 use combine::{error::ParseError, parser::char::newline};
 use combine::{optional, token, Parser};
 
+/// Parses a `name: secs.nsecs` line into `"secs.nsecs"`.
+///
+/// The fraction is kept as text: parsing it as a number drops its leading
+/// zeros, turning `.038772898` into `.38772898`.
 fn time<I>(name: &'static str) -> impl Parser<I, Output = String>
 where
     I: Stream<Token = char>,
@@ -20,11 +27,30 @@ where
         string(name).skip(optional(token(':'))),
         spaces(),
         digits().skip(token('.')),
-        digits().skip(till_newline()),
+        many1::<String, _, _>(digit()).skip(till_newline()),
     )
         .map(|(_, _, secs, nsecs)| format!("{secs}.{nsecs}"))
 }
 
+/// Converts a "secs.fraction" time as returned by [`time_triple`] to milliseconds.
+pub(crate) fn to_timestamp(x: &str) -> Option<UnsignedLustreTimestamp> {
+    let (secs, fraction) = x.split_once('.')?;
+
+    let secs = secs.parse::<i64>().ok()?;
+
+    let millis = fraction
+        .chars()
+        .chain("000".chars())
+        .take(3)
+        .collect::<String>()
+        .parse::<i64>()
+        .ok()?;
+
+    Some(UnsignedLustreTimestamp(
+        secs.checked_mul(1_000)?.checked_add(millis)?,
+    ))
+}
+
 pub(crate) fn time_triple<I>() -> impl Parser<I, Output = String>
 where
     I: Stream<Token = char>,
@@ -76,6 +102,23 @@ mod tests {
         assert_eq!(result, Ok(("1534769431.137892896".to_string(), "\n")));
     }
 
+    #[test]
+    fn test_time_leading_zeros() {
+        let x = "snapshot_time: 1534158712.038772898 (secs.nsecs)\n";
+
+        let (result, _) = time("snapshot_time").parse(x).unwrap();
+
+        assert_eq!(result, "1534158712.038772898");
+        assert_eq!(
+            to_timestamp(&result),
+            Some(UnsignedLustreTimestamp(1_534_158_712_038))
+        );
+        assert_eq!(
+            to_timestamp("1409777887.5"),
+            Some(UnsignedLustreTimestamp(1_409_777_887_500))
+        );
+    }
+
     #[test]
     fn test_time_triple() {
         let x = r#"snapshot_time             1684948453.142852820 secs.nsecs
@@ -3,16 +3,16 @@
 // license that can be found in the LICENSE file.
 
 use crate::{
-    base_parsers::{digits, param, target},
-    types::{HostStat, HostStats, Param, Record},
+    base_parsers::{digits, param, target, till_newline, word},
+    types::{HostStat, HostStats, LustreVersion, Param, Record},
     HealthCheckStat, Target,
 };
 use combine::{
-    choice,
-    error::ParseError,
-    many1, optional,
+    attempt, choice,
+    error::{ParseError, StreamError},
+    many, many1, optional,
     parser::char::{newline, space, string},
-    stream::Stream,
+    stream::{Stream, StreamErrorFor},
     token, Parser,
 };
 
@@ -20,8 +20,19 @@ pub(crate) const MEMUSED_MAX: &str = "memused_max";
 pub(crate) const MEMUSED: &str = "memused";
 pub(crate) const LNET_MEMUSED: &str = "lnet_memused";
 pub(crate) const HEALTH_CHECK: &str = "health_check";
+pub(crate) const VERSION: &str = "version";
+pub(crate) const JOBID_NAME: &str = "jobid_name";
+pub(crate) const JOBID_VAR: &str = "jobid_var";
 
-pub(crate) const TOP_LEVEL_PARAMS: [&str; 4] = [MEMUSED, MEMUSED_MAX, LNET_MEMUSED, HEALTH_CHECK];
+pub(crate) const TOP_LEVEL_PARAMS: [&str; 7] = [
+    MEMUSED,
+    MEMUSED_MAX,
+    LNET_MEMUSED,
+    HEALTH_CHECK,
+    VERSION,
+    JOBID_NAME,
+    JOBID_VAR,
+];
 
 pub(crate) fn top_level_params() -> Vec<String> {
     TOP_LEVEL_PARAMS.iter().map(|x| (*x).to_string()).collect()
@@ -32,6 +43,8 @@ enum TopLevelStat {
     MemusedMax(u64),
     LnetMemused(u64),
     HealthCheck(HealthCheckStat),
+    JobIdName(String),
+    JobIdVar(String),
 }
 
 fn target_health<I>() -> impl Parser<I, Output = Target>
@@ -80,6 +93,45 @@ where
     ))
 }
 
+/// Parses the `version` param.
+///
+/// Newer releases report a single line (`version=2.14.0_ddn133`),
+/// older ones a block of `key: value` lines. Any of those lines may be
+/// missing: the version is taken from the `lustre` key, falling back to
+/// `lnet` and then `build`. A block with none of them yields no version.
+fn lustre_version<I>() -> impl Parser<I, Output = Option<LustreVersion>>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    choice((
+        newline()
+            .with(many(attempt((
+                word().skip(token(':')),
+                till_newline().skip(newline()),
+            ))))
+            .map(|xs: Vec<(String, String)>| {
+                let get = |k: &str| {
+                    xs.iter()
+                        .find(|(x, _)| x == k)
+                        .map(|(_, v)| v.trim().to_string())
+                };
+
+                let lnet = get("lnet");
+
+                ["lustre", "lnet", "build"]
+                    .into_iter()
+                    .find_map(|k| LustreVersion::try_from(get(k)?.as_str()).ok())
+                    .map(|x| LustreVersion { lnet, ..x })
+            }),
+        till_newline().skip(newline()).and_then(|x: String| {
+            LustreVersion::try_from(x.as_str())
+                .map(Some)
+                .map_err(StreamErrorFor::<I>::other)
+        }),
+    ))
+}
+
 fn top_level_stat<I>() -> impl Parser<I, Output = (Param, TopLevelStat)>
 where
     I: Stream<Token = char>,
@@ -104,6 +156,11 @@ where
             param(HEALTH_CHECK),
             health_stats().map(TopLevelStat::HealthCheck),
         ),
+        (
+            param(JOBID_NAME),
+            till_newline().map(TopLevelStat::JobIdName),
+        ),
+        (param(JOBID_VAR), till_newline().map(TopLevelStat::JobIdVar)),
     ))
     .skip(newline())
 }
@@ -119,11 +176,26 @@ where
             TopLevelStat::MemusedMax(value) => HostStats::MemusedMax(HostStat { param, value }),
             TopLevelStat::LnetMemused(value) => HostStats::LNetMemUsed(HostStat { param, value }),
             TopLevelStat::HealthCheck(value) => HostStats::HealthCheck(HostStat { param, value }),
+            TopLevelStat::JobIdName(value) => HostStats::JobIdName(HostStat { param, value }),
+            TopLevelStat::JobIdVar(value) => HostStats::JobIdVar(HostStat { param, value }),
         })
         .map(Record::Host)
         .message("while parsing top_level_param")
 }
 
+/// Parses the `version` param, `None` for a block without a version.
+pub(crate) fn version<I>() -> impl Parser<I, Output = Option<Record>>
+where
+    I: Stream<Token = char>,
+    I::Error: ParseError<I::Token, I::Range, I::Position>,
+{
+    (param(VERSION), lustre_version())
+        .map(|(param, x)| {
+            x.map(|value| Record::Host(HostStats::Version(HostStat { param, value })))
+        })
+        .message("while parsing version")
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -139,6 +211,9 @@ mod tests {
                 "memused_max".to_string(),
                 "lnet_memused".to_string(),
                 "health_check".to_string(),
+                "version".to_string(),
+                "jobid_name".to_string(),
+                "jobid_var".to_string(),
             ]
         )
     }
@@ -295,4 +370,107 @@ NOT HEALTHY
             ))
         )
     }
+
+    #[test]
+    fn test_jobid_name() {
+        let result = parse().parse("jobid_name=%e.%u.%h\n");
+
+        assert_eq!(
+            result,
+            Ok((
+                Record::Host(HostStats::JobIdName(HostStat {
+                    param: Param(JOBID_NAME.to_string()),
+                    value: "%e.%u.%h".to_string(),
+                })),
+                ""
+            ))
+        )
+    }
+
+    #[test]
+    fn test_version() {
+        let result = version().parse("version=2.14.0_ddn133\n");
+
+        assert_eq!(
+            result,
+            Ok((
+                Some(Record::Host(HostStats::Version(HostStat {
+                    param: Param(VERSION.to_string()),
+                    value: LustreVersion {
+                        version: "2.14.0_ddn133".to_string(),
+                        major: 2,
+                        minor: 14,
+                        patch: 0,
+                        vendor: Some("ddn133".to_string()),
+                        lnet: None,
+                    }
+                }))),
+                ""
+            ))
+        )
+    }
+
+    #[test]
+    fn test_legacy_version() {
+        let result = version().parse(
+            r#"version=
+lustre: 2.10.5
+kernel: patchless_client
+build:  2.10.5-RC2
+memused=1
+"#,
+        );
+
+        assert_eq!(
+            result,
+            Ok((
+                Some(Record::Host(HostStats::Version(HostStat {
+                    param: Param(VERSION.to_string()),
+                    value: LustreVersion {
+                        version: "2.10.5".to_string(),
+                        major: 2,
+                        minor: 10,
+                        patch: 5,
+                        vendor: None,
+                        lnet: None,
+                    }
+                }))),
+                "memused=1\n"
+            ))
+        )
+    }
+
+    #[test]
+    fn test_legacy_version_without_lustre() {
+        let result = version().parse(
+            r#"version=
+lnet: 2.5.42.4
+kernel: patchless_client
+memused=1
+"#,
+        );
+
+        assert_eq!(
+            result,
+            Ok((
+                Some(Record::Host(HostStats::Version(HostStat {
+                    param: Param(VERSION.to_string()),
+                    value: LustreVersion {
+                        version: "2.5.42.4".to_string(),
+                        major: 2,
+                        minor: 5,
+                        patch: 42,
+                        vendor: None,
+                        lnet: Some("2.5.42.4".to_string()),
+                    }
+                }))),
+                "memused=1\n"
+            ))
+        );
+
+        assert_eq!(
+            version().parse("version=\nkernel: patchless_client\nmemused=1\n"),
+            Ok((None, "memused=1\n"))
+        );
+    }
 }
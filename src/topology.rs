@@ -0,0 +1,307 @@
+// Copyright (c) 2024 DDN. All rights reserved.
+// Use of this source code is governed by a MIT-style
+// license that can be found in the LICENSE file.
+
+//! Groups the flat list of [`Record`]s into filesystems and their targets.
+//!
+//! Targets are assigned to a filesystem by the `fsname-OSTxxxx` / `fsname-MDTxxxx`
+//! naming convention. The MGT is attached to every filesystem it reports in
+//! `mgs.*.live.*`, so filesystems without local OSTs or MDTs still show up on an
+//! MGS-only node.
+
+use crate::types::{Record, Target, TargetStat, TargetStats, TargetVariant};
+use std::collections::BTreeMap;
+
+/// A single target and all stats collected for it.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct TargetNode<'a> {
+    pub kind: TargetVariant,
+    pub target: Target,
+    pub stats: Vec<&'a TargetStats>,
+}
+
+impl<'a> TargetNode<'a> {
+    fn new(kind: TargetVariant, target: &Target) -> Self {
+        Self {
+            kind,
+            target: target.clone(),
+            stats: vec![],
+        }
+    }
+
+    /// The value of the first stat `f` matches.
+    fn value(&self, f: impl Fn(&TargetStats) -> Option<u64>) -> Option<u64> {
+        self.stats.iter().find_map(|x| f(x))
+    }
+
+    pub fn kbytes_total(&self) -> Option<u64> {
+        self.value(|x| match x {
+            TargetStats::KBytesTotal(x) => Some(x.value),
+            _ => None,
+        })
+    }
+
+    pub fn kbytes_free(&self) -> Option<u64> {
+        self.value(|x| match x {
+            TargetStats::KBytesFree(x) => Some(x.value),
+            _ => None,
+        })
+    }
+
+    pub fn kbytes_avail(&self) -> Option<u64> {
+        self.value(|x| match x {
+            TargetStats::KBytesAvail(x) => Some(x.value),
+            _ => None,
+        })
+    }
+
+    pub fn files_total(&self) -> Option<u64> {
+        self.value(|x| match x {
+            TargetStats::FilesTotal(x) => Some(x.value),
+            _ => None,
+        })
+    }
+
+    pub fn files_free(&self) -> Option<u64> {
+        self.value(|x| match x {
+            TargetStats::FilesFree(x) => Some(x.value),
+            _ => None,
+        })
+    }
+
+    pub fn num_exports(&self) -> Option<u64> {
+        self.value(|x| match x {
+            TargetStats::NumExports(x) => Some(x.value),
+            _ => None,
+        })
+    }
+}
+
+/// Totals derived from the targets of a filesystem.
+///
+/// Space is summed over OSTs and inodes over MDTs, matching `lfs df`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FilesystemTotals {
+    pub kbytes_total: u64,
+    pub kbytes_free: u64,
+    pub kbytes_avail: u64,
+    pub files_total: u64,
+    pub files_free: u64,
+    /// Exports summed over OSTs and MDTs
+    pub exports: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct Filesystem<'a> {
+    pub name: String,
+    pub mgt: Option<TargetNode<'a>>,
+    pub mdts: Vec<TargetNode<'a>>,
+    pub osts: Vec<TargetNode<'a>>,
+    pub totals: FilesystemTotals,
+}
+
+impl<'a> Filesystem<'a> {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            mgt: None,
+            mdts: vec![],
+            osts: vec![],
+            totals: FilesystemTotals::default(),
+        }
+    }
+
+    fn targets(&mut self, kind: TargetVariant) -> &mut Vec<TargetNode<'a>> {
+        match kind {
+            TargetVariant::Mdt => &mut self.mdts,
+            _ => &mut self.osts,
+        }
+    }
+
+    fn compute_totals(&mut self) {
+        fn sum<'a>(xs: &[TargetNode<'a>], f: fn(&TargetNode<'a>) -> Option<u64>) -> u64 {
+            xs.iter().filter_map(f).sum()
+        }
+
+        self.totals = FilesystemTotals {
+            kbytes_total: sum(&self.osts, TargetNode::kbytes_total),
+            kbytes_free: sum(&self.osts, TargetNode::kbytes_free),
+            kbytes_avail: sum(&self.osts, TargetNode::kbytes_avail),
+            files_total: sum(&self.mdts, TargetNode::files_total),
+            files_free: sum(&self.mdts, TargetNode::files_free),
+            exports: sum(&self.osts, TargetNode::num_exports)
+                + sum(&self.mdts, TargetNode::num_exports),
+        };
+    }
+}
+
+/// Returns the kind and target of `x` for stats collected on the server
+/// side of a target.
+fn target_of(x: &TargetStats) -> Option<(TargetVariant, &Target)> {
+    fn key<T>(x: &TargetStat<T>) -> Option<(TargetVariant, &Target)> {
+        Some((x.kind, &x.target))
+    }
+
+    match x {
+        TargetStats::JobStatsOst(x) => key(x),
+        TargetStats::Stats(x) => key(x),
+        TargetStats::BrwStats(x) => key(x),
+        TargetStats::JobStatsMdt(x) => key(x),
+        TargetStats::FilesFree(x) => key(x),
+        TargetStats::FilesTotal(x) => key(x),
+        TargetStats::FsType(x) => key(x),
+        TargetStats::KBytesAvail(x) => key(x),
+        TargetStats::KBytesFree(x) => key(x),
+        TargetStats::KBytesTotal(x) => key(x),
+        TargetStats::NumExports(x) => key(x),
+        TargetStats::TotDirty(x) => key(x),
+        TargetStats::TotGranted(x) => key(x),
+        TargetStats::TotPending(x) => key(x),
+        TargetStats::ContendedLocks(x) => key(x),
+        TargetStats::ContentionSeconds(x) => key(x),
+        TargetStats::ConnectedClients(x) => key(x),
+        TargetStats::CtimeAgeLimit(x) => key(x),
+        TargetStats::EarlyLockCancel(x) => key(x),
+        TargetStats::FsNames(x) => key(x),
+        TargetStats::LockCount(x) => key(x),
+        TargetStats::LockTimeouts(x) => key(x),
+        TargetStats::LockUnusedCount(x) => key(x),
+        TargetStats::LruMaxAge(x) => key(x),
+        TargetStats::LruSize(x) => key(x),
+        TargetStats::MaxNolockBytes(x) => key(x),
+        TargetStats::MaxParallelAst(x) => key(x),
+        TargetStats::ResourceCount(x) => key(x),
+        TargetStats::ThreadsMin(x) => key(x),
+        TargetStats::ThreadsMax(x) => key(x),
+        TargetStats::ThreadsStarted(x) => key(x),
+        TargetStats::RecoveryStatus(x) => key(x),
+        TargetStats::RecoveryConnectedClients(x) => key(x),
+        TargetStats::RecoveryCompletedClients(x) => key(x),
+        TargetStats::RecoveryEvictedClients(x) => key(x),
+        TargetStats::ExportStats(x) => key(x),
+        TargetStats::Changelog(x) => key(x),
+        TargetStats::QuotaStatsOsd(x) => key(x),
+        TargetStats::LfsckNamespace(x) => key(x),
+        TargetStats::LfsckLayout(x) => key(x),
+        TargetStats::HsmControl(x) => key(x),
+        TargetStats::HsmActions(x) => key(x),
+        TargetStats::HsmActiveRequests(x) => key(x),
+        TargetStats::HsmAgents(x) => key(x),
+        TargetStats::OstPool(x) => key(x),
+        TargetStats::Oss(_)
+        | TargetStats::Llite(_)
+        | TargetStats::Mds(_)
+        | TargetStats::QuotaStats(_)
+        | TargetStats::ClientStats(_)
+        | TargetStats::ClientRpcStats(_)
+        | TargetStats::CurDirtyBytes(_)
+        | TargetStats::CurGrantBytes(_)
+        | TargetStats::MaxRpcsInFlight(_)
+        | TargetStats::ClientImport(_)
+        | TargetStats::ClientImportState(_)
+        | TargetStats::LliteReadAheadStats(_)
+        | TargetStats::LliteExtentsStats(_)
+        | TargetStats::LliteOffsetStats(_)
+        | TargetStats::LliteMaxCachedMb(_)
+        | TargetStats::LliteMaxReadAheadMb(_) => None,
+    }
+}
+
+/// Returns the filesystem name of a `fsname-OSTxxxx` style target.
+pub fn fsname(target: &Target) -> Option<&str> {
+    target.rsplit_once('-').map(|(fs, _)| fs)
+}
+
+/// Folds the server side target stats in `records` into filesystems,
+/// ordered by name.
+pub fn filesystems(records: &[Record]) -> Vec<Filesystem<'_>> {
+    let mut targets: BTreeMap<(&Target, TargetVariant), TargetNode> = BTreeMap::new();
+
+    for x in records {
+        let Record::Target(x) = x else {
+            continue;
+        };
+
+        let Some((kind, target)) = target_of(x) else {
+            continue;
+        };
+
+        targets
+            .entry((target, kind))
+            .or_insert_with(|| TargetNode::new(kind, target))
+            .stats
+            .push(x);
+    }
+
+    let mut fss: BTreeMap<String, Filesystem> = BTreeMap::new();
+
+    for ((target, kind), node) in targets {
+        if kind == TargetVariant::Mgt {
+            let names = node.stats.iter().filter_map(|x| match x {
+                TargetStats::FsNames(x) => Some(&x.value),
+                _ => None,
+            });
+
+            for name in names.flatten() {
+                fss.entry(name.0.clone())
+                    .or_insert_with(|| Filesystem::new(&name.0))
+                    .mgt = Some(node.clone());
+            }
+
+            continue;
+        }
+
+        let Some(name) = fsname(target) else {
+            continue;
+        };
+
+        fss.entry(name.to_string())
+            .or_insert_with(|| Filesystem::new(name))
+            .targets(kind)
+            .push(node);
+    }
+
+    fss.into_values()
+        .map(|mut x| {
+            x.compute_totals();
+            x
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_lctl_output, parse_mgs_fs_output};
+
+    #[test]
+    fn test_filesystems() {
+        let xs = include_bytes!("./fixtures/valid/2.14.0_ddn144_mds.txt");
+        let mut records = parse_lctl_output(xs).unwrap();
+        records.append(&mut parse_mgs_fs_output(b"mgs.MGS.live.fs\nmgs.MGS.live.other\n").unwrap());
+
+        let xs = filesystems(&records);
+
+        assert_eq!(
+            xs.iter().map(|x| x.name.as_str()).collect::<Vec<_>>(),
+            ["fs", "other"]
+        );
+
+        let fs = &xs[0];
+
+        assert_eq!(fs.mgt.as_ref().map(|x| x.target.0.as_str()), Some("MGS"));
+        assert_eq!(
+            fs.osts
+                .iter()
+                .map(|x| x.target.0.as_str())
+                .collect::<Vec<_>>(),
+            ["fs-OST0000", "fs-OST0001"]
+        );
+        assert_eq!(fs.mdts.len(), 1);
+
+        insta::assert_debug_snapshot!(fs.totals);
+
+        assert!(xs[1].osts.is_empty());
+        assert_eq!(xs[1].totals, FilesystemTotals::default());
+    }
+}
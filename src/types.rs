@@ -2,8 +2,8 @@
 // Use of this source code is governed by a MIT-style
 // license that can be found in the LICENSE file.
 
-use crate::LustreCollectorError;
-use std::{fmt, ops::Deref, time::Duration};
+use crate::{jobid::JobIdFields, LustreCollectorError};
+use std::{collections::BTreeMap, fmt, ops::Deref, time::Duration};
 
 #[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 /// The hostname cooresponding to these stats.
@@ -17,7 +17,9 @@ impl Deref for Host {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
 /// The Lustre target cooresponding to these stats.
 pub struct Target(pub String);
 
@@ -29,7 +31,7 @@ impl Deref for Target {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 /// The name of the stat.
 pub struct Param(pub String);
 
@@ -65,6 +67,9 @@ pub struct JobStatsOst {
 pub struct ExportStats {
     pub nid: String,
     pub stats: Vec<Stat>,
+    /// When the stats were sampled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_time: Option<UnsignedLustreTimestamp>,
 }
 
 /// Used to represent an unsigned timestamp in Lustre.
@@ -142,6 +147,9 @@ impl fmt::Display for UnsignedLustreTimestamp {
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
 pub struct JobStatOst {
     pub job_id: String,
+    /// Parts of `job_id` according to the `jobid_name` in effect, see [`crate::jobid::annotate`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id_fields: Option<JobIdFields>,
     pub snapshot_time: UnsignedLustreTimestamp,
     pub start_time: Option<UnsignedLustreTimestamp>,
     pub elapsed_time: Option<String>,
@@ -167,6 +175,9 @@ pub struct JobStatsMdt {
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
 pub struct JobStatMdt {
     pub job_id: String,
+    /// Parts of `job_id` according to the `jobid_name` in effect, see [`crate::jobid::annotate`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id_fields: Option<JobIdFields>,
     pub snapshot_time: UnsignedLustreTimestamp,
     pub start_time: Option<UnsignedLustreTimestamp>,
     pub elapsed_time: Option<String>,
@@ -193,8 +204,234 @@ pub struct JobStatMdt {
     pub parallel_rename_file: Option<BytesStat>,
 }
 
+/// A single operation of a job, e.g. `read_bytes`, `getattr` or `fallocate`.
+#[derive(Clone, Default, PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct JobStatOp {
+    pub samples: i64,
+    pub unit: String,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub sum: Option<i64>,
+    pub sumsq: Option<i64>,
+}
+
+impl From<&ReqsStat> for JobStatOp {
+    fn from(x: &ReqsStat) -> Self {
+        Self {
+            samples: x.samples,
+            unit: x.unit.clone(),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&BytesStat> for JobStatOp {
+    fn from(x: &BytesStat) -> Self {
+        Self {
+            samples: x.samples,
+            unit: x.unit.clone(),
+            min: Some(x.min),
+            max: Some(x.max),
+            sum: Some(x.sum),
+            sumsq: None,
+        }
+    }
+}
+
+/// A `job_stats` entry of an OST or MDT with every operation kept by name.
+///
+/// Unlike [`JobStatOst`] and [`JobStatMdt`] this keeps operations added in
+/// newer Lustre releases. Converting into either checks that the operations
+/// they need are present.
+#[derive(Clone, PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct JobStat {
+    pub job_id: String,
+    pub snapshot_time: UnsignedLustreTimestamp,
+    pub start_time: Option<UnsignedLustreTimestamp>,
+    pub elapsed_time: Option<String>,
+    pub ops: BTreeMap<String, JobStatOp>,
+}
+
+impl JobStat {
+    fn op(&self, name: &str) -> Result<&JobStatOp, LustreCollectorError> {
+        self.ops.get(name).ok_or_else(|| {
+            LustreCollectorError::ConversionError(format!("job {}: missing `{name}`", self.job_id))
+        })
+    }
+
+    fn reqs(&self, name: &str) -> Result<ReqsStat, LustreCollectorError> {
+        let x = self.op(name)?;
+
+        Ok(ReqsStat {
+            samples: x.samples,
+            unit: x.unit.clone(),
+        })
+    }
+
+    fn bytes(&self, name: &str) -> Result<BytesStat, LustreCollectorError> {
+        let x = self.op(name)?;
+
+        let field = |v: Option<i64>, field: &str| {
+            v.ok_or_else(|| {
+                LustreCollectorError::ConversionError(format!(
+                    "job {}: `{name}` has no `{field}`",
+                    self.job_id
+                ))
+            })
+        };
+
+        Ok(BytesStat {
+            samples: x.samples,
+            unit: x.unit.clone(),
+            min: field(x.min, "min")?,
+            max: field(x.max, "max")?,
+            sum: field(x.sum, "sum")?,
+        })
+    }
+
+    fn optional_bytes(&self, name: &str) -> Result<Option<BytesStat>, LustreCollectorError> {
+        self.ops
+            .contains_key(name)
+            .then(|| self.bytes(name))
+            .transpose()
+    }
+}
+
+impl TryFrom<JobStat> for JobStatOst {
+    type Error = LustreCollectorError;
+
+    fn try_from(x: JobStat) -> Result<Self, Self::Error> {
+        Ok(Self {
+            read_bytes: x.bytes("read_bytes")?,
+            write_bytes: x.bytes("write_bytes")?,
+            getattr: x.reqs("getattr")?,
+            setattr: x.reqs("setattr")?,
+            punch: x.reqs("punch")?,
+            sync: x.reqs("sync")?,
+            destroy: x.reqs("destroy")?,
+            create: x.reqs("create")?,
+            statfs: x.reqs("statfs")?,
+            get_info: x.reqs("get_info")?,
+            set_info: x.reqs("set_info")?,
+            quotactl: x.reqs("quotactl")?,
+            job_id: x.job_id,
+            snapshot_time: x.snapshot_time,
+            start_time: x.start_time,
+            elapsed_time: x.elapsed_time,
+            job_id_fields: None,
+        })
+    }
+}
+
+impl TryFrom<JobStat> for JobStatMdt {
+    type Error = LustreCollectorError;
+
+    fn try_from(x: JobStat) -> Result<Self, Self::Error> {
+        Ok(Self {
+            open: x.bytes("open")?,
+            close: x.bytes("close")?,
+            mknod: x.bytes("mknod")?,
+            link: x.bytes("link")?,
+            unlink: x.bytes("unlink")?,
+            mkdir: x.bytes("mkdir")?,
+            rmdir: x.bytes("rmdir")?,
+            rename: x.bytes("rename")?,
+            getattr: x.bytes("getattr")?,
+            setattr: x.bytes("setattr")?,
+            getxattr: x.bytes("getxattr")?,
+            setxattr: x.bytes("setxattr")?,
+            statfs: x.bytes("statfs")?,
+            sync: x.bytes("sync")?,
+            samedir_rename: x.bytes("samedir_rename")?,
+            crossdir_rename: x.bytes("crossdir_rename")?,
+            read_bytes: x.bytes("read_bytes")?,
+            write_bytes: x.bytes("write_bytes")?,
+            punch: x.bytes("punch")?,
+            parallel_rename_dir: x.optional_bytes("parallel_rename_dir")?,
+            parallel_rename_file: x.optional_bytes("parallel_rename_file")?,
+            job_id: x.job_id,
+            snapshot_time: x.snapshot_time,
+            start_time: x.start_time,
+            elapsed_time: x.elapsed_time,
+            job_id_fields: None,
+        })
+    }
+}
+
+impl From<&JobStatOst> for JobStat {
+    fn from(x: &JobStatOst) -> Self {
+        let bytes = [
+            ("read_bytes", &x.read_bytes),
+            ("write_bytes", &x.write_bytes),
+        ];
+        let reqs = [
+            ("getattr", &x.getattr),
+            ("setattr", &x.setattr),
+            ("punch", &x.punch),
+            ("sync", &x.sync),
+            ("destroy", &x.destroy),
+            ("create", &x.create),
+            ("statfs", &x.statfs),
+            ("get_info", &x.get_info),
+            ("set_info", &x.set_info),
+            ("quotactl", &x.quotactl),
+        ];
+
+        Self {
+            job_id: x.job_id.clone(),
+            snapshot_time: x.snapshot_time.clone(),
+            start_time: x.start_time.clone(),
+            elapsed_time: x.elapsed_time.clone(),
+            ops: bytes
+                .map(|(k, v)| (k.to_string(), v.into()))
+                .into_iter()
+                .chain(reqs.map(|(k, v)| (k.to_string(), v.into())))
+                .collect(),
+        }
+    }
+}
+
+impl From<&JobStatMdt> for JobStat {
+    fn from(x: &JobStatMdt) -> Self {
+        let ops: [(&str, Option<&BytesStat>); 21] = [
+            ("open", Some(&x.open)),
+            ("close", Some(&x.close)),
+            ("mknod", Some(&x.mknod)),
+            ("link", Some(&x.link)),
+            ("unlink", Some(&x.unlink)),
+            ("mkdir", Some(&x.mkdir)),
+            ("rmdir", Some(&x.rmdir)),
+            ("rename", Some(&x.rename)),
+            ("getattr", Some(&x.getattr)),
+            ("setattr", Some(&x.setattr)),
+            ("getxattr", Some(&x.getxattr)),
+            ("setxattr", Some(&x.setxattr)),
+            ("statfs", Some(&x.statfs)),
+            ("sync", Some(&x.sync)),
+            ("samedir_rename", Some(&x.samedir_rename)),
+            ("crossdir_rename", Some(&x.crossdir_rename)),
+            ("read_bytes", Some(&x.read_bytes)),
+            ("write_bytes", Some(&x.write_bytes)),
+            ("punch", Some(&x.punch)),
+            ("parallel_rename_dir", x.parallel_rename_dir.as_ref()),
+            ("parallel_rename_file", x.parallel_rename_file.as_ref()),
+        ];
+
+        Self {
+            job_id: x.job_id.clone(),
+            snapshot_time: x.snapshot_time.clone(),
+            start_time: x.start_time.clone(),
+            elapsed_time: x.elapsed_time.clone(),
+            ops: ops
+                .into_iter()
+                .filter_map(|(k, v)| Some((k.to_string(), v?.into())))
+                .collect(),
+        }
+    }
+}
+
 pub mod lnet_exports {
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
 
     #[derive(serde::Serialize, serde::Deserialize)]
     pub struct LocalNiS {
@@ -214,7 +451,8 @@ pub mod lnet_exports {
         pub interfaces: Option<HashMap<i64, String>>,
     }
 
-    #[derive(serde::Serialize, serde::Deserialize)]
+    /// Per message type counters.
+    #[derive(PartialEq, Eq, Clone, Debug, serde::Serialize, serde::Deserialize)]
     pub struct Stats {
         pub put: i64,
         pub get: i64,
@@ -223,28 +461,28 @@ pub mod lnet_exports {
         pub hello: i64,
     }
 
-    #[derive(serde::Serialize, serde::Deserialize)]
+    #[derive(PartialEq, Eq, Clone, Debug, serde::Serialize, serde::Deserialize)]
     pub struct HealthStats {
-        #[serde(rename = "health value")]
-        health_value: i64,
-        interrupts: i64,
-        dropped: i64,
-        aborted: i64,
-        #[serde(rename = "no route")]
-        no_route: i64,
-        timeouts: i64,
-        error: i64,
+        #[serde(alias = "health value")]
+        pub health_value: i64,
+        pub interrupts: i64,
+        pub dropped: i64,
+        pub aborted: i64,
+        #[serde(alias = "no route")]
+        pub no_route: i64,
+        pub timeouts: i64,
+        pub error: i64,
     }
 
-    #[derive(serde::Serialize, serde::Deserialize)]
+    #[derive(PartialEq, Eq, Clone, Debug, serde::Serialize, serde::Deserialize)]
     pub struct HealthStatsPeer {
-        #[serde(rename = "health value")]
-        health_value: i64,
-        dropped: i64,
-        timeout: i64,
-        error: i64,
-        #[serde(rename = "network timeout")]
-        network_timeout: i64,
+        #[serde(alias = "health value")]
+        pub health_value: i64,
+        pub dropped: i64,
+        pub timeout: i64,
+        pub error: i64,
+        #[serde(alias = "network timeout")]
+        pub network_timeout: i64,
     }
 
     #[derive(serde::Serialize, serde::Deserialize)]
@@ -273,23 +511,54 @@ pub mod lnet_exports {
         pub peer_ni: Vec<PeerNi>,
     }
 
+    /// A peer NI from `lnetctl peer show -v 3`. The statistics sections
+    /// are only present at higher verbosity levels.
     #[derive(serde::Serialize, serde::Deserialize)]
     pub struct PeerNi {
-        nid: String,
-        state: String,
-        max_ni_tx_credits: i64,
-        available_tx_credits: i64,
-        min_tx_credits: i64,
-        tx_q_num_of_buf: i64,
-        available_rtr_credits: i64,
-        min_rtr_credits: i64,
-        refcount: i64,
-        statistics: LNetStatistics,
-        sent_stats: Stats,
-        received_stats: Stats,
-        dropped_stats: Stats,
+        pub nid: String,
+        pub state: String,
+        pub max_ni_tx_credits: i64,
+        pub available_tx_credits: i64,
+        pub min_tx_credits: i64,
+        pub tx_q_num_of_buf: i64,
+        pub available_rtr_credits: i64,
+        pub min_rtr_credits: i64,
+        pub refcount: i64,
+        pub statistics: Option<LNetStatistics>,
+        pub sent_stats: Option<Stats>,
+        pub received_stats: Option<Stats>,
+        pub dropped_stats: Option<Stats>,
         #[serde(rename = "health stats")]
-        health_stats: HealthStatsPeer,
+        pub health_stats: Option<HealthStatsPeer>,
+    }
+
+    /// A route from `lnetctl route show -v`.
+    #[derive(serde::Serialize, serde::Deserialize)]
+    pub struct Route {
+        pub net: String,
+        pub gateway: String,
+        pub hop: i64,
+        pub priority: i64,
+        pub state: String,
+    }
+
+    /// A router buffer pool from `lnetctl routing show`.
+    #[derive(PartialEq, Eq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+    pub struct RouterBuffers {
+        pub npages: i64,
+        pub nbuffers: i64,
+        pub credits: i64,
+        pub mincredits: i64,
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(untagged)]
+    pub enum Routing {
+        Enable {
+            enable: i64,
+        },
+        /// Buffer pools (tiny, small, large) keyed by `cpt[N]`
+        Cpt(BTreeMap<String, BTreeMap<String, RouterBuffers>>),
     }
 
     #[derive(serde::Serialize, serde::Deserialize)]
@@ -327,7 +596,7 @@ pub mod lnet_exports {
         pub drop_length: i64,
     }
 
-    #[derive(serde::Serialize, serde::Deserialize)]
+    #[derive(PartialEq, Eq, Clone, Debug, serde::Serialize, serde::Deserialize)]
     pub struct Tunables {
         pub peer_timeout: i64,
         pub peer_credits: i64,
@@ -354,7 +623,9 @@ pub struct HostStat<T> {
     pub value: T,
 }
 
-#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize, Clone, Copy)]
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Hash, Debug, serde::Serialize, serde::Deserialize, Clone, Copy,
+)]
 pub enum TargetVariant {
     Ost,
     Mgt,
@@ -383,13 +654,35 @@ impl Deref for TargetVariant {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 /// Stats specific to a target.
 pub struct TargetStat<T> {
     pub kind: TargetVariant,
     pub param: Param,
     pub target: Target,
     pub value: T,
+    /// When a stats or histogram block was sampled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_time: Option<UnsignedLustreTimestamp>,
+}
+
+/// Leaves out an absent `snapshot_time` like serializing does, as most
+/// target params don't report one.
+impl<T: fmt::Debug> fmt::Debug for TargetStat<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut x = f.debug_struct("TargetStat");
+
+        x.field("kind", &self.kind)
+            .field("param", &self.param)
+            .field("target", &self.target)
+            .field("value", &self.value);
+
+        if let Some(time) = &self.snapshot_time {
+            x.field("snapshot_time", time);
+        }
+
+        x.finish()
+    }
 }
 
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
@@ -397,6 +690,9 @@ pub struct TargetStat<T> {
 pub struct OssStat {
     pub param: Param,
     pub stats: Vec<Stat>,
+    /// When the stats were sampled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_time: Option<UnsignedLustreTimestamp>,
 }
 
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
@@ -405,6 +701,43 @@ pub struct LliteStat {
     pub target: Target,
     pub param: Param,
     pub stats: Vec<Stat>,
+    /// When the stats were sampled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_time: Option<UnsignedLustreTimestamp>,
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// Stats from parsing `llite.*.<PARAM>`
+pub struct LliteTargetStat<T> {
+    pub target: Target,
+    pub param: Param,
+    pub value: T,
+    /// When a stats or histogram block was sampled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_time: Option<UnsignedLustreTimestamp>,
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// A single row of `llite.*.offset_stats`
+pub struct LliteOffsetStat {
+    /// `R` or `W`
+    pub op: char,
+    pub pid: u64,
+    pub range_start: u64,
+    pub range_end: u64,
+    pub smallest_extent: u64,
+    pub largest_extent: u64,
+    pub offset: i64,
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// Page cache usage from parsing `llite.*.max_cached_mb`
+pub struct LliteMaxCachedMb {
+    pub users: u64,
+    pub max_cached_mb: u64,
+    pub used_mb: u64,
+    pub unused_mb: u64,
+    pub reclaim_count: Option<u64>,
 }
 
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
@@ -412,6 +745,9 @@ pub struct LliteStat {
 pub struct MdsStat {
     pub param: Param,
     pub stats: Vec<Stat>,
+    /// When the stats were sampled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_time: Option<UnsignedLustreTimestamp>,
 }
 
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
@@ -424,6 +760,21 @@ pub struct TargetQuotaStat<T> {
     pub value: T,
 }
 
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// Stats from a client (`osc` or `mdc`) connection to a target.
+pub struct ClientTargetStat<T> {
+    pub kind: TargetVariant,
+    /// The target the connection points at, e.g. `fs-OST0000`
+    pub target: Target,
+    /// The client instance owning the connection
+    pub client: String,
+    pub param: Param,
+    pub value: T,
+    /// When a stats or histogram block was sampled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_time: Option<UnsignedLustreTimestamp>,
+}
+
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
 /// Stats specific to a LNet Nid.
 pub struct LNetStat<T> {
@@ -432,6 +783,34 @@ pub struct LNetStat<T> {
     pub value: T,
 }
 
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// Stats specific to a peer NI, as seen from this node.
+pub struct LNetPeerStat<T> {
+    pub primary_nid: String,
+    pub nid: String,
+    pub param: Param,
+    pub value: T,
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// Stats specific to a route, keyed by remote net, gateway and hop count.
+pub struct LNetRouteStat<T> {
+    pub net: String,
+    pub gateway: String,
+    pub hop: i64,
+    pub param: Param,
+    pub value: T,
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// Stats specific to a router buffer pool on a CPT.
+pub struct LNetRouterBufferStat<T> {
+    pub cpt: u64,
+    pub pool: String,
+    pub param: Param,
+    pub value: T,
+}
+
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
 /// Stats global across LNet Nids.
 pub struct LNetStatGlobal<T> {
@@ -446,6 +825,13 @@ pub struct ChangelogStat {
     pub users: Vec<ChangeLogUser>,
 }
 
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// An OST pool from parsing `lod.*.pools.*`.
+pub struct OstPool {
+    pub name: String,
+    pub osts: Vec<Target>,
+}
+
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ChangeLogUser {
     pub user: String,
@@ -490,6 +876,21 @@ pub struct BrwStats {
     pub buckets: Vec<BrwStatsBucket>,
 }
 
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct RpcStatsCounter {
+    pub name: String,
+    pub value: u64,
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// Stats from parsing `osc.*.rpc_stats` or `mdc.*.rpc_stats`
+pub struct RpcStats {
+    /// RPCs and pages currently in flight or pending
+    pub counters: Vec<RpcStatsCounter>,
+    /// Pages per RPC, RPCs in flight and offset histograms
+    pub histograms: Vec<BrwStats>,
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 pub enum RecoveryStatus {
     Complete,
@@ -500,12 +901,333 @@ pub enum RecoveryStatus {
     Unknown,
 }
 
+/// Status of an LFSCK component, as reported in `lfsck_namespace` and `lfsck_layout`
+#[derive(PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub enum LfsckStatus {
+    Init,
+    ScanningPhase1,
+    ScanningPhase2,
+    Completed,
+    Failed,
+    Stopped,
+    Paused,
+    Crashed,
+    Partial,
+    CoFailed,
+    CoStopped,
+    CoPaused,
+    Unknown,
+}
+
+impl From<&str> for LfsckStatus {
+    fn from(x: &str) -> Self {
+        match x.trim() {
+            "init" => Self::Init,
+            "scanning-phase1" => Self::ScanningPhase1,
+            "scanning-phase2" => Self::ScanningPhase2,
+            "completed" => Self::Completed,
+            "failed" => Self::Failed,
+            "stopped" => Self::Stopped,
+            "paused" => Self::Paused,
+            "crashed" => Self::Crashed,
+            "partial" => Self::Partial,
+            "co-failed" => Self::CoFailed,
+            "co-stopped" => Self::CoStopped,
+            "co-paused" => Self::CoPaused,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// Progress of an LFSCK component from parsing `mdd.*.lfsck_namespace`,
+/// `mdd.*.lfsck_layout` or `obdfilter.*.lfsck_layout`
+pub struct LfsckStats {
+    pub status: LfsckStatus,
+    pub success_count: u64,
+    pub checked_phase1: u64,
+    pub checked_phase2: u64,
+    pub failed_phase1: u64,
+    pub failed_phase2: u64,
+    /// Repair counters, e.g. `dirent_repaired` or `repaired_dangling`
+    pub repaired: BTreeMap<String, u64>,
+    /// In seconds
+    pub run_time_phase1: Option<u64>,
+    /// In seconds
+    pub run_time_phase2: Option<u64>,
+    /// In items per second
+    pub average_speed_phase1: Option<u64>,
+    /// In items per second
+    pub average_speed_phase2: Option<u64>,
+    /// In items per second, only while scanning
+    pub real_time_speed_phase1: Option<u64>,
+    /// In items per second, only while scanning
+    pub real_time_speed_phase2: Option<u64>,
+    /// In seconds, `None` if LFSCK never completed
+    pub time_since_last_completed: Option<u64>,
+}
+
+/// State of the HSM coordinator, from `mdt.*.hsm_control`
+#[derive(PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub enum HsmControl {
+    Enabled,
+    Disabled,
+    Stopped,
+    Shutdown,
+    Unknown,
+}
+
+impl From<&str> for HsmControl {
+    fn from(x: &str) -> Self {
+        match x.trim() {
+            "enabled" => Self::Enabled,
+            "disabled" => Self::Disabled,
+            "stopped" => Self::Stopped,
+            "shutdown" => Self::Shutdown,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub enum HsmAction {
+    Noop,
+    Archive,
+    Restore,
+    Remove,
+    Cancel,
+    Unknown,
+}
+
+impl From<&str> for HsmAction {
+    fn from(x: &str) -> Self {
+        match x.trim() {
+            "NOOP" => Self::Noop,
+            "ARCHIVE" => Self::Archive,
+            "RESTORE" => Self::Restore,
+            "REMOVE" => Self::Remove,
+            "CANCEL" => Self::Cancel,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub enum HsmActionStatus {
+    Waiting,
+    Started,
+    Succeed,
+    Failed,
+    Canceled,
+    Unknown,
+}
+
+impl From<&str> for HsmActionStatus {
+    fn from(x: &str) -> Self {
+        match x.trim() {
+            "WAITING" => Self::Waiting,
+            "STARTED" => Self::Started,
+            "SUCCEED" => Self::Succeed,
+            "FAILED" => Self::Failed,
+            "CANCELED" => Self::Canceled,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// Number of HSM actions per archive, action type and status
+pub struct HsmActionCount {
+    pub archive_id: u64,
+    pub action: HsmAction,
+    pub status: HsmActionStatus,
+    pub count: u64,
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// A registered copytool, from `mdt.*.hsm.agents`
+pub struct HsmAgent {
+    pub uuid: String,
+    /// Archive ids served by the agent, `ANY` or a comma separated list
+    pub archive_id: String,
+    /// Requests currently being processed
+    pub current: u64,
+    pub ok: u64,
+    pub errors: u64,
+}
+
+/// State of a client import, as reported in `osc.*.import` and `osc.*.state`
+#[derive(PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub enum ImportState {
+    Closed,
+    New,
+    Disconn,
+    Connecting,
+    Replay,
+    ReplayLocks,
+    ReplayWait,
+    Recover,
+    Full,
+    Evicted,
+    Idle,
+    Unknown,
+}
+
+impl From<&str> for ImportState {
+    fn from(x: &str) -> Self {
+        match x.trim() {
+            "CLOSED" => Self::Closed,
+            "NEW" => Self::New,
+            "DISCONN" => Self::Disconn,
+            "CONNECTING" => Self::Connecting,
+            "REPLAY" => Self::Replay,
+            "REPLAY_LOCKS" => Self::ReplayLocks,
+            "REPLAY_WAIT" => Self::ReplayWait,
+            "RECOVER" => Self::Recover,
+            "FULL" => Self::Full,
+            "EVICTED" => Self::Evicted,
+            "IDLE" => Self::Idle,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// Connection health from parsing `osc.*.import` or `mdc.*.import`
+pub struct ClientImport {
+    pub state: ImportState,
+    pub failover_nids: Vec<String>,
+    pub current_connection: Option<String>,
+    pub connection_attempts: u64,
+    pub generation: u64,
+    pub in_progress_invalidations: u64,
+    /// RPCs currently in flight
+    pub inflight: u64,
+    pub unregistering: u64,
+    pub timeouts: u64,
+    /// Average RPC wait time, in usecs
+    pub avg_waittime: Option<u64>,
+    /// Adaptive timeout service estimate, in seconds
+    pub service_estimate: Option<u64>,
+    /// Adaptive timeout network estimate, in seconds
+    pub network_estimate: Option<u64>,
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ImportStateChange {
+    /// When the import entered `state`, in seconds since the epoch
+    pub time: i64,
+    pub state: ImportState,
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// Import state history from parsing `osc.*.state`
+pub struct ImportStateHistory {
+    pub current_state: ImportState,
+    pub state_history: Vec<ImportStateChange>,
+}
+
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
 pub enum HostStats {
     MemusedMax(HostStat<u64>),
     Memused(HostStat<u64>),
     LNetMemUsed(HostStat<u64>),
     HealthCheck(HostStat<HealthCheckStat>),
+    Version(HostStat<LustreVersion>),
+    ObdDevice(HostStat<ObdDevice>),
+    /// The `jobid_name` format, e.g. `%e.%u`
+    JobIdName(HostStat<String>),
+    /// Where job IDs come from, e.g. `procname_uid` or `SLURM_JOB_ID`
+    JobIdVar(HostStat<String>),
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+/// The running Lustre version, e.g. `2.14.0_ddn133`
+pub struct LustreVersion {
+    pub version: String,
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    /// Vendor build suffix, e.g. `ddn133`
+    pub vendor: Option<String>,
+    /// The LNet version, only reported by older releases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lnet: Option<String>,
+}
+
+impl TryFrom<&str> for LustreVersion {
+    type Error = LustreCollectorError;
+
+    fn try_from(x: &str) -> Result<Self, Self::Error> {
+        let version = x.trim();
+
+        let idx = version
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(version.len());
+
+        let (numbers, vendor) = version.split_at(idx);
+
+        let mut numbers = numbers.split('.').map(str::parse::<u32>);
+
+        let mut next = || {
+            numbers.next().transpose().map_err(|e| {
+                LustreCollectorError::ConversionError(format!("Invalid version {version}: {e}"))
+            })
+        };
+
+        let major = next()?.ok_or_else(|| {
+            LustreCollectorError::ConversionError(format!("Invalid version {version}"))
+        })?;
+        let minor = next()?.unwrap_or_default();
+        let patch = next()?.unwrap_or_default();
+
+        let vendor = vendor.trim_start_matches(['_', '-']);
+
+        Ok(LustreVersion {
+            version: version.to_string(),
+            major,
+            minor,
+            patch,
+            vendor: (!vendor.is_empty()).then(|| vendor.to_string()),
+            lnet: None,
+        })
+    }
+}
+
+/// Setup state of an OBD device, as reported by `lctl dl`
+#[derive(PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub enum ObdDeviceStatus {
+    Up,
+    Stopping,
+    Inactive,
+    Attached,
+    Unknown,
+}
+
+impl From<&str> for ObdDeviceStatus {
+    fn from(x: &str) -> Self {
+        match x {
+            "UP" => Self::Up,
+            "ST" => Self::Stopping,
+            "IN" => Self::Inactive,
+            "AT" => Self::Attached,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// A single OBD device, as listed by `lctl dl -t`
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ObdDevice {
+    pub index: u64,
+    pub status: ObdDeviceStatus,
+    /// The device type, e.g. `osd-ldiskfs`, `obdfilter`, `osc`
+    pub kind: String,
+    pub name: String,
+    pub uuid: String,
+    pub refcount: u64,
+    /// The peer NID, only present for client devices
+    pub nid: Option<String>,
 }
 
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
@@ -588,6 +1310,77 @@ pub enum TargetStats {
     Changelog(TargetStat<ChangelogStat>),
     QuotaStats(TargetQuotaStat<QuotaStats>),
     QuotaStatsOsd(TargetStat<QuotaStatsOsd>),
+    /// Operation stats of a client connection
+    ClientStats(ClientTargetStat<Vec<Stat>>),
+    /// RPC histograms of a client connection
+    ClientRpcStats(ClientTargetStat<RpcStats>),
+    /// Dirty bytes cached by the client
+    CurDirtyBytes(ClientTargetStat<u64>),
+    /// Grant the client holds from the target
+    CurGrantBytes(ClientTargetStat<u64>),
+    MaxRpcsInFlight(ClientTargetStat<u64>),
+    /// Connection state and health of a client import
+    ClientImport(ClientTargetStat<ClientImport>),
+    /// Recent state changes of a client import
+    ClientImportState(ClientTargetStat<ImportStateHistory>),
+    /// Read-ahead hits, misses and their causes
+    LliteReadAheadStats(LliteTargetStat<Vec<Stat>>),
+    /// Read/write extent size histogram, `None` when collection is disabled
+    LliteExtentsStats(LliteTargetStat<Option<BrwStats>>),
+    /// Per-process read/write offsets, `None` when collection is disabled
+    LliteOffsetStats(LliteTargetStat<Option<Vec<LliteOffsetStat>>>),
+    LliteMaxCachedMb(LliteTargetStat<LliteMaxCachedMb>),
+    LliteMaxReadAheadMb(LliteTargetStat<u64>),
+    LfsckNamespace(TargetStat<LfsckStats>),
+    LfsckLayout(TargetStat<LfsckStats>),
+    HsmControl(TargetStat<HsmControl>),
+    /// Actions in the coordinator's llog, including completed ones
+    HsmActions(TargetStat<Vec<HsmActionCount>>),
+    /// Actions currently handed to copytools
+    HsmActiveRequests(TargetStat<Vec<HsmActionCount>>),
+    HsmAgents(TargetStat<Vec<HsmAgent>>),
+    /// OST pool as seen by an MDT
+    OstPool(TargetStat<OstPool>),
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub enum LNetNiStatus {
+    Up,
+    Down,
+    Unknown,
+}
+
+impl From<&str> for LNetNiStatus {
+    fn from(x: &str) -> Self {
+        match x.trim() {
+            "up" => Self::Up,
+            "down" => Self::Down,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// CPU partitions an NI is bound to.
+#[derive(PartialEq, Eq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct LNetNiCpt {
+    /// Partition of the network device, `None` when it has no affinity (`-1`)
+    pub dev_cpt: Option<u64>,
+    /// Partitions the NI's scheduler threads run on
+    pub cpts: Vec<u64>,
+}
+
+impl From<&lnet_exports::LocalNiS> for LNetNiCpt {
+    fn from(x: &lnet_exports::LocalNiS) -> Self {
+        Self {
+            dev_cpt: u64::try_from(x.dev_cpt).ok(),
+            cpts: x
+                .cpt
+                .trim_matches(|c| c == '[' || c == ']')
+                .split(',')
+                .filter_map(|x| x.trim().parse().ok())
+                .collect(),
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
@@ -598,6 +1391,58 @@ pub enum LNetStats {
     SendLength(LNetStatGlobal<i64>),
     RecvLength(LNetStatGlobal<i64>),
     DropLength(LNetStatGlobal<i64>),
+    MsgsAlloc(LNetStatGlobal<i64>),
+    MsgsMax(LNetStatGlobal<i64>),
+    RstAlloc(LNetStatGlobal<i64>),
+    Errors(LNetStatGlobal<i64>),
+    GlobalSendCount(LNetStatGlobal<i64>),
+    ResendCount(LNetStatGlobal<i64>),
+    ResponseTimeoutCount(LNetStatGlobal<i64>),
+    LocalInterruptCount(LNetStatGlobal<i64>),
+    LocalDroppedCount(LNetStatGlobal<i64>),
+    LocalAbortedCount(LNetStatGlobal<i64>),
+    LocalNoRouteCount(LNetStatGlobal<i64>),
+    LocalTimeoutCount(LNetStatGlobal<i64>),
+    LocalErrorCount(LNetStatGlobal<i64>),
+    RemoteDroppedCount(LNetStatGlobal<i64>),
+    RemoteErrorCount(LNetStatGlobal<i64>),
+    RemoteTimeoutCount(LNetStatGlobal<i64>),
+    NetworkTimeoutCount(LNetStatGlobal<i64>),
+    GlobalRecvCount(LNetStatGlobal<i64>),
+    RouteCount(LNetStatGlobal<i64>),
+    GlobalDropCount(LNetStatGlobal<i64>),
+    RouteLength(LNetStatGlobal<i64>),
+    /// Messages sent by the NI, per message type
+    SentStats(LNetStat<lnet_exports::Stats>),
+    /// Messages received by the NI, per message type
+    ReceivedStats(LNetStat<lnet_exports::Stats>),
+    /// Messages dropped by the NI, per message type
+    DroppedStats(LNetStat<lnet_exports::Stats>),
+    /// NI health value and error counters
+    Health(LNetStat<lnet_exports::HealthStats>),
+    /// Whether the NI is up or down
+    Status(LNetStat<LNetNiStatus>),
+    /// NI credit tunables
+    Tunables(LNetStat<lnet_exports::Tunables>),
+    /// CPU partitions of the NI
+    Cpt(LNetStat<LNetNiCpt>),
+    PeerMaxNiTxCredits(LNetPeerStat<i64>),
+    PeerAvailableTxCredits(LNetPeerStat<i64>),
+    PeerMinTxCredits(LNetPeerStat<i64>),
+    PeerTxQNumOfBuf(LNetPeerStat<i64>),
+    PeerAvailableRtrCredits(LNetPeerStat<i64>),
+    PeerMinRtrCredits(LNetPeerStat<i64>),
+    /// Whether the peer NI is up or down
+    PeerState(LNetPeerStat<LNetNiStatus>),
+    /// Peer NI health value and error counters
+    PeerHealth(LNetPeerStat<lnet_exports::HealthStatsPeer>),
+    /// Whether the route is up or down
+    RouteState(LNetRouteStat<LNetNiStatus>),
+    RoutePriority(LNetRouteStat<i64>),
+    /// Whether this node routes
+    RoutingEnabled(LNetStatGlobal<bool>),
+    /// Router buffer pool sizes and credits
+    RouterBuffers(LNetRouterBufferStat<lnet_exports::RouterBuffers>),
 }
 
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
@@ -606,6 +1451,17 @@ pub enum LustreServiceStats {
     LdlmCbd(Vec<Stat>),
 }
 
+/// A param block that no parser matched and was skipped by
+/// [`crate::parser::parse_lenient`]
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Diagnostic {
+    /// The skipped param, `None` if the block didn't start with a `param=` line
+    pub param: Option<Param>,
+    /// Line of the input the block starts on, starting at 1
+    pub line: usize,
+    pub text: String,
+}
+
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
 pub enum Record {
     Host(HostStats),
@@ -613,6 +1469,7 @@ pub enum Record {
     LustreService(LustreServiceStats),
     Node(NodeStats),
     Target(TargetStats),
+    Diagnostic(Diagnostic),
 }
 
 #[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
@@ -691,4 +1548,85 @@ mod tests {
             Err(e) => panic!("Error occurred: {:?}", e),
         }
     }
+
+    #[test]
+    fn test_snapshot_time_omitted_when_absent() {
+        let mut x = TargetStat {
+            kind: TargetVariant::Ost,
+            param: Param("brw_stats".to_string()),
+            target: Target("fs-OST0000".to_string()),
+            value: 1,
+            snapshot_time: None,
+        };
+
+        let json = serde_json::to_string(&x).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"kind":"Ost","param":"brw_stats","target":"fs-OST0000","value":1}"#
+        );
+        assert_eq!(serde_json::from_str::<TargetStat<u64>>(&json).unwrap(), x);
+        assert_eq!(
+            format!("{x:?}"),
+            r#"TargetStat { kind: Ost, param: Param("brw_stats"), target: Target("fs-OST0000"), value: 1 }"#
+        );
+
+        x.snapshot_time = Some(UnsignedLustreTimestamp(1_700_000_000_000));
+
+        assert!(serde_json::to_string(&x)
+            .unwrap()
+            .contains(r#""snapshot_time":"#));
+    }
+
+    fn op(samples: i64, unit: &str, sum: Option<i64>) -> JobStatOp {
+        JobStatOp {
+            samples,
+            unit: unit.to_string(),
+            min: sum.map(|_| 0),
+            max: sum,
+            sum,
+            sumsq: None,
+        }
+    }
+
+    #[test]
+    fn test_job_stat_conversions() {
+        let mut x = JobStat {
+            job_id: "dd.0".to_string(),
+            snapshot_time: UnsignedLustreTimestamp(1_700_000_000),
+            start_time: None,
+            elapsed_time: None,
+            ops: BTreeMap::from([
+                ("read_bytes".to_string(), op(1, "bytes", Some(4096))),
+                ("write_bytes".to_string(), op(0, "bytes", Some(0))),
+            ]),
+        };
+
+        for name in [
+            "getattr", "setattr", "punch", "sync", "destroy", "create", "statfs", "get_info",
+            "set_info", "quotactl",
+        ] {
+            x.ops.insert(name.to_string(), op(2, "reqs", None));
+        }
+
+        let ost = JobStatOst::try_from(x.clone()).unwrap();
+
+        assert_eq!(ost.read_bytes.sum, 4096);
+        assert_eq!(ost.quotactl.samples, 2);
+        assert_eq!(JobStat::from(&ost), x);
+
+        // Newer ops are kept in the map but dropped by the fixed struct
+        x.ops
+            .insert("fallocate".to_string(), op(3, "usecs", Some(9)));
+
+        assert_eq!(JobStatOst::try_from(x.clone()).unwrap(), ost);
+
+        x.ops.remove("punch");
+
+        assert!(matches!(
+            JobStatOst::try_from(x.clone()),
+            Err(LustreCollectorError::ConversionError(_))
+        ));
+        assert!(JobStatMdt::try_from(x).is_err());
+    }
 }